@@ -0,0 +1,59 @@
+/* C API of szyk, build the library with
+ * `cargo rustc --release --features ffi --crate-type cdylib` */
+
+#ifndef SZYK_H
+#define SZYK_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct SzykGraph SzykGraph;
+
+typedef enum SzykStatus {
+    SZYK_OK = 0,
+    /* a pointer argument was null */
+    SZYK_NULL_POINTER = 1,
+    /* the ids hold the id that wasn't found */
+    SZYK_TARGET_NOT_FOUND = 2,
+    /* the ids hold the cycle in dependency order, starting and ending on the same id */
+    SZYK_CYCLIC_DEPENDENCY = 3,
+    /* the ids hold the node depending on itself */
+    SZYK_SELF_DEPENDENCY = 4,
+    /* sorting failed for any other reason, the ids are empty */
+    SZYK_SORT_FAILED = 5,
+} SzykStatus;
+
+/* array of ids owned by the library, released with szyk_ids_free */
+typedef struct SzykIds {
+    uint64_t *ids;
+    size_t len;
+} SzykIds;
+
+/* creates an empty graph, released with szyk_graph_free */
+SzykGraph *szyk_graph_new(void);
+
+/* releases a graph, does nothing for NULL */
+void szyk_graph_free(SzykGraph *graph);
+
+/* adds a node without dependencies, does nothing if it's already in the graph */
+SzykStatus szyk_graph_add_node(SzykGraph *graph, uint64_t id);
+
+/* makes `from` depend on `to`, `to` doesn't have to be in the graph yet */
+SzykStatus szyk_graph_add_edge(SzykGraph *graph, uint64_t from, uint64_t to);
+
+/* fills `out` with ids in topological order, ending on `target`; on failure `out` holds the ids
+ * described by the status, `out` has to be released unless the status is SZYK_NULL_POINTER */
+SzykStatus szyk_graph_sort(const SzykGraph *graph, uint64_t target, SzykIds *out);
+
+/* releases ids filled by szyk_graph_sort */
+void szyk_ids_free(SzykIds ids);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
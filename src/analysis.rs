@@ -0,0 +1,571 @@
+use crate::traversal::Traversal;
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::hash::Hash;
+use core::num::NonZeroUsize;
+use core::ops::Add;
+
+/// returns ids of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// each paired with its level: the longest distance to a node without dependencies
+///
+/// nodes on the same level don't depend on each other, so a plan can run each level in parallel
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = levels(
+///         &[
+///             Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///             Node::new("planks", vec!["wood"], "Planks"),
+///             Node::new("sticks", vec!["planks"], "Sticks"),
+///             Node::new("wood", vec![], "Wood"),
+///         ],
+///         "wooden pickaxe",
+///     );
+///     assert_eq!(
+///         result,
+///         Ok(vec![("wood", 0), ("planks", 1), ("sticks", 2), ("wooden pickaxe", 3)])
+///     );
+/// ```
+pub fn levels<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<(Id, usize)>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut order = Vec::new();
+    traversal.visit(index, &mut |index| order.push(index))?;
+
+    // dependencies come first in the order, so their levels are already known
+    let mut level = vec![0; domain.len()];
+    for &index in order.iter() {
+        for dep in traversal.dep_indices(index) {
+            let dep = dep?;
+            level[index] = level[index].max(level[dep] + 1);
+        }
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|index| (domain[index].id.clone(), level[index]))
+        .collect())
+}
+
+/// distance of a node from the nodes without dependencies, see [`depths`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Depth {
+    /// fewest edges on a path to a node without dependencies
+    pub nearest: usize,
+    /// most edges on a path to a node without dependencies, the same as its level in [`levels`]
+    pub farthest: usize,
+}
+
+/// returns ids of every node from `domain` in topological order, each paired with its [`Depth`]
+///
+/// nodes without dependencies have both distances equal to 0, fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = depths(&[
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ])
+///     .unwrap();
+///     assert_eq!(result[3], ("wooden pickaxe", Depth { nearest: 2, farthest: 3 }));
+/// ```
+pub fn depths<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Vec<(Id, Depth)>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let mut order = Vec::with_capacity(domain.len());
+    for index in 0..domain.len() {
+        traversal.visit(index, &mut |index| order.push(index))?;
+    }
+
+    // dependencies come first in the order, so their depths are already known
+    let mut depth = vec![Depth::default(); domain.len()];
+    for &index in order.iter() {
+        let mut nearest = None;
+        let mut farthest = 0;
+        for dep in traversal.dep_indices(index) {
+            let dep = depth[dep?];
+            nearest = Some(nearest.map_or(dep.nearest, |nearest: usize| nearest.min(dep.nearest)));
+            farthest = farthest.max(dep.farthest + 1);
+        }
+        depth[index] = Depth {
+            nearest: nearest.map_or(0, |nearest| nearest + 1),
+            farthest,
+        };
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|index| (domain[index].id.clone(), depth[index]))
+        .collect())
+}
+
+/// returns the heaviest chain of dependencies ending on the node with id of `target`, starting on a node
+/// without dependencies, together with the total `weight` of its nodes
+///
+/// on ties the dependency listed first wins, fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], 1),
+///         Node::new("planks", vec!["wood"], 2),
+///         Node::new("sticks", vec!["wood"], 5),
+///         Node::new("wood", vec![], 10),
+///     ];
+///     let result = critical_path(&domain, "wooden pickaxe", |node| node.value);
+///     assert_eq!(result, Ok((vec!["wood", "sticks", "wooden pickaxe"], 16)));
+/// ```
+pub fn critical_path<Id, Item, W, F>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    mut weight: F,
+) -> Result<(Vec<Id>, W), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    W: Copy + PartialOrd + Add<Output = W>,
+    F: FnMut(&Node<Id, Item>) -> W,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut order = Vec::new();
+    traversal.visit(index, &mut |index| order.push(index))?;
+
+    // heaviest chain ending on every node, as its total weight and the previous node on it
+    let mut cost: Vec<Option<W>> = vec![None; domain.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; domain.len()];
+    for &index in order.iter() {
+        let mut heaviest: Option<(W, usize)> = None;
+        for dep in traversal.dep_indices(index) {
+            let dep = dep?;
+            if let Some(dep_cost) = cost[dep] {
+                if !matches!(heaviest, Some((heaviest, _)) if heaviest >= dep_cost) {
+                    heaviest = Some((dep_cost, dep));
+                }
+            }
+        }
+
+        let own = weight(&domain[index]);
+        cost[index] = Some(match heaviest {
+            Some((dep_cost, _)) => dep_cost + own,
+            None => own,
+        });
+        previous[index] = heaviest.map(|(_, dep)| dep);
+    }
+
+    let mut path = vec![domain[index].id.clone()];
+    let mut current = index;
+    while let Some(dep) = previous[current] {
+        path.push(domain[dep].id.clone());
+        current = dep;
+    }
+    path.reverse();
+
+    let total = cost[index].expect("the target is always visited");
+    Ok((path, total))
+}
+
+/// summary of the shape of a domain, see [`stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub nodes: usize,
+    /// dependencies present in the domain, counted once per listing
+    pub edges: usize,
+    /// most edges on a chain of dependencies, the highest level in [`levels`]
+    pub max_depth: usize,
+    /// most nodes on one level
+    pub max_width: usize,
+    /// nodes without dependencies in the domain
+    pub roots: usize,
+    /// nodes no other node depends on, like [`crate::sinks`]
+    pub sinks: usize,
+    /// groups of nodes connected by dependencies in either direction
+    pub components: usize,
+}
+
+/// returns [`Stats`] of `domain`, fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["wood"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///         Node::new("stone", vec![], "Stone"),
+///     ];
+///     assert_eq!(
+///         stats(&domain),
+///         Ok(Stats {
+///             nodes: 5,
+///             edges: 4,
+///             max_depth: 2,
+///             max_width: 2,
+///             roots: 2,
+///             sinks: 2,
+///             components: 2,
+///         })
+///     );
+/// ```
+pub fn stats<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Stats, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let graph = CompactGraph::new(domain)?;
+    let order = graph.sort_all()?;
+
+    // dependencies come first in the order, so their levels are already known
+    let mut level = vec![0; domain.len()];
+    let mut depended_on = vec![false; domain.len()];
+    // union-find forest of connected nodes
+    let mut parent: Vec<usize> = (0..domain.len()).collect();
+    for &index in order.iter() {
+        let index = index as usize;
+        for &dep in graph.deps(index as u32) {
+            let dep = dep as usize;
+            level[index] = level[index].max(level[dep] + 1);
+            depended_on[dep] = true;
+            let (root, dep_root) = (find(&mut parent, index), find(&mut parent, dep));
+            parent[root] = dep_root;
+        }
+    }
+
+    let max_depth = level.iter().copied().max().unwrap_or(0);
+    let mut width = vec![0; max_depth + 1];
+    for &level in level.iter() {
+        width[level] += 1;
+    }
+
+    Ok(Stats {
+        nodes: domain.len(),
+        edges: graph.edge_count(),
+        max_depth,
+        max_width: width.iter().copied().max().unwrap_or(0),
+        roots: (0..domain.len() as u32)
+            .filter(|&index| graph.deps(index).is_empty())
+            .count(),
+        sinks: depended_on
+            .iter()
+            .filter(|&&depended_on| !depended_on)
+            .count(),
+        components: (0..domain.len())
+            .filter(|&index| find(&mut parent, index) == index)
+            .count(),
+    })
+}
+
+/// root of the tree of `index` in the union-find forest `parent`, halving paths on the way
+fn find(parent: &mut [usize], mut index: usize) -> usize {
+    while parent[index] != index {
+        parent[index] = parent[parent[index]];
+        index = parent[index];
+    }
+    index
+}
+
+/// estimated run of a domain on parallel workers, see [`makespan`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule<Id, W> {
+    /// time the last node finishes at
+    pub total: W,
+    /// ids of nodes paired with the time they start at, in the order they start
+    pub starts: Vec<(Id, W)>,
+}
+
+/// estimates how long running every node from `domain` takes on `workers` parallel workers
+///
+/// simulates list scheduling: whenever a worker is free it starts the ready node that comes first in
+/// `domain`, running it for its `duration`; fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::num::NonZeroUsize;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], 1),
+///         Node::new("planks", vec!["wood"], 2),
+///         Node::new("sticks", vec!["wood"], 5),
+///         Node::new("wood", vec![], 10),
+///     ];
+///     let workers = NonZeroUsize::new(2).unwrap();
+///     let schedule = makespan(&domain, workers, |node| node.value).unwrap();
+///     assert_eq!(schedule.total, 16);
+///     assert_eq!(
+///         schedule.starts,
+///         vec![("wood", 0), ("planks", 10), ("sticks", 10), ("wooden pickaxe", 15)]
+///     );
+///     assert_eq!(makespan(&domain, NonZeroUsize::MIN, |node| node.value).unwrap().total, 18);
+/// ```
+pub fn makespan<Id, Item, W, F>(
+    domain: &[Node<Id, Item>],
+    workers: NonZeroUsize,
+    mut duration: F,
+) -> Result<Schedule<Id, W>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    W: Copy + Default + PartialOrd + Add<Output = W>,
+    F: FnMut(&Node<Id, Item>) -> W,
+{
+    // there can't be more busy workers than nodes
+    let workers = workers.get().min(domain.len());
+
+    let graph = CompactGraph::new(domain)?;
+    graph.sort_all()?;
+
+    let mut pending = vec![0; domain.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); domain.len()];
+    for (position, pending) in pending.iter_mut().enumerate() {
+        for dep in graph.dep_positions(position) {
+            *pending += 1;
+            dependents[dep].push(position);
+        }
+    }
+    let durations: Vec<W> = domain.iter().map(&mut duration).collect();
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..domain.len())
+        .filter(|&position| pending[position] == 0)
+        .map(Reverse)
+        .collect();
+    // nodes being run with the time they finish at
+    let mut running: Vec<(W, usize)> = Vec::with_capacity(workers);
+    let mut starts = Vec::with_capacity(domain.len());
+    let mut time = W::default();
+    loop {
+        while running.len() < workers {
+            let position = match ready.pop() {
+                Some(Reverse(position)) => position,
+                None => break,
+            };
+            starts.push((domain[position].id.clone(), time));
+            running.push((time + durations[position], position));
+        }
+
+        let next =
+            running
+                .iter()
+                .map(|&(finish, _)| finish)
+                .fold(None, |next, finish| match next {
+                    Some(next) if next <= finish => Some(next),
+                    _ => Some(finish),
+                });
+        time = match next {
+            Some(next) => next,
+            None => break,
+        };
+
+        let mut nth = 0;
+        while nth < running.len() {
+            let (finish, position) = running[nth];
+            if finish > time {
+                nth += 1;
+                continue;
+            }
+            running.swap_remove(nth);
+            for &dependent in dependents[position].iter() {
+                pending[dependent] -= 1;
+                if pending[dependent] == 0 {
+                    ready.push(Reverse(dependent));
+                }
+            }
+        }
+    }
+
+    Ok(Schedule {
+        total: time,
+        starts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_works() {
+        assert_eq!(stats::<i32, ()>(&[]), Ok(Stats::default()));
+
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![4], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![6], "mouse").with_optional_deps(vec![7]),
+            Node::new(6, vec![], "cheese"),
+            Node::new(8, vec![], "bird"),
+        ];
+        assert_eq!(
+            stats(&domain),
+            Ok(Stats {
+                nodes: 7,
+                edges: 5,
+                max_depth: 2,
+                max_width: 3,
+                roots: 3,
+                sinks: 3,
+                components: 3,
+            })
+        );
+
+        let domain = [Node::new(1, vec![2], ()), Node::new(2, vec![1], ())];
+        assert_eq!(
+            stats(&domain),
+            Err(TopsortError::CyclicDependency(vec![1, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn levels_works() {
+        let result = levels(
+            &[
+                Node::new(1, vec![2, 3, 4], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+                Node::new(4, vec![], "dog"),
+            ],
+            1,
+        );
+        assert_eq!(result, Ok(vec![(2, 0), (3, 1), (4, 0), (1, 2)]));
+    }
+
+    #[test]
+    fn levels_errors() {
+        assert_eq!(
+            levels(&[Node::new(1, vec![2], "hello")], 1),
+            Err(TopsortError::TargetNotFound(2))
+        );
+        assert_eq!(
+            levels(&[Node::new(1, vec![1], "hello")], 1),
+            Err(TopsortError::SelfDependency(1))
+        );
+    }
+
+    #[test]
+    fn depths_works() {
+        let result = depths(&[
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![2], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ]);
+        let depth = |nearest, farthest| Depth { nearest, farthest };
+        assert_eq!(
+            result,
+            Ok(vec![
+                (2, depth(0, 0)),
+                (4, depth(1, 1)),
+                (3, depth(2, 2)),
+                (1, depth(1, 3)),
+                (5, depth(0, 0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn critical_path_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], 1.0),
+            Node::new(2, vec![4], 2.0),
+            Node::new(3, vec![4], 2.0),
+            Node::new(4, vec![], 0.5),
+            Node::new(5, vec![], 100.0),
+        ];
+        let result = critical_path(&domain, 1, |node| node.value);
+        assert_eq!(result, Ok((vec![4, 2, 1], 3.5)));
+
+        let result = critical_path(&domain, 4, |node| node.value);
+        assert_eq!(result, Ok((vec![4], 0.5)));
+    }
+
+    #[test]
+    fn critical_path_errors() {
+        let domain = [Node::new(1, vec![2], 1), Node::new(2, vec![1], 1)];
+        assert_eq!(
+            critical_path(&domain, 3, |node| node.value),
+            Err(TopsortError::TargetNotFound(3))
+        );
+        assert_eq!(
+            critical_path(&domain, 1, |node| node.value),
+            Err(TopsortError::CyclicDependency(vec![1, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn makespan_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], 1.5),
+            Node::new(2, vec![], 2.0),
+            Node::new(3, vec![], 3.0),
+            Node::new(4, vec![], 4.0),
+        ];
+        assert_eq!(
+            makespan(&domain, NonZeroUsize::new(2).unwrap(), |node| node.value),
+            Ok(Schedule {
+                total: 6.0,
+                starts: vec![(2, 0.0), (3, 0.0), (4, 2.0), (1, 3.0)]
+            })
+        );
+        assert_eq!(
+            makespan(&domain, NonZeroUsize::new(8).unwrap(), |node| node.value)
+                .map(|schedule| schedule.total),
+            Ok(4.5)
+        );
+        assert_eq!(
+            makespan(&domain, NonZeroUsize::new(1).unwrap(), |node| node.value)
+                .map(|schedule| schedule.total),
+            Ok(10.5)
+        );
+        assert_eq!(
+            makespan(&domain, NonZeroUsize::MAX, |node| node.value).map(|schedule| schedule.total),
+            Ok(4.5)
+        );
+        assert_eq!(
+            makespan::<i32, (), u32, _>(&[], NonZeroUsize::new(1).unwrap(), |_| 1),
+            Ok(Schedule {
+                total: 0,
+                starts: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn makespan_errors() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![1], "world"),
+        ];
+        assert!(matches!(
+            makespan(&domain, NonZeroUsize::new(2).unwrap(), |_| 1),
+            Err(TopsortError::CyclicDependency(_))
+        ));
+        assert_eq!(
+            makespan(
+                &[Node::new(1, vec![2], "hello")],
+                NonZeroUsize::new(2).unwrap(),
+                |_| 1
+            ),
+            Err(TopsortError::TargetNotFound(2))
+        );
+    }
+}
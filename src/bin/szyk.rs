@@ -0,0 +1,244 @@
+use std::io::Read;
+use std::process::ExitCode;
+use szyk::dot::{self, DotOptions};
+use szyk::{Graph, Node};
+
+const USAGE: &str = "\
+usage: szyk [--format edges|dot] [--target ID] [--cycles | --render] [FILE]
+
+reads a graph from FILE or stdin and prints ids in topological order, one per line
+
+input formats (detected from the input unless --format is given):
+  edges  one `node dependency` pair per line, a lone id adds a node, `#` starts a comment
+  dot    a `digraph`, an edge `a -> b` makes `a` depend on `b`
+
+options:
+  --target ID  only sort ID and its dependencies
+  --cycles     print nodes of every cycle on a line instead of sorting
+  --render     print the graph as DOT, highlighting the target and cycles";
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Edges,
+    Dot,
+}
+
+#[derive(Debug, PartialEq)]
+enum Output {
+    Order,
+    Cycles,
+    Render,
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    format: Option<Format>,
+    target: Option<String>,
+    output: Output,
+    file: Option<String>,
+}
+
+fn parse_args<I>(args: I) -> Result<Args, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = Args {
+        format: None,
+        target: None,
+        output: Output::Order,
+        file: None,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                parsed.format = match args.next().as_deref() {
+                    Some("edges") => Some(Format::Edges),
+                    Some("dot") => Some(Format::Dot),
+                    Some(other) => return Err(format!("unknown format `{}`", other)),
+                    None => return Err(String::from("--format needs a value")),
+                }
+            }
+            "--target" => match args.next() {
+                Some(target) => parsed.target = Some(target),
+                None => return Err(String::from("--target needs a value")),
+            },
+            "--cycles" => parsed.output = Output::Cycles,
+            "--render" => parsed.output = Output::Render,
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option `{}`", arg))
+            }
+            _ if parsed.file.is_some() => return Err(String::from("more than one input file")),
+            _ => parsed.file = Some(arg),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// DOT input starts with a keyword or a comment, anything else is read as an edge list
+fn detect_format(input: &str) -> Format {
+    let input = input.trim_start();
+    let dot = ["digraph", "strict", "graph", "//", "/*"]
+        .iter()
+        .any(|prefix| input.starts_with(prefix));
+    if dot {
+        Format::Dot
+    } else {
+        Format::Edges
+    }
+}
+
+fn parse_edges(input: &str) -> Result<Graph<String, ()>, String> {
+    let mut graph = Graph::new();
+    for (line, text) in input.lines().enumerate() {
+        let text = text.split('#').next().unwrap_or("");
+        let ids: Vec<&str> = text.split_whitespace().collect();
+        match ids.as_slice() {
+            [] => {}
+            [id] => {
+                graph.add_node(id.to_string(), ());
+            }
+            [from, to] => {
+                for id in [from, to] {
+                    if !graph.contains(&id.to_string()) {
+                        graph.add_node(id.to_string(), ());
+                    }
+                }
+                graph
+                    .add_edge(from.to_string(), to.to_string())
+                    .map_err(|err| format!("line {}: {}", line + 1, err))?;
+            }
+            _ => {
+                return Err(format!(
+                    "line {}: expected `node dependency`, found {} ids",
+                    line + 1,
+                    ids.len()
+                ))
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let mut input = String::new();
+    match &args.file {
+        Some(file) if file != "-" => {
+            input = std::fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?
+        }
+        _ => {
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    let format = args.format.unwrap_or_else(|| detect_format(&input));
+    let graph = match format {
+        Format::Edges => parse_edges(&input)?,
+        Format::Dot => dot::parse(&input).map_err(|err| err.to_string())?,
+    };
+    let nodes = graph.nodes();
+
+    let mut out = String::new();
+    match args.output {
+        Output::Order => {
+            let mut push = |node: &Node<String, ()>| {
+                out.push_str(&node.id);
+                out.push('\n');
+            };
+            match args.target {
+                Some(target) => graph.sort_cb(target, &mut push),
+                None => szyk::sort_all_cb(nodes, &mut push),
+            }
+            .map_err(|err| err.to_string())?;
+        }
+        Output::Cycles => {
+            for component in szyk::scc(nodes).map_err(|err| err.to_string())? {
+                let cyclic = component.len() > 1
+                    || graph
+                        .get(&component[0])
+                        .is_some_and(|node| node.deps.contains(&node.id));
+                if cyclic {
+                    out.push_str(&component.join(" "));
+                    out.push('\n');
+                }
+            }
+        }
+        Output::Render => {
+            let options = DotOptions {
+                target: args.target,
+                cycles: true,
+            };
+            out = dot::to_dot_with(nodes, &options);
+        }
+    }
+
+    Ok(out)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    match parse_args(args).and_then(run) {
+        Ok(out) => {
+            print!("{}", out);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("szyk: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_args() {
+        assert_eq!(
+            args(&["--target", "a", "--cycles", "graph.dot"]),
+            Ok(Args {
+                format: None,
+                target: Some(String::from("a")),
+                output: Output::Cycles,
+                file: Some(String::from("graph.dot")),
+            })
+        );
+        assert!(args(&["--format", "xml"]).is_err());
+        assert!(args(&["--target"]).is_err());
+        assert!(args(&["a", "b"]).is_err());
+    }
+
+    #[test]
+    fn parses_edges() {
+        let graph = parse_edges("# recipe\nplanks wood\nsticks planks\nstone\n").unwrap();
+        assert_eq!(
+            graph
+                .sort(String::from("sticks"))
+                .map(|values| values.len()),
+            Ok(3)
+        );
+        assert!(graph.contains(&String::from("stone")));
+        assert!(parse_edges("a b c").is_err());
+    }
+
+    #[test]
+    fn detects_format() {
+        assert_eq!(detect_format("  digraph { a -> b }"), Format::Dot);
+        assert_eq!(detect_format("// deps\ndigraph {}"), Format::Dot);
+        assert_eq!(detect_format("a b\n"), Format::Edges);
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// fixed-size set of positions packed into words, using one bit per position
+///
+/// used by traversals to mark nodes by their position in the domain, see [`crate::Sorter::visited`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut set = BitSet::new(100);
+///     set.insert(3);
+///     set.insert(70);
+///     assert!(set.contains(70) && !set.contains(4));
+///     assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 70]);
+///     assert_eq!(set.count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// empty set of positions below `len`
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// number of positions the set can hold
+    pub fn capacity(&self) -> usize {
+        self.len
+    }
+
+    /// panics if `position` is out of bounds
+    pub fn contains(&self, position: usize) -> bool {
+        self.words[position / 64] & (1 << (position % 64)) != 0
+    }
+
+    /// panics if `position` is out of bounds
+    pub fn insert(&mut self, position: usize) {
+        self.words[position / 64] |= 1 << (position % 64);
+    }
+
+    /// panics if `position` is out of bounds
+    pub fn remove(&mut self, position: usize) {
+        self.words[position / 64] &= !(1 << (position % 64));
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// number of positions in the set
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// positions in the set in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            let mut word = word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(index * 64 + bit)
+            })
+        })
+    }
+
+    /// adds all positions from `other`, which has to have the same size
+    pub fn union_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_union() {
+        let mut a = BitSet::new(130);
+        let mut b = BitSet::new(130);
+        a.insert(0);
+        a.insert(64);
+        b.insert(129);
+        a.union_with(&b);
+        assert!(a.contains(0) && a.contains(64) && a.contains(129));
+        assert!(!a.contains(1) && !a.contains(128));
+    }
+
+    #[test]
+    fn remove_and_iter() {
+        let mut set = BitSet::new(200);
+        for position in [199, 0, 63, 64, 65] {
+            set.insert(position);
+        }
+        set.remove(64);
+        set.remove(1);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 63, 65, 199]);
+        assert_eq!(set.count(), 4);
+        assert_eq!(set.capacity(), 200);
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.iter().next(), None);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{Graph, TopsortError};
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::hash::Hash;
+
+/// builds a [`Graph`] whose order doesn't depend on the order nodes and edges were added in
+///
+/// nodes of the built graph are sorted by id and dependencies of each node are sorted too, so a graph
+/// assembled by iterating a `HashMap` sorts the same way on every run
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::collections::HashMap;
+///
+///     let mut recipes = HashMap::new();
+///     recipes.insert("wooden pickaxe", vec!["sticks", "planks"]);
+///     recipes.insert("sticks", vec!["planks"]);
+///     recipes.insert("planks", vec!["wood"]);
+///     recipes.insert("wood", vec![]);
+///
+///     let mut builder = GraphBuilder::new();
+///     for (id, deps) in recipes.iter() {
+///         builder.node(*id, *id).deps(*id, deps.iter().copied());
+///     }
+///     let graph = builder.build().unwrap();
+///     let ids: Vec<_> = graph.nodes().iter().map(|node| node.id).collect();
+///     assert_eq!(ids, vec!["planks", "sticks", "wood", "wooden pickaxe"]);
+///     assert_eq!(
+///         graph.sort("wooden pickaxe"),
+///         Ok(vec!["wood", "planks", "sticks", "wooden pickaxe"])
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<Id, Item>
+where
+    Id: Clone + Ord + Hash,
+{
+    values: BTreeMap<Id, Item>,
+    deps: BTreeMap<Id, BTreeSet<Id>>,
+}
+
+impl<Id, Item> Default for GraphBuilder<Id, Item>
+where
+    Id: Clone + Ord + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id, Item> GraphBuilder<Id, Item>
+where
+    Id: Clone + Ord + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            values: BTreeMap::new(),
+            deps: BTreeMap::new(),
+        }
+    }
+
+    /// adds a node with id of `id`, replacing the value of a node added before with the same id
+    pub fn node(&mut self, id: Id, value: Item) -> &mut Self {
+        self.values.insert(id, value);
+        self
+    }
+
+    /// makes `from` depend on `to`, `from` has to be added with [`GraphBuilder::node`] before
+    /// [`GraphBuilder::build`], `to` doesn't
+    pub fn edge(&mut self, from: Id, to: Id) -> &mut Self {
+        self.deps.entry(from).or_default().insert(to);
+        self
+    }
+
+    /// makes `id` depend on every id of `deps`, see [`GraphBuilder::edge`]
+    pub fn deps<I>(&mut self, id: Id, deps: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Id>,
+    {
+        self.deps.entry(id).or_default().extend(deps);
+        self
+    }
+
+    /// returns the graph with nodes sorted by id and their dependencies sorted, fails with
+    /// [`TopsortError::TargetNotFound`] if an edge starts at a node that wasn't added
+    pub fn build(self) -> Result<Graph<Id, Item>, TopsortError<Id>> {
+        let mut graph = Graph::new();
+        for (id, value) in self.values {
+            graph.add_node(id, value);
+        }
+        for (from, deps) in self.deps {
+            for to in deps {
+                graph.add_edge(from.clone(), to)?;
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn build_is_deterministic() {
+        let mut forward = GraphBuilder::new();
+        forward
+            .node(1, "hello")
+            .node(2, "world")
+            .node(3, "cat")
+            .edge(1, 3)
+            .edge(1, 2)
+            .edge(3, 2);
+        let mut backward = GraphBuilder::new();
+        backward
+            .edge(3, 2)
+            .deps(1, vec![2, 3])
+            .node(3, "cat")
+            .node(2, "world")
+            .node(1, "hello");
+
+        let forward = forward.build().unwrap();
+        let backward = backward.build().unwrap();
+        assert_eq!(forward.nodes(), backward.nodes());
+        assert_eq!(forward.sort(1), Ok(vec!["world", "cat", "hello"]));
+        assert_eq!(
+            forward
+                .nodes()
+                .iter()
+                .map(|node| node.id)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn build_fails_on_edge_from_missing_node() {
+        let mut builder = GraphBuilder::new();
+        builder.node(1, ()).edge(1, 2).edge(4, 1);
+        assert_eq!(
+            builder.build().map(|graph| graph.len()),
+            Err(TopsortError::TargetNotFound(4))
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::bitset::BitSet;
+use crate::collections::HashMap;
+use crate::scc::components;
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+/// precomputed transitive dependencies of every node, answering [`depends_on`](Self::depends_on) in constant time
+///
+/// takes O(V²) bits of memory, cycles are allowed
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let closure = TransitiveClosure::new(&[
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ])
+///     .unwrap();
+///     assert!(closure.depends_on(&"wooden pickaxe", &"wood"));
+///     assert!(!closure.depends_on(&"wood", &"sticks"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransitiveClosure<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    index: HashMap<Id, usize>,
+    component_of: Vec<usize>,
+    /// positions reachable from members of every strongly connected component
+    reachable: Vec<BitSet>,
+}
+
+impl<Id> TransitiveClosure<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// fails if any dependency is missing from `domain`
+    pub fn new<Item>(domain: &[Node<Id, Item>]) -> Result<Self, TopsortError<Id>> {
+        let graph = CompactGraph::new(domain)?;
+        let components = components(&graph);
+
+        let mut component_of = vec![0; domain.len()];
+        for (component, members) in components.iter().enumerate() {
+            for &position in members.iter() {
+                component_of[position] = component;
+            }
+        }
+
+        // components come in topological order, so the ones depended on are already done
+        let mut reachable: Vec<BitSet> = Vec::with_capacity(components.len());
+        for (component, members) in components.iter().enumerate() {
+            let mut reach = BitSet::new(domain.len());
+            let mut cyclic = false;
+            for &position in members.iter() {
+                for dep in graph.dep_positions(position) {
+                    reach.insert(dep);
+                    if component_of[dep] == component {
+                        cyclic = true;
+                    } else {
+                        reach.union_with(&reachable[component_of[dep]]);
+                    }
+                }
+            }
+
+            // members of a cycle reach each other and themselves
+            if cyclic {
+                for &position in members.iter() {
+                    reach.insert(position);
+                }
+            }
+            reachable.push(reach);
+        }
+
+        let mut index = HashMap::with_capacity(domain.len());
+        for (position, node) in domain.iter().enumerate() {
+            index.entry(node.id.clone()).or_insert(position);
+        }
+
+        Ok(Self {
+            index,
+            component_of,
+            reachable,
+        })
+    }
+
+    /// whether the node with id of `a` transitively depends on the node with id of `b`
+    ///
+    /// a node only depends on itself if it's part of a cycle, unknown ids depend on nothing
+    pub fn depends_on<Q>(&self, a: &Q, b: &Q) -> bool
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(&a), Some(&b)) => self.reachable[self.component_of[a]].contains(b),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depends_on_works() {
+        let closure = TransitiveClosure::new(&[
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![], "dog"),
+        ])
+        .unwrap();
+        assert!(closure.depends_on(&1, &4));
+        assert!(closure.depends_on(&3, &4));
+        assert!(!closure.depends_on(&2, &4));
+        assert!(!closure.depends_on(&1, &1));
+        assert!(!closure.depends_on(&4, &1));
+        assert!(!closure.depends_on(&1, &5));
+    }
+
+    #[test]
+    fn depends_on_with_cycles() {
+        let closure = TransitiveClosure::new(&[
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2, 4], "cat"),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![5], "mouse"),
+        ])
+        .unwrap();
+        assert!(closure.depends_on(&2, &2));
+        assert!(closure.depends_on(&3, &2));
+        assert!(closure.depends_on(&2, &4));
+        assert!(closure.depends_on(&1, &3));
+        assert!(!closure.depends_on(&1, &1));
+        assert!(!closure.depends_on(&4, &4));
+        assert!(closure.depends_on(&5, &5));
+    }
+
+    #[test]
+    fn missing_dependency() {
+        let result = TransitiveClosure::new(&[Node::new(1, vec![2], "hello")]);
+        assert_eq!(result.err(), Some(TopsortError::TargetNotFound(2)));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::bitset::BitSet;
+use crate::collections::HashMap;
+use crate::{HasDependencies, TopsortError};
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::hash::Hash;
+
+/// dependency graph with ids mapped to `u32` indices and dependencies stored in one contiguous array
+///
+/// dependencies of the node at index `i` are `targets[offsets[i]..offsets[i + 1]]`, in the order they
+/// are listed, so algorithms walk plain integer slices instead of hashing ids; indices follow the order
+/// of the domain the graph was built from
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let graph = CompactGraph::new(&[
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ])
+///     .unwrap();
+///     assert_eq!(graph.index_of(&"cat"), Some(0));
+///     assert_eq!(graph.deps(0), &[1]);
+///
+///     let order: Vec<_> = graph.sort(&"cat").unwrap().into_iter().map(|index| graph.id(index)).collect();
+///     assert_eq!(order, vec![&"dog", &"cat"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompactGraph<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    ids: Vec<Id>,
+    index: HashMap<Id, u32>,
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+}
+
+impl<Id> CompactGraph<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// fails if ids in `domain` aren't unique or a dependency is missing from it, optional dependencies
+    /// missing from it are left out
+    ///
+    /// panics if `domain` or the total number of its dependencies doesn't fit in `u32`
+    pub fn new<T>(domain: &[T]) -> Result<Self, TopsortError<Id>>
+    where
+        T: HasDependencies<Id = Id>,
+    {
+        let mut index = HashMap::with_capacity(domain.len());
+        for (position, node) in domain.iter().enumerate() {
+            let position = u32::try_from(position).expect("too many nodes for a CompactGraph");
+            if index.insert(node.id().clone(), position).is_some() {
+                return Err(TopsortError::DuplicateId(node.id().clone()));
+            }
+        }
+
+        let mut offsets = Vec::with_capacity(domain.len() + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for node in domain.iter() {
+            for dep in node.deps() {
+                match index.get(dep) {
+                    Some(&dep) => targets.push(dep),
+                    None if node.is_optional(dep) => {}
+                    None => return Err(TopsortError::TargetNotFound(dep.clone())),
+                }
+            }
+            offsets.push(u32::try_from(targets.len()).expect("too many edges for a CompactGraph"));
+        }
+
+        Ok(Self {
+            ids: domain.iter().map(|node| node.id().clone()).collect(),
+            index,
+            offsets,
+            targets,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// total number of dependencies of all nodes
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// panics if `index` is out of bounds
+    pub fn id(&self, index: u32) -> &Id {
+        &self.ids[index as usize]
+    }
+
+    pub fn index_of<Q>(&self, id: &Q) -> Option<u32>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(id).copied()
+    }
+
+    /// indices of dependencies of the node at `index`, panics if `index` is out of bounds
+    pub fn deps(&self, index: u32) -> &[u32] {
+        let start = self.offsets[index as usize] as usize;
+        let end = self.offsets[index as usize + 1] as usize;
+        &self.targets[start..end]
+    }
+
+    /// positions of dependencies of the node at `position`
+    pub(crate) fn dep_positions(&self, position: usize) -> impl Iterator<Item = usize> + '_ {
+        self.deps(position as u32).iter().map(|&dep| dep as usize)
+    }
+
+    /// indices of nodes in topological order, ending on the node with id of `target`
+    pub fn sort<Q>(&self, target: &Q) -> Result<Vec<u32>, TopsortError<Id>>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+    {
+        let start = match self.index_of(target) {
+            Some(start) => start,
+            None => return Err(TopsortError::TargetNotFound(target.to_owned())),
+        };
+
+        let mut search = Search::new(self.len());
+        self.visit(&mut search, start)?;
+        Ok(search.order)
+    }
+
+    /// indices of every node in topological order, nodes that don't depend on each other are ordered by index
+    pub fn sort_all(&self) -> Result<Vec<u32>, TopsortError<Id>> {
+        let mut search = Search::new(self.len());
+        for start in 0..self.len() as u32 {
+            self.visit(&mut search, start)?;
+        }
+        Ok(search.order)
+    }
+
+    fn visit(&self, search: &mut Search, start: u32) -> Result<(), TopsortError<Id>> {
+        if search.visited.contains(start as usize) {
+            return Ok(());
+        }
+
+        search.on_path.insert(start as usize);
+        search.stack.push((start, 0));
+        while let Some((index, next_dep)) = search.stack.last_mut() {
+            let index = *index;
+            match self.deps(index).get(*next_dep) {
+                Some(&dep) => {
+                    *next_dep += 1;
+                    if search.visited.contains(dep as usize) {
+                        continue;
+                    }
+                    if dep == index {
+                        return Err(TopsortError::SelfDependency(self.id(dep).clone()));
+                    }
+                    if search.on_path.contains(dep as usize) {
+                        let from = search
+                            .stack
+                            .iter()
+                            .position(|&(on_stack, _)| on_stack == dep)
+                            .unwrap_or(0);
+                        return Err(TopsortError::CyclicDependency(
+                            search.stack[from..]
+                                .iter()
+                                .map(|&(on_stack, _)| self.id(on_stack).clone())
+                                .chain(core::iter::once(self.id(dep).clone()))
+                                .collect(),
+                        ));
+                    }
+
+                    search.on_path.insert(dep as usize);
+                    search.stack.push((dep, 0));
+                }
+                None => {
+                    search.stack.pop();
+                    search.on_path.remove(index as usize);
+                    search.visited.insert(index as usize);
+                    search.order.push(index);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// state of a depth-first search over a [`CompactGraph`]
+struct Search {
+    visited: BitSet,
+    on_path: BitSet,
+    stack: Vec<(u32, usize)>,
+    order: Vec<u32>,
+}
+
+impl Search {
+    fn new(len: usize) -> Self {
+        Self {
+            visited: BitSet::new(len),
+            on_path: BitSet::new(len),
+            stack: Vec::new(),
+            order: Vec::with_capacity(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[test]
+    fn builds_csr() {
+        let graph = CompactGraph::new(&[
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![], ()),
+            Node::new(3, vec![2], ()),
+        ])
+        .unwrap();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.deps(0), &[1, 2]);
+        assert_eq!(graph.deps(1), &[] as &[u32]);
+        assert_eq!(graph.deps(2), &[1]);
+        assert_eq!(graph.id(2), &3);
+        assert_eq!(graph.index_of(&4), None);
+    }
+
+    #[test]
+    fn sort_matches_traversal() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![5], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ];
+        let graph = CompactGraph::new(&domain).unwrap();
+        let values = |order: Vec<u32>| -> Vec<_> {
+            order
+                .into_iter()
+                .map(|index| domain[index as usize].value)
+                .collect()
+        };
+        assert_eq!(graph.sort(&1).map(values), crate::sort(&domain, 1));
+        assert_eq!(graph.sort_all().map(values), crate::sort_all(&domain));
+        assert_eq!(graph.sort(&6), Err(TopsortError::TargetNotFound(6)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            CompactGraph::new(&[Node::new(1, vec![2], ())]).map(|graph| graph.len()),
+            Err(TopsortError::TargetNotFound(2))
+        );
+        assert_eq!(
+            CompactGraph::new(&[Node::new(1, vec![], ()), Node::new(1, vec![], ())])
+                .map(|graph| graph.len()),
+            Err(TopsortError::DuplicateId(1))
+        );
+
+        let graph = CompactGraph::new(&[
+            Node::new(1, vec![2], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![2, 3], ()),
+        ])
+        .unwrap();
+        assert_eq!(
+            graph.sort(&1),
+            Err(TopsortError::CyclicDependency(vec![2, 3, 2]))
+        );
+        let graph = CompactGraph::new(&[Node::new(1, vec![1], ())]).unwrap();
+        assert_eq!(graph.sort_all(), Err(TopsortError::SelfDependency(1)));
+    }
+}
@@ -0,0 +1,184 @@
+/// returns positions of nodes of a table known at compile time in topological order, usable in
+/// `const` items
+///
+/// `deps[i]` lists positions of dependencies of the node at position `i`; among nodes whose
+/// dependencies are all placed the one at the lowest position comes first, so the order is stable;
+/// panics, failing the build when evaluated in a `const`, on cycles and positions out of the table
+///
+/// # Examples:
+/// ```
+///     use szyk::sort_const;
+///
+///     const PLUGINS: [&str; 3] = ["render", "window", "input"];
+///     // render needs the window, input needs the window
+///     const ORDER: [usize; 3] = sort_const(&[&[1], &[], &[1]]);
+///     assert_eq!(ORDER, [1, 0, 2]);
+///     assert_eq!(PLUGINS[ORDER[0]], "window");
+/// ```
+pub const fn sort_const<const N: usize>(deps: &[&[usize]; N]) -> [usize; N] {
+    let mut order = [0; N];
+    let mut placed = [false; N];
+    let mut len = 0;
+    while len < N {
+        let mut position = 0;
+        let mut found = false;
+        while position < N && !found {
+            if !placed[position] && all_placed(deps[position], &placed) {
+                found = true;
+            } else {
+                position += 1;
+            }
+        }
+        if !found {
+            panic!("cyclic dependency");
+        }
+
+        placed[position] = true;
+        order[len] = position;
+        len += 1;
+    }
+    order
+}
+
+/// returns positions of nodes of a table of named nodes known at compile time in topological order
+/// like [`sort_const`], every node being its id and ids of its dependencies
+///
+/// panics, failing the build when evaluated in a `const`, on cycles, missing dependencies and duplicate
+/// ids
+///
+/// # Examples:
+/// ```
+///     use szyk::sort_const_named;
+///
+///     const SYSTEMS: [(&str, &[&str]); 3] = [
+///         ("physics", &["input"]),
+///         ("render", &["physics"]),
+///         ("input", &[]),
+///     ];
+///     const ORDER: [usize; 3] = sort_const_named(&SYSTEMS);
+///     assert_eq!(ORDER, [2, 0, 1]);
+/// ```
+pub const fn sort_const_named<const N: usize>(nodes: &[(&str, &[&str]); N]) -> [usize; N] {
+    let mut order = [0; N];
+    let mut placed = [false; N];
+    let mut len = 0;
+
+    let mut position = 0;
+    while position < N {
+        let mut other = position + 1;
+        while other < N {
+            if str_eq(nodes[position].0, nodes[other].0) {
+                panic!("duplicate id");
+            }
+            other += 1;
+        }
+        position += 1;
+    }
+
+    while len < N {
+        let mut position = 0;
+        let mut found = false;
+        while position < N && !found {
+            if !placed[position] && all_placed_named(nodes, nodes[position].1, &placed) {
+                found = true;
+            } else {
+                position += 1;
+            }
+        }
+        if !found {
+            panic!("cyclic dependency");
+        }
+
+        placed[position] = true;
+        order[len] = position;
+        len += 1;
+    }
+    order
+}
+
+const fn all_placed<const N: usize>(deps: &[usize], placed: &[bool; N]) -> bool {
+    let mut nth = 0;
+    while nth < deps.len() {
+        if deps[nth] >= N {
+            panic!("dependency out of the table");
+        }
+        if !placed[deps[nth]] {
+            return false;
+        }
+        nth += 1;
+    }
+    true
+}
+
+const fn all_placed_named<const N: usize>(
+    nodes: &[(&str, &[&str]); N],
+    deps: &[&str],
+    placed: &[bool; N],
+) -> bool {
+    let mut nth = 0;
+    while nth < deps.len() {
+        if !placed[find(nodes, deps[nth])] {
+            return false;
+        }
+        nth += 1;
+    }
+    true
+}
+
+const fn find<const N: usize>(nodes: &[(&str, &[&str]); N], id: &str) -> usize {
+    let mut position = 0;
+    while position < N {
+        if str_eq(nodes[position].0, id) {
+            return position;
+        }
+        position += 1;
+    }
+    panic!("dependency not found")
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut nth = 0;
+    while nth < a.len() {
+        if a[nth] != b[nth] {
+            return false;
+        }
+        nth += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_const_works() {
+        const ORDER: [usize; 5] = sort_const(&[&[1, 2], &[3], &[3, 1], &[], &[]]);
+        assert_eq!(ORDER, [3, 1, 2, 0, 4]);
+        assert_eq!(sort_const::<0>(&[]), [0usize; 0]);
+
+        const NAMED: [usize; 4] = sort_const_named(&[
+            ("hello", &["world", "cat"]),
+            ("world", &[]),
+            ("cat", &["dog"]),
+            ("dog", &[]),
+        ]);
+        assert_eq!(NAMED, [1, 3, 2, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cyclic dependency")]
+    fn sort_const_cycle() {
+        sort_const(&[&[1], &[0]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "dependency not found")]
+    fn sort_const_named_missing() {
+        sort_const_named(&[("hello", &["world"])]);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::scc::components;
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::mem;
+
+/// returns every elementary cycle of `domain` found with Johnson's algorithm, as ids in dependency order
+/// starting and ending on the same id like in [`TopsortError::CyclicDependency`]
+///
+/// every cycle starts on its node that comes first in `domain`, cycles are grouped by that node in the
+/// order of `domain`; the number of cycles can grow exponentially with the size of the domain, fails on
+/// missing dependencies
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog", "mouse"], "Garfield"),
+///         Node::new("dog", vec!["cat"], "Odie"),
+///         Node::new("mouse", vec!["cat", "mouse"], "Jerry"),
+///     ];
+///     assert_eq!(
+///         find_cycles(&domain),
+///         Ok(vec![
+///             vec!["cat", "dog", "cat"],
+///             vec!["cat", "mouse", "cat"],
+///             vec!["mouse", "mouse"],
+///         ])
+///     );
+/// ```
+pub fn find_cycles<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Vec<Vec<Id>>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let graph = CompactGraph::new(domain)?;
+    let mut component_of = vec![0; graph.len()];
+    for (component, members) in components(&graph).into_iter().enumerate() {
+        for position in members {
+            component_of[position] = component;
+        }
+    }
+
+    // dependencies within the same component, once each
+    let deps: Vec<Vec<usize>> = (0..graph.len())
+        .map(|position| {
+            let mut deps: Vec<usize> = Vec::new();
+            for dep in graph.dep_positions(position) {
+                if component_of[dep] == component_of[position] && !deps.contains(&dep) {
+                    deps.push(dep);
+                }
+            }
+            deps
+        })
+        .collect();
+
+    let mut cycles = Vec::new();
+    let mut search = Search {
+        deps: &deps,
+        blocked: vec![false; graph.len()],
+        blocking: vec![Vec::new(); graph.len()],
+    };
+    for start in 0..graph.len() {
+        search.circuits(start, &component_of, &mut |path| {
+            cycles.push(
+                path.iter()
+                    .chain(core::iter::once(&start))
+                    .map(|&position| domain[position].id.clone())
+                    .collect(),
+            )
+        });
+    }
+
+    Ok(cycles)
+}
+
+/// state of Johnson's search for circuits through a single node
+struct Search<'a> {
+    deps: &'a [Vec<usize>],
+    blocked: Vec<bool>,
+    /// nodes to unblock once the node is unblocked
+    blocking: Vec<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    /// calls `cb` with positions on every circuit through `start` which only visits nodes after it in
+    /// its component, starting on `start`
+    fn circuits<F>(&mut self, start: usize, component_of: &[usize], cb: &mut F)
+    where
+        F: FnMut(&[usize]),
+    {
+        let allowed =
+            |position: usize| position >= start && component_of[position] == component_of[start];
+        for position in (start..self.deps.len()).filter(|&position| allowed(position)) {
+            self.blocked[position] = false;
+            self.blocking[position].clear();
+        }
+
+        // explicit call stack of (node, position of the next dependency to visit, found a circuit)
+        let mut frames = vec![(start, 0, false)];
+        let mut path = vec![start];
+        self.blocked[start] = true;
+        while let Some((node, next_dep, found)) = frames.last_mut() {
+            let node = *node;
+            match self.deps[node].get(*next_dep) {
+                Some(&dep) => {
+                    *next_dep += 1;
+                    if !allowed(dep) {
+                        continue;
+                    }
+                    if dep == start {
+                        *found = true;
+                        cb(&path);
+                    } else if !self.blocked[dep] {
+                        self.blocked[dep] = true;
+                        path.push(dep);
+                        frames.push((dep, 0, false));
+                    }
+                }
+                None => {
+                    let found = *found;
+                    if found {
+                        self.unblock(node);
+                    } else {
+                        for &dep in self.deps[node].iter().filter(|&&dep| allowed(dep)) {
+                            if !self.blocking[dep].contains(&node) {
+                                self.blocking[dep].push(node);
+                            }
+                        }
+                    }
+
+                    frames.pop();
+                    path.pop();
+                    if let Some((_, _, parent_found)) = frames.last_mut() {
+                        *parent_found |= found;
+                    }
+                }
+            }
+        }
+    }
+
+    fn unblock(&mut self, position: usize) {
+        let mut stack = vec![position];
+        while let Some(position) = stack.pop() {
+            self.blocked[position] = false;
+            for blocked in mem::take(&mut self.blocking[position]) {
+                if self.blocked[blocked] {
+                    stack.push(blocked);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_cycles_works() {
+        let domain = [
+            Node::new(1, vec![2, 4], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2, 5, 1], "cat"),
+            Node::new(4, vec![5, 1], "dog"),
+            Node::new(5, vec![], "mouse"),
+            Node::new(6, vec![6, 6], "bird"),
+        ];
+        assert_eq!(
+            find_cycles(&domain),
+            Ok(vec![
+                vec![1, 2, 3, 1],
+                vec![1, 4, 1],
+                vec![2, 3, 2],
+                vec![6, 6],
+            ])
+        );
+    }
+
+    #[test]
+    fn find_cycles_complete_graph() {
+        // every ordered selection of 2 or more nodes, up to rotation
+        let domain: Vec<_> = (0..5)
+            .map(|id| Node::new(id, (0..5).filter(|&dep| dep != id).collect(), ()))
+            .collect();
+        let cycles = find_cycles(&domain).unwrap();
+        assert_eq!(cycles.len(), 10 + 20 + 30 + 24);
+        for cycle in cycles.iter() {
+            assert_eq!(cycle.first(), cycle.last());
+            let mut members = cycle[1..].to_vec();
+            members.sort_unstable();
+            members.dedup();
+            assert_eq!(members.len(), cycle.len() - 1);
+        }
+    }
+
+    #[test]
+    fn find_cycles_errors() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+        ];
+        assert_eq!(find_cycles(&domain), Ok(vec![]));
+        assert_eq!(
+            find_cycles(&[Node::new(1, vec![2], "hello")]),
+            Err(TopsortError::TargetNotFound(2))
+        );
+    }
+}
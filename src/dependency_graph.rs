@@ -0,0 +1,273 @@
+use crate::collections::{HashMap, HashSet};
+use crate::{Graph, TopsortError};
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+/// dependency graph kept in a user structure, sortable with [`sort_graph`] without copying it into nodes
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::collections::HashMap;
+///
+///     struct Package {
+///         deps: Vec<u32>,
+///     }
+///
+///     struct Registry(HashMap<u32, Package>);
+///
+///     impl DependencyGraph for Registry {
+///         type Id = u32;
+///
+///         fn nodes(&self) -> impl Iterator<Item = &u32> {
+///             self.0.keys()
+///         }
+///
+///         fn deps_of(&self, id: &u32) -> Option<&[u32]> {
+///             self.0.get(id).map(|package| package.deps.as_slice())
+///         }
+///     }
+///
+///     let mut registry = Registry(HashMap::new());
+///     registry.0.insert(1, Package { deps: vec![2] });
+///     registry.0.insert(2, Package { deps: vec![] });
+///     assert_eq!(sort_graph(&registry, 1), Ok(vec![2, 1]));
+/// ```
+pub trait DependencyGraph {
+    type Id: Clone + Eq + Hash;
+
+    /// ids of all nodes, unrelated nodes are sorted by [`sort_graph_all`] in this order
+    fn nodes(&self) -> impl Iterator<Item = &Self::Id>;
+
+    /// dependencies of the node with id of `id`, `None` if it's not in the graph
+    fn deps_of(&self, id: &Self::Id) -> Option<&[Self::Id]>;
+}
+
+impl<Id, Item> DependencyGraph for Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    type Id = Id;
+
+    fn nodes(&self) -> impl Iterator<Item = &Id> {
+        Graph::nodes(self).iter().map(|node| &node.id)
+    }
+
+    fn deps_of(&self, id: &Id) -> Option<&[Id]> {
+        self.get(id).map(|node| node.deps.as_slice())
+    }
+}
+
+/// map from ids to their dependencies
+impl<Id, S> DependencyGraph for HashMap<Id, Vec<Id>, S>
+where
+    Id: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    type Id = Id;
+
+    fn nodes(&self) -> impl Iterator<Item = &Id> {
+        self.keys()
+    }
+
+    fn deps_of(&self, id: &Id) -> Option<&[Id]> {
+        self.get(id).map(Vec::as_slice)
+    }
+}
+
+/// returns ids of nodes from `graph` in topological order, ending on `target`
+///
+/// follows the same rules as [`crate::sort`], dependencies are visited in the order returned by
+/// [`DependencyGraph::deps_of`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::collections::HashMap;
+///
+///     let mut graph = HashMap::new();
+///     graph.insert("wooden pickaxe", vec!["planks", "sticks"]);
+///     graph.insert("planks", vec!["wood"]);
+///     graph.insert("sticks", vec!["planks"]);
+///     graph.insert("wood", vec![]);
+///     assert_eq!(
+///         sort_graph(&graph, "wooden pickaxe"),
+///         Ok(vec!["wood", "planks", "sticks", "wooden pickaxe"])
+///     );
+/// ```
+pub fn sort_graph<G>(graph: &G, target: G::Id) -> Result<Vec<G::Id>, TopsortError<G::Id>>
+where
+    G: DependencyGraph + ?Sized,
+{
+    let mut visit = Visit::new(graph);
+    visit.visit(target)?;
+    Ok(visit.order)
+}
+
+/// returns ids of every node from `graph` in topological order, including disconnected components
+///
+/// nodes that don't depend on each other follow the order of [`DependencyGraph::nodes`]
+pub fn sort_graph_all<G>(graph: &G) -> Result<Vec<G::Id>, TopsortError<G::Id>>
+where
+    G: DependencyGraph + ?Sized,
+{
+    let mut visit = Visit::new(graph);
+    for id in graph.nodes() {
+        visit.visit(id.clone())?;
+    }
+    Ok(visit.order)
+}
+
+/// node on the explicit work stack of [`Visit`]
+struct Frame<'a, Id> {
+    id: Id,
+    deps: &'a [Id],
+    /// position of the next dependency to visit
+    next_dep: usize,
+}
+
+/// state of a depth-first traversal over a [`DependencyGraph`], keyed by ids instead of positions
+struct Visit<'a, G>
+where
+    G: DependencyGraph + ?Sized,
+{
+    graph: &'a G,
+    visited: HashSet<G::Id>,
+    on_path: HashSet<G::Id>,
+    stack: Vec<Frame<'a, G::Id>>,
+    order: Vec<G::Id>,
+}
+
+impl<'a, G> Visit<'a, G>
+where
+    G: DependencyGraph + ?Sized,
+{
+    fn new(graph: &'a G) -> Self {
+        Self {
+            graph,
+            visited: HashSet::new(),
+            on_path: HashSet::new(),
+            stack: Vec::new(),
+            order: Vec::new(),
+        }
+    }
+
+    fn deps_of(&self, id: &G::Id) -> Result<&'a [G::Id], TopsortError<G::Id>> {
+        match self.graph.deps_of(id) {
+            Some(deps) => Ok(deps),
+            None => Err(TopsortError::TargetNotFound(id.clone())),
+        }
+    }
+
+    /// appends `target` and its dependencies to `order`, unless they were already visited
+    fn visit(&mut self, target: G::Id) -> Result<(), TopsortError<G::Id>> {
+        if self.visited.contains(&target) {
+            return Ok(());
+        }
+
+        let deps = self.deps_of(&target)?;
+        self.on_path.insert(target.clone());
+        self.stack.push(Frame {
+            id: target,
+            deps,
+            next_dep: 0,
+        });
+
+        while let Some(frame) = self.stack.last_mut() {
+            let deps: &'a [G::Id] = frame.deps;
+            match deps.get(frame.next_dep) {
+                Some(dep) => {
+                    frame.next_dep += 1;
+                    if self.visited.contains(dep) {
+                        continue;
+                    }
+
+                    // detect cyclic dependencies
+                    if *dep == frame.id {
+                        return Err(TopsortError::SelfDependency(dep.clone()));
+                    }
+                    if self.on_path.contains(dep) {
+                        return Err(TopsortError::CyclicDependency(self.cycle(dep)));
+                    }
+
+                    let dep_deps = self.deps_of(dep)?;
+                    self.on_path.insert(dep.clone());
+                    self.stack.push(Frame {
+                        id: dep.clone(),
+                        deps: dep_deps,
+                        next_dep: 0,
+                    });
+                }
+                None => {
+                    // all dependencies visited
+                    if let Some(frame) = self.stack.pop() {
+                        self.on_path.remove(&frame.id);
+                        self.visited.insert(frame.id.clone());
+                        self.order.push(frame.id);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// collects ids on the `stack` starting from `start`, closing the cycle on it
+    fn cycle(&self, start: &G::Id) -> Vec<G::Id> {
+        let position = self
+            .stack
+            .iter()
+            .position(|frame| frame.id == *start)
+            .unwrap_or(0);
+
+        self.stack[position..]
+            .iter()
+            .map(|frame| frame.id.clone())
+            .chain(core::iter::once(start.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_graph_works() {
+        let mut graph = Graph::new();
+        for id in 1..=4 {
+            graph.add_node(id, ());
+        }
+        graph.add_edge(1, 2).unwrap();
+        graph.add_edge(1, 3).unwrap();
+        graph.add_edge(3, 2).unwrap();
+        assert_eq!(sort_graph(&graph, 1), Ok(vec![2, 3, 1]));
+        assert_eq!(sort_graph_all(&graph), Ok(vec![2, 3, 1, 4]));
+    }
+
+    #[test]
+    fn sort_graph_errors() {
+        let mut graph = HashMap::new();
+        graph.insert(1, vec![2]);
+        graph.insert(2, vec![3]);
+        graph.insert(3, vec![1]);
+        graph.insert(4, vec![4]);
+        graph.insert(5, vec![6]);
+        assert_eq!(
+            sort_graph(&graph, 1),
+            Err(TopsortError::CyclicDependency(vec![1, 2, 3, 1]))
+        );
+        assert_eq!(sort_graph(&graph, 4), Err(TopsortError::SelfDependency(4)));
+        assert_eq!(sort_graph(&graph, 5), Err(TopsortError::TargetNotFound(6)));
+        assert_eq!(sort_graph(&graph, 7), Err(TopsortError::TargetNotFound(7)));
+    }
+
+    #[test]
+    fn sort_graph_deep_chain() {
+        let depth = 100_000;
+        let graph: HashMap<u32, Vec<u32>> = (0..depth)
+            .map(|i| (i, if i > 0 { vec![i - 1] } else { vec![] }))
+            .collect();
+        assert_eq!(sort_graph(&graph, depth - 1), Ok((0..depth).collect()));
+    }
+}
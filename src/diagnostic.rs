@@ -0,0 +1,143 @@
+use crate::traversal::first_index;
+use crate::{Node, TopsortError};
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+
+/// cycle error rendered by `miette` as the chain of ids it goes through, each id labelled with the
+/// value of its node
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec!["cat"], "Odie"),
+///     ];
+///     let err = sort(&domain, "cat").unwrap_err();
+///     let diagnostic = CycleDiagnostic::new(&err, &domain).unwrap();
+///     assert_eq!(diagnostic.chain(), "cat → dog → cat");
+///
+///     let report = miette::Report::new(diagnostic);
+///     assert_eq!(report.to_string(), "cyclic dependency");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CycleDiagnostic {
+    chain: String,
+    /// position and length of every id in the chain, with its label
+    labels: Vec<(usize, usize, Option<String>)>,
+}
+
+impl CycleDiagnostic {
+    /// describes a [`TopsortError::CyclicDependency`] or [`TopsortError::SelfDependency`] of a sort of
+    /// `domain`, returns `None` for other errors
+    ///
+    /// ids missing from `domain` are left without a label
+    pub fn new<Id, Item>(err: &TopsortError<Id>, domain: &[Node<Id, Item>]) -> Option<Self>
+    where
+        Id: Clone + Eq + Hash + Display,
+        Item: Display,
+    {
+        let cycle = match err {
+            TopsortError::CyclicDependency(cycle) => cycle.clone(),
+            TopsortError::SelfDependency(id) => vec![id.clone(), id.clone()],
+            _ => return None,
+        };
+
+        let index = first_index(domain);
+        let mut chain = String::new();
+        let mut labels = Vec::with_capacity(cycle.len());
+        for (nth, id) in cycle.iter().enumerate() {
+            if nth > 0 {
+                chain.push_str(" → ");
+            }
+            let start = chain.len();
+            chain.push_str(&id.to_string());
+            // the closing id repeats the first one, label it only once
+            let label = match index.get(id) {
+                Some(&position) if nth + 1 < cycle.len() => {
+                    Some(domain[position].value.to_string())
+                }
+                _ => None,
+            };
+            labels.push((start, chain.len() - start, label));
+        }
+
+        Some(Self { chain, labels })
+    }
+
+    /// ids of the cycle joined with arrows
+    pub fn chain(&self) -> &str {
+        &self.chain
+    }
+}
+
+impl Display for CycleDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cyclic dependency")
+    }
+}
+
+impl std::error::Error for CycleDiagnostic {}
+
+impl Diagnostic for CycleDiagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new("szyk::cyclic_dependency"))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(
+            "remove one of the dependencies in the chain to break the cycle",
+        ))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.chain)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(self.labels.iter().map(|(start, len, label)| {
+            LabeledSpan::new(label.clone(), *start, *len)
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_cycle() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![1], "cat"),
+        ];
+        let diagnostic = CycleDiagnostic::new(
+            &TopsortError::CyclicDependency(vec![1, 2, 3, 4, 1]),
+            &domain,
+        )
+        .unwrap();
+        assert_eq!(diagnostic.chain(), "1 → 2 → 3 → 4 → 1");
+        let labels: Vec<_> = diagnostic
+            .labels()
+            .unwrap()
+            .map(|span| (span.offset(), span.len(), span.label().map(String::from)))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (0, 1, Some(String::from("hello"))),
+                (6, 1, Some(String::from("world"))),
+                (12, 1, Some(String::from("cat"))),
+                (18, 1, None),
+                (24, 1, None),
+            ]
+        );
+
+        let diagnostic = CycleDiagnostic::new(&TopsortError::SelfDependency(2), &domain).unwrap();
+        assert_eq!(diagnostic.chain(), "2 → 2");
+        assert!(CycleDiagnostic::new(&TopsortError::TargetNotFound(2), &domain).is_none());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::collections::{HashMap, HashSet};
+use crate::traversal::{first_index, Traversal};
+use crate::{Node, TopsortError};
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// changes between two domains, see [`diff`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff<Id> {
+    /// ids of nodes only in the new domain, in its order
+    pub added_nodes: Vec<Id>,
+    /// ids of nodes only in the old domain, in its order
+    pub removed_nodes: Vec<Id>,
+    /// pairs of a node and its dependency only in the new domain, in its order
+    pub added_edges: Vec<(Id, Id)>,
+    /// pairs of a node and its dependency only in the old domain, in its order
+    pub removed_edges: Vec<(Id, Id)>,
+}
+
+impl<Id> Diff<Id> {
+    /// whether both domains have the same nodes and dependencies
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// returns nodes and dependencies added and removed going from `old` to `new`
+///
+/// values of nodes aren't compared, the first node wins on duplicate ids
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let old = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec![], "Planks"),
+///         Node::new("sticks", vec![], "Sticks"),
+///     ];
+///     let new = [
+///         Node::new("wooden pickaxe", vec!["planks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(
+///         diff(&old, &new),
+///         Diff {
+///             added_nodes: vec!["wood"],
+///             removed_nodes: vec!["sticks"],
+///             added_edges: vec![("planks", "wood")],
+///             removed_edges: vec![("wooden pickaxe", "sticks")],
+///         }
+///     );
+/// ```
+pub fn diff<Id, A, B>(old: &[Node<Id, A>], new: &[Node<Id, B>]) -> Diff<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    let old_index = first_index(old);
+    let new_index = first_index(new);
+    let old_edges = edges(old, &old_index);
+    let new_edges = edges(new, &new_index);
+
+    Diff {
+        added_nodes: only_in(new, &new_index, &old_index),
+        removed_nodes: only_in(old, &old_index, &new_index),
+        added_edges: edges_only_in(&new_edges, &old_edges),
+        removed_edges: edges_only_in(&old_edges, &new_edges),
+    }
+}
+
+/// returns ids of nodes present in both `old` and `new` whose place in the order of [`crate::sort_all`]
+/// among such nodes changed, in the new order
+///
+/// fails if either domain can't be sorted
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let old = [
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("planks", vec![], "Planks"),
+///         Node::new("string", vec![], "String"),
+///     ];
+///     let new = [
+///         Node::new("sticks", vec![], "Sticks"),
+///         Node::new("planks", vec!["sticks"], "Planks"),
+///         Node::new("string", vec![], "String"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(reordered_nodes(&old, &new), Ok(vec!["sticks", "planks"]));
+/// ```
+pub fn reordered_nodes<Id, A, B>(
+    old: &[Node<Id, A>],
+    new: &[Node<Id, B>],
+) -> Result<Vec<Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let old_order = order(old)?;
+    let new_order = order(new)?;
+
+    let old_ids: HashSet<&Id> = old_order.iter().copied().collect();
+    let new_ids: HashSet<&Id> = new_order.iter().copied().collect();
+    let old_common = old_order.into_iter().filter(|id| new_ids.contains(id));
+    let new_common: Vec<&Id> = new_order
+        .into_iter()
+        .filter(|id| old_ids.contains(id))
+        .collect();
+    let old_place: HashMap<&Id, usize> = old_common
+        .enumerate()
+        .map(|(place, id)| (id, place))
+        .collect();
+
+    Ok(new_common
+        .into_iter()
+        .enumerate()
+        .filter(|(place, id)| old_place.get(id) != Some(place))
+        .map(|(_, id)| id.clone())
+        .collect())
+}
+
+/// ids of every node in `domain` in the order of [`crate::sort_all`]
+fn order<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Vec<&Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let mut order = Vec::with_capacity(domain.len());
+    for index in 0..domain.len() {
+        traversal.visit(index, &mut |index| order.push(&domain[index].id))?;
+    }
+    Ok(order)
+}
+
+/// pairs of a node and its dependency, once per pair, nodes shadowed by duplicates are skipped
+fn edges<'a, Id, Item>(
+    domain: &'a [Node<Id, Item>],
+    index: &HashMap<&Id, usize>,
+) -> Vec<(&'a Id, &'a Id)>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut seen = HashSet::new();
+    domain
+        .iter()
+        .enumerate()
+        .filter(|(position, node)| index.get(&node.id) == Some(position))
+        .flat_map(|(_, node)| node.deps.iter().map(move |dep| (&node.id, dep)))
+        .filter(|&edge| seen.insert(edge))
+        .collect()
+}
+
+/// `edges` missing from `other`
+fn edges_only_in<Id>(edges: &[(&Id, &Id)], other: &[(&Id, &Id)]) -> Vec<(Id, Id)>
+where
+    Id: Clone + Eq + Hash,
+{
+    let other: HashSet<&(&Id, &Id)> = other.iter().collect();
+    edges
+        .iter()
+        .filter(|edge| !other.contains(edge))
+        .map(|&(id, dep)| (id.clone(), dep.clone()))
+        .collect()
+}
+
+/// ids of nodes in `domain` missing from `other`, once per id
+fn only_in<Id, Item>(
+    domain: &[Node<Id, Item>],
+    index: &HashMap<&Id, usize>,
+    other: &HashMap<&Id, usize>,
+) -> Vec<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain
+        .iter()
+        .enumerate()
+        .filter(|(position, node)| {
+            index.get(&node.id) == Some(position) && !other.contains_key(&node.id)
+        })
+        .map(|(_, node)| node.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_works() {
+        let old = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(3, vec![4], "dog"),
+        ];
+        let new = [
+            Node::new(1, vec![3, 2, 5], 1),
+            Node::new(3, vec![], 3),
+            Node::new(5, vec![2, 2], 5),
+        ];
+        assert_eq!(
+            diff(&old, &new),
+            Diff {
+                added_nodes: vec![5],
+                removed_nodes: vec![2],
+                added_edges: vec![(1, 5), (5, 2)],
+                removed_edges: vec![],
+            }
+        );
+        assert!(diff(&old, &old).is_empty());
+        assert!(!diff(&new, &old).is_empty());
+    }
+
+    #[test]
+    fn reordered_nodes_works() {
+        let old = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+        ];
+        let new = [
+            Node::new(1, vec![3, 2], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        assert_eq!(reordered_nodes(&old, &new), Ok(vec![3, 2]));
+        assert_eq!(reordered_nodes(&old, &old), Ok(vec![]));
+
+        let cyclic = [Node::new(1, vec![1], "hello")];
+        assert_eq!(
+            reordered_nodes(&old, &cyclic),
+            Err(TopsortError::SelfDependency(1))
+        );
+    }
+}
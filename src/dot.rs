@@ -0,0 +1,929 @@
+//! Graphviz DOT export and import
+//!
+//! # Examples
+//! ```
+//!     use szyk::Node;
+//!     use szyk::dot;
+//!
+//!     let domain = [
+//!         Node::new("cat", vec!["dog"], "Garfield"),
+//!         Node::new("dog", vec![], "Odie"),
+//!     ];
+//!     assert_eq!(
+//!         dot::to_dot(&domain),
+//!         "digraph {\n    \"cat\";\n    \"dog\";\n    \"cat\" -> \"dog\";\n}\n"
+//!     );
+//!
+//!     let graph = dot::parse("digraph { cat -> dog }").unwrap();
+//!     assert_eq!(graph.sort(String::from("cat")).unwrap().len(), 2);
+//! ```
+
+use crate::collections::{HashMap, HashSet};
+use crate::traversal::first_index;
+use crate::{Edge, Graph, Node};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Write};
+use core::hash::Hash;
+
+const CLOSURE_COLOR: &str = "blue";
+const CYCLE_COLOR: &str = "red";
+
+/// controls highlighting in [`to_dot_with`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotOptions<Id> {
+    /// highlights the node with this id and all of its transitive dependencies
+    pub target: Option<Id>,
+    /// highlights nodes and edges forming cycles
+    pub cycles: bool,
+}
+
+impl<Id> Default for DotOptions<Id> {
+    fn default() -> Self {
+        Self {
+            target: None,
+            cycles: false,
+        }
+    }
+}
+
+/// renders `domain` as a DOT digraph with edges pointing from nodes to their dependencies
+///
+/// dependencies missing from `domain` are rendered as edges to implicit nodes, [`Node::attributes`]
+/// are rendered as attributes of node statements, e.g. `label` or `shape`, and [`crate::Edge`] data as
+/// `label`, `class` (the kind) and `weight` attributes of edge statements
+///
+/// # Examples
+/// ```
+///     use szyk::Node;
+///     use szyk::dot;
+///
+///     let domain = [Node::new("cat", vec![], "Garfield").with_attribute("label", "Garfield")];
+///     assert_eq!(dot::to_dot(&domain), "digraph {\n    \"cat\" [label=\"Garfield\"];\n}\n");
+/// ```
+pub fn to_dot<Id, Item>(domain: &[Node<Id, Item>]) -> String
+where
+    Id: Clone + Eq + Hash + Display,
+{
+    to_dot_with(domain, &DotOptions::default())
+}
+
+/// renders `domain` as a DOT digraph, highlighting parts of it according to `options`
+///
+/// # Examples
+/// ```
+///     use szyk::Node;
+///     use szyk::dot::{self, DotOptions};
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     let options = DotOptions { target: Some("dog"), cycles: false };
+///     assert_eq!(
+///         dot::to_dot_with(&domain, &options),
+///         "digraph {\n    \"cat\";\n    \"dog\" [color=blue];\n    \"cat\" -> \"dog\";\n}\n"
+///     );
+/// ```
+pub fn to_dot_with<Id, Item>(domain: &[Node<Id, Item>], options: &DotOptions<Id>) -> String
+where
+    Id: Clone + Eq + Hash + Display,
+{
+    let mut out = String::new();
+    write_dot(&mut out, domain, options).expect("writing to a String doesn't fail");
+    out
+}
+
+fn write_dot<Id, Item, W>(
+    out: &mut W,
+    domain: &[Node<Id, Item>],
+    options: &DotOptions<Id>,
+) -> fmt::Result
+where
+    Id: Clone + Eq + Hash + Display,
+    W: Write,
+{
+    let index = first_index(domain);
+    let closure = match &options.target {
+        Some(target) => closure(domain, &index, target),
+        None => HashSet::new(),
+    };
+    let cycles = if options.cycles {
+        cycle_edges(domain, &index)
+    } else {
+        HashSet::new()
+    };
+
+    let cycle_nodes: HashSet<usize> = cycles.iter().map(|&(from, _)| from).collect();
+
+    writeln!(out, "digraph {{")?;
+    for (position, node) in domain.iter().enumerate() {
+        let color = match (cycle_nodes.contains(&position), closure.contains(&position)) {
+            (true, _) => Some(CYCLE_COLOR),
+            (false, true) => Some(CLOSURE_COLOR),
+            (false, false) => None,
+        };
+
+        // highlighting takes precedence over a color given in attributes
+        let mut attributes: Vec<String> = node
+            .attributes
+            .iter()
+            .filter(|(key, _)| color.is_none() || key.as_str() != "color")
+            .map(|(key, value)| format!("{}={}", attribute_key(key), quote(value)))
+            .collect();
+        if let Some(color) = color {
+            attributes.push(format!("color={}", color));
+        }
+
+        if attributes.is_empty() {
+            writeln!(out, "    {};", quote(&node.id))?;
+        } else {
+            writeln!(out, "    {} [{}];", quote(&node.id), attributes.join(", "))?;
+        }
+    }
+
+    for (position, node) in domain.iter().enumerate() {
+        for dep in node.deps.iter() {
+            let dep_index = index.get(dep).copied();
+            let color = match dep_index {
+                Some(dep_index) if cycles.contains(&(position, dep_index)) => Some(CYCLE_COLOR),
+                Some(dep_index) if closure.contains(&position) && closure.contains(&dep_index) => {
+                    Some(CLOSURE_COLOR)
+                }
+                _ => None,
+            };
+
+            let mut attributes = Vec::new();
+            if let Some(edge) = node.edge(dep) {
+                if let Some(label) = &edge.label {
+                    attributes.push(format!("label={}", quote(label)));
+                }
+                if let Some(kind) = &edge.kind {
+                    attributes.push(format!("class={}", quote(kind)));
+                }
+                if let Some(weight) = edge.weight {
+                    attributes.push(format!("weight={}", weight));
+                }
+            }
+            if let Some(color) = color {
+                attributes.push(format!("color={}", color));
+            }
+
+            if attributes.is_empty() {
+                writeln!(out, "    {} -> {};", quote(&node.id), quote(dep))?;
+            } else {
+                writeln!(
+                    out,
+                    "    {} -> {} [{}];",
+                    quote(&node.id),
+                    quote(dep),
+                    attributes.join(", ")
+                )?;
+            }
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/// quotes `id` as a DOT string
+fn quote<Id>(id: &Id) -> String
+where
+    Id: Display,
+{
+    format!(
+        "\"{}\"",
+        id.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// renders `key` bare when it's a plain identifier, quoted otherwise
+fn attribute_key(key: &str) -> String {
+    let plain = key.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        key.to_string()
+    } else {
+        quote(&key)
+    }
+}
+
+/// positions of the node with id of `target` and all of its transitive dependencies
+fn closure<Id, Item>(
+    domain: &[Node<Id, Item>],
+    index: &HashMap<&Id, usize>,
+    target: &Id,
+) -> HashSet<usize>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut closure = HashSet::new();
+    let mut stack: Vec<usize> = index.get(target).copied().into_iter().collect();
+    while let Some(position) = stack.pop() {
+        if closure.insert(position) {
+            stack.extend(
+                domain[position]
+                    .deps
+                    .iter()
+                    .filter_map(|dep| index.get(dep)),
+            );
+        }
+    }
+    closure
+}
+
+/// edges, as pairs of positions, of every cycle found by a depth-first search over `domain`
+fn cycle_edges<Id, Item>(
+    domain: &[Node<Id, Item>],
+    index: &HashMap<&Id, usize>,
+) -> HashSet<(usize, usize)>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut edges = HashSet::new();
+    let mut visited = vec![false; domain.len()];
+    let mut on_path = vec![false; domain.len()];
+
+    for root in 0..domain.len() {
+        if visited[root] {
+            continue;
+        }
+
+        let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+        on_path[root] = true;
+        while let Some((position, next_dep)) = stack.last_mut() {
+            let position = *position;
+            match domain[position].deps.get(*next_dep) {
+                Some(dep) => {
+                    *next_dep += 1;
+                    let dep_position = match index.get(dep) {
+                        Some(&dep_position) => dep_position,
+                        None => continue,
+                    };
+
+                    if on_path[dep_position] {
+                        // back edge, walk the stack to record the whole cycle
+                        let start = stack
+                            .iter()
+                            .position(|(on_stack, _)| *on_stack == dep_position)
+                            .unwrap_or(0);
+                        for window in stack[start..].windows(2) {
+                            edges.insert((window[0].0, window[1].0));
+                        }
+                        edges.insert((position, dep_position));
+                    } else if !visited[dep_position] {
+                        on_path[dep_position] = true;
+                        stack.push((dep_position, 0));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    on_path[position] = false;
+                    visited[position] = true;
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// error returned when DOT input can't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotParseError {
+    /// line of the input the error was found on, starting from 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for DotParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl core::error::Error for DotParseError {}
+
+/// parses a DOT digraph into a [`Graph`], `a -> b` makes `a` depend on `b`
+///
+/// nodes are added in the order they first appear and subgraphs are flattened; attributes of node
+/// statements are kept in [`Node::attributes`], `label`, `class` and `weight` attributes of edge
+/// statements in [`crate::Edge`] data of the edges, other attributes are ignored
+///
+/// # Examples
+/// ```
+///     use szyk::dot;
+///
+///     let graph = dot::parse(r#"
+///         digraph crafting {
+///             "wooden pickaxe" -> { planks sticks };
+///             sticks -> planks -> wood;
+///         }
+///     "#).unwrap();
+///     let order: Vec<_> = graph.nodes().iter().map(|node| node.id.as_str()).collect();
+///     assert_eq!(order, vec!["wooden pickaxe", "planks", "sticks", "wood"]);
+/// ```
+pub fn parse(input: &str) -> Result<Graph<String, ()>, DotParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+        graph: Graph::new(),
+    };
+    parser.graph()?;
+    Ok(parser.graph)
+}
+
+/// parses a DOT digraph into nodes, see [`parse`]
+pub fn from_dot(input: &str) -> Result<Vec<Node<String, ()>>, DotParseError> {
+    parse(input).map(Graph::into_nodes)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// * `String` - identifier, numeral, quoted or HTML string
+    /// * `bool` - whether the identifier was unquoted and can be a keyword
+    Id(String, bool),
+    Arrow,
+    UndirectedEdge,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Semicolon,
+    Comma,
+    Equals,
+    Colon,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Id(text, _) => write!(f, "`{}`", text),
+            Token::Arrow => write!(f, "`->`"),
+            Token::UndirectedEdge => write!(f, "`--`"),
+            Token::LeftBrace => write!(f, "`{{`"),
+            Token::RightBrace => write!(f, "`}}`"),
+            Token::LeftBracket => write!(f, "`[`"),
+            Token::RightBracket => write!(f, "`]`"),
+            Token::Semicolon => write!(f, "`;`"),
+            Token::Comma => write!(f, "`,`"),
+            Token::Equals => write!(f, "`=`"),
+            Token::Colon => write!(f, "`:`"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, DotParseError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            // preprocessor output and line comments
+            '#' => {
+                while !matches!(chars.peek(), None | Some('\n')) {
+                    chars.next();
+                }
+                continue;
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while !matches!(chars.peek(), None | Some('\n')) {
+                    chars.next();
+                }
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => return Err(error(line, "unterminated comment")),
+                    }
+                }
+                continue;
+            }
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => text.push('"'),
+                            Some('\\') => text.push('\\'),
+                            // line continuation
+                            Some('\n') => line += 1,
+                            Some(c) => {
+                                text.push('\\');
+                                text.push(c);
+                            }
+                            None => return Err(error(start, "unterminated string")),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                        None => return Err(error(start, "unterminated string")),
+                    }
+                }
+                Token::Id(text, false)
+            }
+            '<' => {
+                let start = line;
+                let mut text = String::new();
+                let mut depth = 1;
+                loop {
+                    match chars.next() {
+                        Some('>') if depth == 1 => break,
+                        Some(c) => {
+                            match c {
+                                '<' => depth += 1,
+                                '>' => depth -= 1,
+                                '\n' => line += 1,
+                                _ => {}
+                            }
+                            text.push(c);
+                        }
+                        None => return Err(error(start, "unterminated HTML string")),
+                    }
+                }
+                Token::Id(text, false)
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                Token::Arrow
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                Token::UndirectedEdge
+            }
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            '=' => Token::Equals,
+            ':' => Token::Colon,
+            c if is_id_char(c) || c == '-' => {
+                let mut text = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !is_id_char(c) {
+                        break;
+                    }
+                    text.push(c);
+                    chars.next();
+                }
+                Token::Id(text, true)
+            }
+            c => return Err(error(line, &format!("unexpected character `{}`", c))),
+        };
+        tokens.push((token, line));
+    }
+
+    Ok(tokens)
+}
+
+/// data of edges given by `label`, `class` and `weight` attributes, if any of them is there
+fn edge_data(attributes: Vec<(String, String)>) -> Option<Edge> {
+    let mut edge = Edge::new();
+    let mut found = false;
+    for (key, value) in attributes {
+        match key.as_str() {
+            "label" => edge.label = Some(value),
+            "class" => edge.kind = Some(value),
+            "weight" => match value.parse() {
+                Ok(weight) => edge.weight = Some(weight),
+                Err(_) => continue,
+            },
+            _ => continue,
+        }
+        found = true;
+    }
+    if found {
+        Some(edge)
+    } else {
+        None
+    }
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || !c.is_ascii()
+}
+
+fn error(line: usize, message: &str) -> DotParseError {
+    DotParseError {
+        line,
+        message: message.to_string(),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    graph: Graph<String, ()>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens
+            .get(self.position + offset)
+            .map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn unexpected(&self) -> DotParseError {
+        match self.peek() {
+            Some(token) => error(self.line(), &format!("unexpected {}", token)),
+            None => error(self.line(), "unexpected end of input"),
+        }
+    }
+
+    fn is_keyword(&self, offset: usize, keyword: &str) -> bool {
+        match self.peek_at(offset) {
+            Some(Token::Id(text, true)) => text.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), DotParseError> {
+        if self.peek() == Some(&token) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn id(&mut self) -> Result<String, DotParseError> {
+        match self.peek() {
+            Some(Token::Id(text, _)) => {
+                let text = text.clone();
+                self.position += 1;
+                Ok(text)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// graph : [ strict ] digraph [ ID ] '{' stmt_list '}'
+    fn graph(&mut self) -> Result<(), DotParseError> {
+        if self.is_keyword(0, "strict") {
+            self.position += 1;
+        }
+        if self.is_keyword(0, "graph") {
+            return Err(error(
+                self.line(),
+                "only directed graphs (digraph) are supported",
+            ));
+        }
+        if !self.is_keyword(0, "digraph") {
+            return Err(self.unexpected());
+        }
+        self.position += 1;
+
+        if let Some(Token::Id(_, _)) = self.peek() {
+            self.position += 1;
+        }
+        self.expect(Token::LeftBrace)?;
+        self.stmt_list()?;
+        self.expect(Token::RightBrace)?;
+
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected()),
+        }
+    }
+
+    /// returns ids of all nodes mentioned in the statements
+    fn stmt_list(&mut self) -> Result<Vec<String>, DotParseError> {
+        let mut ids = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::RightBrace) => return Ok(ids),
+                Some(Token::Semicolon) => self.position += 1,
+                Some(_) => self.stmt(&mut ids)?,
+            }
+        }
+    }
+
+    fn stmt(&mut self, ids: &mut Vec<String>) -> Result<(), DotParseError> {
+        // attr_stmt : (graph | node | edge) attr_list
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|keyword| self.is_keyword(0, keyword))
+            && self.peek_at(1) == Some(&Token::LeftBracket)
+        {
+            self.position += 1;
+            return self.attr_list().map(|_| ());
+        }
+
+        // ID '=' ID
+        if let (Some(Token::Id(_, _)), Some(Token::Equals)) = (self.peek(), self.peek_at(1)) {
+            self.position += 2;
+            return self.id().map(|_| ());
+        }
+
+        // node_stmt : node_id [ attr_list ]
+        // edge_stmt : (node_id | subgraph) edgeRHS [ attr_list ]
+        let mut is_node_stmt =
+            !self.is_keyword(0, "subgraph") && self.peek() != Some(&Token::LeftBrace);
+        let mut from = self.operand(ids)?;
+        let mut edges = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Arrow) => {
+                    is_node_stmt = false;
+                    self.position += 1;
+                    let to = self.operand(ids)?;
+                    for id in from.iter() {
+                        for dep in to.iter() {
+                            self.graph
+                                .add_edge(id.clone(), dep.clone())
+                                .expect("operands are added to the graph");
+                            edges.push((id.clone(), dep.clone()));
+                        }
+                    }
+                    from = to;
+                }
+                Some(Token::UndirectedEdge) => {
+                    return Err(error(self.line(), "undirected edges are not supported"))
+                }
+                _ => break,
+            }
+        }
+
+        let attributes = self.attr_list()?;
+        if is_node_stmt {
+            for (key, value) in attributes {
+                self.graph.set_attribute(&from[0], key, value);
+            }
+        } else if let Some(edge) = edge_data(attributes) {
+            for (id, dep) in edges {
+                self.graph
+                    .add_edge_with(id, dep, edge.clone())
+                    .expect("operands are added to the graph");
+            }
+        }
+        Ok(())
+    }
+
+    /// node_id or subgraph, returns ids of the nodes it stands for
+    fn operand(&mut self, ids: &mut Vec<String>) -> Result<Vec<String>, DotParseError> {
+        // subgraph : [ subgraph [ ID ] ] '{' stmt_list '}'
+        if self.is_keyword(0, "subgraph") || self.peek() == Some(&Token::LeftBrace) {
+            if self.is_keyword(0, "subgraph") {
+                self.position += 1;
+                if let Some(Token::Id(_, _)) = self.peek() {
+                    self.position += 1;
+                }
+            }
+            self.expect(Token::LeftBrace)?;
+            let inner = self.stmt_list()?;
+            self.expect(Token::RightBrace)?;
+            ids.extend(inner.iter().cloned());
+            return Ok(inner);
+        }
+
+        // node_id : ID [ ':' ID [ ':' ID ] ], ports are ignored
+        let id = self.id()?;
+        for _ in 0..2 {
+            if self.peek() == Some(&Token::Colon) {
+                self.position += 1;
+                self.id()?;
+            }
+        }
+
+        if !self.graph.contains(&id) {
+            self.graph.add_node(id.clone(), ());
+        }
+        ids.push(id.clone());
+        Ok(vec![id])
+    }
+
+    /// attr_list : '[' [ a_list ] ']' [ attr_list ], returns attributes with values
+    fn attr_list(&mut self) -> Result<Vec<(String, String)>, DotParseError> {
+        let mut attributes = Vec::new();
+        while self.peek() == Some(&Token::LeftBracket) {
+            self.position += 1;
+            while self.peek() != Some(&Token::RightBracket) {
+                let key = self.id()?;
+                if self.peek() == Some(&Token::Equals) {
+                    self.position += 1;
+                    attributes.push((key, self.id()?));
+                }
+                if let Some(Token::Semicolon) | Some(Token::Comma) = self.peek() {
+                    self.position += 1;
+                }
+            }
+            self.position += 1;
+        }
+
+        Ok(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_dot_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2, 4], "cat"),
+        ];
+        assert_eq!(
+            to_dot(&domain),
+            "digraph {
+    \"1\";
+    \"2\";
+    \"3\";
+    \"1\" -> \"2\";
+    \"1\" -> \"3\";
+    \"3\" -> \"2\";
+    \"3\" -> \"4\";
+}
+"
+        );
+    }
+
+    #[test]
+    fn escapes_ids() {
+        let domain = [Node::new("say \"hi\"", vec![], ())];
+        assert_eq!(to_dot(&domain), "digraph {\n    \"say \\\"hi\\\"\";\n}\n");
+    }
+
+    #[test]
+    fn parse_works() {
+        let nodes = from_dot(
+            r#"
+            /* crafting recipes */
+            strict digraph "crafting" {
+                graph [rankdir=LR];
+                node [shape=box, color="gray"];
+                "wooden pickaxe" [label="Pickaxe"];
+                "wooden pickaxe" -> planks:e;
+                "wooden pickaxe" -> sticks // inline comment
+                sticks -> planks -> wood [color=red]
+                # preprocessor line
+                subgraph cluster_raw { wood; stone }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![
+                Node::new(
+                    String::from("wooden pickaxe"),
+                    vec![String::from("planks"), String::from("sticks")],
+                    ()
+                )
+                .with_attribute("label", "Pickaxe"),
+                Node::new(String::from("planks"), vec![String::from("wood")], ()),
+                Node::new(String::from("sticks"), vec![String::from("planks")], ()),
+                Node::new(String::from("wood"), vec![], ()),
+                Node::new(String::from("stone"), vec![], ()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_subgraph_edges() {
+        let graph = parse("digraph { a -> { b c } -> d }").unwrap();
+        assert_eq!(graph.sort(String::from("a")), Ok(vec![(), (), (), ()]));
+        assert_eq!(
+            graph.get(&String::from("b")).map(|node| node.deps.to_vec()),
+            Some(vec![String::from("d")])
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let domain = vec![
+            Node::new(String::from("say \"hi\""), vec![String::from("a\\b")], ())
+                .with_attribute("label", "Hi")
+                .with_attribute("fill color", "#fff"),
+            Node::new(String::from("a\\b"), vec![], ())
+                .with_edge(
+                    String::from("c"),
+                    Edge::new().with_label("ab").with_weight(1.5),
+                )
+                .with_edge(String::from("d"), Edge::new().with_kind("runtime")),
+            Node::new(String::from("c"), vec![], ()),
+            Node::new(String::from("d"), vec![], ()),
+        ];
+        assert_eq!(from_dot(&to_dot(&domain)).unwrap(), domain);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("graph { a -- b }").unwrap_err().to_string(),
+            "line 1: only directed graphs (digraph) are supported"
+        );
+        assert_eq!(
+            parse("digraph {\n a -> \n }").unwrap_err(),
+            DotParseError {
+                line: 3,
+                message: String::from("unexpected `}`"),
+            }
+        );
+        assert_eq!(
+            parse("digraph { a -> b").unwrap_err().message,
+            "unexpected end of input"
+        );
+        assert_eq!(
+            parse("digraph { \"a }").unwrap_err().message,
+            "unterminated string"
+        );
+    }
+
+    #[test]
+    fn renders_attributes() {
+        let domain = [
+            Node::new(1, vec![2], "hello")
+                .with_attribute("shape", "box")
+                .with_attribute("color", "green"),
+            Node::new(2, vec![], "world").with_attribute("2x", "say \"hi\""),
+        ];
+        let options = DotOptions {
+            target: Some(2),
+            cycles: false,
+        };
+        assert_eq!(
+            to_dot_with(&domain, &options),
+            "digraph {
+    \"1\" [color=\"green\", shape=\"box\"];
+    \"2\" [\"2x\"=\"say \\\"hi\\\"\", color=blue];
+    \"1\" -> \"2\";
+}
+"
+        );
+
+        let domain =
+            [Node::new(1, vec![], ())
+                .with_edge(2, Edge::new().with_label("uses").with_weight(2.0))];
+        assert_eq!(
+            to_dot(&domain),
+            "digraph {\n    \"1\";\n    \"1\" -> \"2\" [label=\"uses\", weight=2];\n}\n"
+        );
+    }
+
+    #[test]
+    fn highlights_closure_and_cycles() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![1], "dog"),
+        ];
+        let options = DotOptions {
+            target: Some(1),
+            cycles: true,
+        };
+        assert_eq!(
+            to_dot_with(&domain, &options),
+            "digraph {
+    \"1\" [color=blue];
+    \"2\" [color=red];
+    \"3\" [color=red];
+    \"4\";
+    \"1\" -> \"2\" [color=blue];
+    \"2\" -> \"3\" [color=red];
+    \"3\" -> \"2\" [color=red];
+    \"4\" -> \"1\";
+}
+"
+        );
+    }
+}
@@ -0,0 +1,563 @@
+use crate::collections::HashMap;
+use crate::TopsortError;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+
+/// dependency graph keeping a topological order of its nodes up to date as nodes and edges are added,
+/// using the Pearce–Kelly algorithm
+///
+/// adding an edge only reorders nodes between its endpoints in the current order and edges that would
+/// create a cycle are rejected
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut graph = DynamicGraph::new();
+///     graph.add_node("cat", "Garfield");
+///     graph.add_node("dog", "Odie");
+///     graph.add_edge("cat", "dog").unwrap();
+///     assert_eq!(graph.ids().collect::<Vec<_>>(), vec![&"dog", &"cat"]);
+///
+///     assert_eq!(
+///         graph.add_edge("dog", "cat"),
+///         Err(TopsortError::CyclicDependency(vec!["dog", "cat", "dog"]))
+///     );
+/// ```
+///
+/// callbacks subscribed to a node are called after every edit adding to its transitive dependencies:
+/// ```
+///     use szyk::*;
+///     use std::sync::mpsc;
+///
+///     let mut graph = DynamicGraph::new();
+///     graph.add_node("cat", "Garfield");
+///     graph.add_node("dog", "Odie");
+///     graph.add_node("mouse", "Jerry");
+///
+///     let (sender, receiver) = mpsc::channel();
+///     graph
+///         .subscribe(&"cat", move |id: &&str| sender.send(*id).unwrap())
+///         .unwrap();
+///     graph.add_edge("dog", "mouse").unwrap();
+///     graph.add_edge("cat", "dog").unwrap();
+///     assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["cat"]);
+/// ```
+#[derive(Debug)]
+pub struct DynamicGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    ids: Vec<Id>,
+    values: Vec<Item>,
+    index: HashMap<Id, usize>,
+    deps: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+    /// position of each node in the topological order
+    ord: Vec<usize>,
+    /// nodes in topological order
+    order: Vec<usize>,
+    /// scratch space of searches, always all `false` between calls
+    visited: Vec<bool>,
+    subscribers: Vec<Subscriber<Id>>,
+    /// id of the next subscription
+    next_subscription: usize,
+}
+
+/// handle of a callback subscribed with [`DynamicGraph::subscribe`], used to unsubscribe it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription(usize);
+
+struct Subscriber<Id> {
+    subscription: Subscription,
+    /// index of the watched node
+    index: usize,
+    cb: Box<dyn FnMut(&Id) + Send + Sync>,
+}
+
+impl<Id> fmt::Debug for Subscriber<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("subscription", &self.subscription)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Id, Item> DynamicGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            ids: Vec::new(),
+            values: Vec::new(),
+            index: HashMap::new(),
+            deps: Vec::new(),
+            dependents: Vec::new(),
+            ord: Vec::new(),
+            order: Vec::new(),
+            visited: Vec::new(),
+            subscribers: Vec::new(),
+            next_subscription: 0,
+        }
+    }
+
+    /// adds a node without dependencies at the end of the order
+    ///
+    /// if a node with the same `id` already exists its value is replaced and returned
+    pub fn add_node(&mut self, id: Id, value: Item) -> Option<Item> {
+        if let Some(&index) = self.index.get(&id) {
+            return Some(core::mem::replace(&mut self.values[index], value));
+        }
+
+        let index = self.ids.len();
+        self.index.insert(id.clone(), index);
+        self.ids.push(id);
+        self.values.push(value);
+        self.deps.push(Vec::new());
+        self.dependents.push(Vec::new());
+        self.ord.push(self.order.len());
+        self.order.push(index);
+        self.visited.push(false);
+        None
+    }
+
+    /// makes `from` depend on `to`, moving nodes as needed to keep the order valid
+    ///
+    /// both nodes have to be in the graph, an edge that would create a cycle is rejected with
+    /// [`TopsortError::CyclicDependency`], or [`TopsortError::SelfDependency`] for a self-loop,
+    /// and leaves the graph unchanged
+    pub fn add_edge(&mut self, from: Id, to: Id) -> Result<(), TopsortError<Id>> {
+        let from = self.find_index(from)?;
+        let to = self.find_index(to)?;
+
+        if self.deps[from].contains(&to) {
+            return Ok(());
+        }
+
+        if from == to {
+            return Err(TopsortError::SelfDependency(self.ids[from].clone()));
+        }
+
+        // watched nodes which don't depend on `to` yet, actually notified only if the edge is added
+        let mut changed = if self.subscribers.is_empty() {
+            Vec::new()
+        } else {
+            self.newly_depending(from, to)
+        };
+
+        // the dependency has to come first, only nodes between the two positions can be affected
+        if self.ord[to] > self.ord[from] {
+            let forward = self.search_dependents(from, to)?;
+            let backward = self.search_deps(to, self.ord[from]);
+            self.reorder(backward, forward);
+        }
+
+        self.deps[from].push(to);
+        self.dependents[to].push(from);
+
+        changed.sort_by_key(|&index| self.ord[index]);
+        for index in changed {
+            let id = &self.ids[index];
+            for subscriber in self.subscribers.iter_mut() {
+                if subscriber.index == index {
+                    (subscriber.cb)(id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// calls `cb` with `id` after every edit changing transitive dependencies of the node with id of
+    /// `id`, which has to be in the graph
+    ///
+    /// callbacks run once per edit after the graph is updated, in topological order of their nodes and
+    /// in the order they were subscribed for the same node
+    pub fn subscribe<F>(&mut self, id: &Id, cb: F) -> Result<Subscription, TopsortError<Id>>
+    where
+        F: FnMut(&Id) + Send + Sync + 'static,
+    {
+        let index = self.find_index(id.clone())?;
+        let subscription = Subscription(self.next_subscription);
+        self.next_subscription += 1;
+        self.subscribers.push(Subscriber {
+            subscription,
+            index,
+            cb: Box::new(cb),
+        });
+        Ok(subscription)
+    }
+
+    /// removes a callback added with [`subscribe`](Self::subscribe), returns `false` if it was already
+    /// removed
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers
+            .retain(|subscriber| subscriber.subscription != subscription);
+        self.subscribers.len() != len
+    }
+
+    /// returns ids in topological order
+    pub fn ids(&self) -> impl Iterator<Item = &Id> {
+        self.order.iter().map(move |&index| &self.ids[index])
+    }
+
+    /// returns ids and values in topological order
+    pub fn iter(&self) -> impl Iterator<Item = (&Id, &Item)> {
+        self.order
+            .iter()
+            .map(move |&index| (&self.ids[index], &self.values[index]))
+    }
+
+    /// position of the node with id of `id` in the current topological order
+    pub fn position<Q>(&self, id: &Q) -> Option<usize>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(id).map(|&index| self.ord[index])
+    }
+
+    pub fn get<Q>(&self, id: &Q) -> Option<&Item>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(id).map(|&index| &self.values[index])
+    }
+
+    /// ids of direct dependencies of the node with id of `id`
+    pub fn deps<Q>(&self, id: &Q) -> Option<Vec<&Id>>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index
+            .get(id)
+            .map(|&index| self.deps[index].iter().map(|&dep| &self.ids[dep]).collect())
+    }
+
+    pub fn contains<Q>(&self, id: &Q) -> bool
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    fn find_index(&self, id: Id) -> Result<usize, TopsortError<Id>> {
+        match self.index.get(&id) {
+            Some(&index) => Ok(index),
+            None => Err(TopsortError::TargetNotFound(id)),
+        }
+    }
+
+    /// collects nodes depending on `start` which are ordered no later than `to`, failing if `to`
+    /// is among them since `to` depending on `start` closes a cycle
+    fn search_dependents(
+        &mut self,
+        start: usize,
+        to: usize,
+    ) -> Result<Vec<usize>, TopsortError<Id>> {
+        let upper = self.ord[to];
+        let mut found = vec![start];
+        let mut parent = HashMap::new();
+        let mut stack = vec![start];
+        self.visited[start] = true;
+
+        while let Some(index) = stack.pop() {
+            for &dependent in self.dependents[index].iter() {
+                if dependent == to {
+                    parent.insert(to, index);
+                    let cycle = self.cycle(start, to, &parent);
+                    self.clear(&found);
+                    return Err(TopsortError::CyclicDependency(cycle));
+                }
+
+                if !self.visited[dependent] && self.ord[dependent] < upper {
+                    self.visited[dependent] = true;
+                    parent.insert(dependent, index);
+                    found.push(dependent);
+                    stack.push(dependent);
+                }
+            }
+        }
+
+        self.clear(&found);
+        Ok(found)
+    }
+
+    /// collects dependencies of `start` which are ordered after `lower`
+    fn search_deps(&mut self, start: usize, lower: usize) -> Vec<usize> {
+        let mut found = vec![start];
+        let mut stack = vec![start];
+        self.visited[start] = true;
+
+        while let Some(index) = stack.pop() {
+            for &dep in self.deps[index].iter() {
+                if !self.visited[dep] && self.ord[dep] > lower {
+                    self.visited[dep] = true;
+                    found.push(dep);
+                    stack.push(dep);
+                }
+            }
+        }
+
+        self.clear(&found);
+        found
+    }
+
+    /// watched nodes that will transitively depend on `to` once `from` depends on it but don't yet:
+    /// `from` and its dependents, except `to` and its dependents
+    fn newly_depending(&mut self, from: usize, to: usize) -> Vec<usize> {
+        let already = self.collect_dependents(to);
+        if already.contains(&from) {
+            return Vec::new();
+        }
+        for &index in already.iter() {
+            self.visited[index] = true;
+        }
+        let mut changed = Vec::new();
+        let mut stack = vec![from];
+        let mut found = vec![from];
+        self.visited[from] = true;
+        while let Some(index) = stack.pop() {
+            if self
+                .subscribers
+                .iter()
+                .any(|subscriber| subscriber.index == index)
+            {
+                changed.push(index);
+            }
+            for &dependent in self.dependents[index].iter() {
+                if !self.visited[dependent] {
+                    self.visited[dependent] = true;
+                    found.push(dependent);
+                    stack.push(dependent);
+                }
+            }
+        }
+        self.clear(&already);
+        self.clear(&found);
+        changed
+    }
+
+    /// `start` and every node depending on it, directly or not
+    fn collect_dependents(&mut self, start: usize) -> Vec<usize> {
+        let mut found = vec![start];
+        let mut stack = vec![start];
+        self.visited[start] = true;
+
+        while let Some(index) = stack.pop() {
+            for &dependent in self.dependents[index].iter() {
+                if !self.visited[dependent] {
+                    self.visited[dependent] = true;
+                    found.push(dependent);
+                    stack.push(dependent);
+                }
+            }
+        }
+
+        self.clear(&found);
+        found
+    }
+
+    fn clear(&mut self, indices: &[usize]) {
+        for &index in indices {
+            self.visited[index] = false;
+        }
+    }
+
+    /// cycle created by making `from` depend on `to` when `to` depends on `from` through `parent` links,
+    /// in dependency order starting and ending on `from`
+    fn cycle(&self, from: usize, to: usize, parent: &HashMap<usize, usize>) -> Vec<Id> {
+        let mut cycle = vec![self.ids[from].clone()];
+        let mut index = to;
+        while index != from {
+            cycle.push(self.ids[index].clone());
+            index = parent[&index];
+        }
+        cycle.push(self.ids[from].clone());
+        cycle
+    }
+
+    /// moves `backward` nodes (dependencies) before `forward` nodes (dependents), reusing their positions
+    fn reorder(&mut self, mut backward: Vec<usize>, mut forward: Vec<usize>) {
+        backward.sort_by_key(|&index| self.ord[index]);
+        forward.sort_by_key(|&index| self.ord[index]);
+
+        let mut slots: Vec<usize> = backward
+            .iter()
+            .chain(forward.iter())
+            .map(|&index| self.ord[index])
+            .collect();
+        slots.sort_unstable();
+
+        for (index, slot) in backward.into_iter().chain(forward).zip(slots) {
+            self.ord[index] = slot;
+            self.order[slot] = index;
+        }
+    }
+}
+
+impl<Id, Item> Default for DynamicGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid(graph: &DynamicGraph<i32, ()>) {
+        for (index, deps) in graph.deps.iter().enumerate() {
+            for &dep in deps {
+                assert!(graph.ord[dep] < graph.ord[index]);
+            }
+        }
+        for (position, &index) in graph.order.iter().enumerate() {
+            assert_eq!(graph.ord[index], position);
+        }
+    }
+
+    #[test]
+    fn keeps_order_valid() {
+        let mut graph = DynamicGraph::new();
+        for id in 0..8 {
+            graph.add_node(id, ());
+        }
+        // every edge goes against the insertion order
+        for (from, to) in [
+            (0, 7),
+            (1, 6),
+            (0, 1),
+            (6, 5),
+            (5, 4),
+            (7, 3),
+            (3, 2),
+            (2, 4),
+        ] {
+            graph.add_edge(from, to).unwrap();
+            assert_valid(&graph);
+        }
+        assert_eq!(graph.position(&4), Some(0));
+        assert_eq!(graph.position(&0), Some(7));
+    }
+
+    #[test]
+    fn pseudo_random_insertions() {
+        let mut graph = DynamicGraph::new();
+        for id in 0..30 {
+            graph.add_node(id, ());
+        }
+        let mut seed: u64 = 42;
+        for _ in 0..300 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let from = (seed >> 33) as i32 % 30;
+            let to = (seed >> 45) as i32 % 30;
+            match graph.add_edge(from, to) {
+                Ok(()) => {}
+                Err(TopsortError::CyclicDependency(cycle)) => {
+                    assert_eq!(cycle.first(), Some(&from));
+                    assert_eq!(cycle.last(), Some(&from));
+                }
+                Err(TopsortError::SelfDependency(id)) => assert_eq!((id, id), (from, to)),
+                Err(err) => panic!("unexpected {:?}", err),
+            }
+            assert_valid(&graph);
+        }
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let mut graph = DynamicGraph::new();
+        for id in 1..=4 {
+            graph.add_node(id, ());
+        }
+        graph.add_edge(1, 2).unwrap();
+        graph.add_edge(2, 3).unwrap();
+        graph.add_edge(3, 4).unwrap();
+        let order: Vec<_> = graph.ids().copied().collect();
+
+        assert_eq!(
+            graph.add_edge(4, 2),
+            Err(TopsortError::CyclicDependency(vec![4, 2, 3, 4]))
+        );
+        assert_eq!(graph.add_edge(3, 3), Err(TopsortError::SelfDependency(3)));
+        assert_eq!(graph.ids().copied().collect::<Vec<_>>(), order);
+        assert_eq!(graph.deps(&4), Some(vec![]));
+        assert_valid(&graph);
+    }
+
+    #[test]
+    fn subscriptions() {
+        use std::sync::{Arc, Mutex};
+
+        let mut graph = DynamicGraph::new();
+        for id in 1..=5 {
+            graph.add_node(id, ());
+        }
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut subscriptions = Vec::new();
+        for id in 1..=5 {
+            let calls = Arc::clone(&calls);
+            subscriptions.push(
+                graph
+                    .subscribe(&id, move |id| calls.lock().unwrap().push(*id))
+                    .unwrap(),
+            );
+        }
+        let take = || core::mem::take(&mut *calls.lock().unwrap());
+
+        graph.add_edge(1, 2).unwrap();
+        assert_eq!(take(), vec![1]);
+        graph.add_edge(2, 3).unwrap();
+        assert_eq!(take(), vec![2, 1]);
+        // 1 already depends on 3
+        graph.add_edge(1, 3).unwrap();
+        assert_eq!(take(), Vec::<i32>::new());
+        graph.add_edge(4, 1).unwrap();
+        assert_eq!(take(), vec![4]);
+        graph.add_edge(4, 1).unwrap();
+        assert_eq!(take(), Vec::<i32>::new());
+
+        // rejected edges change nothing
+        assert!(graph.add_edge(3, 4).is_err());
+        assert_eq!(take(), Vec::<i32>::new());
+
+        assert!(graph.unsubscribe(subscriptions[0]));
+        assert!(!graph.unsubscribe(subscriptions[0]));
+        graph.add_edge(3, 5).unwrap();
+        assert_eq!(take(), vec![3, 2, 4]);
+
+        assert_eq!(
+            graph.subscribe(&6, |_| {}),
+            Err(TopsortError::TargetNotFound(6))
+        );
+    }
+
+    #[test]
+    fn missing_nodes() {
+        let mut graph = DynamicGraph::new();
+        graph.add_node(1, ());
+        assert_eq!(graph.add_edge(1, 2), Err(TopsortError::TargetNotFound(2)));
+        assert_eq!(graph.add_edge(3, 1), Err(TopsortError::TargetNotFound(3)));
+    }
+}
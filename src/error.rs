@@ -0,0 +1,276 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TopsortError<Id> {
+    /// * `Id` - target that wasn't found
+    TargetNotFound(Id),
+    /// * `Vec<Id>` - ids forming the cycle in dependency order, starting and ending on the same id
+    CyclicDependency(Vec<Id>),
+    /// * `Id` - node listing itself in its dependencies
+    SelfDependency(Id),
+    /// * `Vec<Id>` - ids of all nodes involved in cycles, in domain order
+    CyclicNodes(Vec<Id>),
+    /// * `Id` - id shared by more than one node in the domain
+    DuplicateId(Id),
+    /// * `Vec<(Id, Id)>` - pairs of a node and its missing dependency, in domain order
+    MissingDependencies(Vec<(Id, Id)>),
+    /// * `(Id, Id)` - node listed in an order before its dependency, or without it
+    UnorderedDependency(Id, Id),
+    /// the sort was stopped by its cancellation flag
+    Cancelled,
+    /// * `Id` - first node found deeper than the depth limit of the sort
+    DepthLimitExceeded(Id),
+}
+
+impl<Id> fmt::Display for TopsortError<Id>
+where
+    Id: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopsortError::TargetNotFound(id) => write!(f, "target `{}` not found", id),
+            TopsortError::CyclicDependency(cycle) => {
+                write!(f, "cyclic dependency: ")?;
+                write_ids(f, cycle, " -> ")
+            }
+            TopsortError::SelfDependency(id) => write!(f, "`{}` depends on itself", id),
+            TopsortError::CyclicNodes(ids) => {
+                write!(f, "cyclic dependency between ")?;
+                write_ids(f, ids, ", ")
+            }
+            TopsortError::DuplicateId(id) => write!(f, "duplicate id `{}`", id),
+            TopsortError::MissingDependencies(missing) => {
+                write!(f, "missing dependencies: ")?;
+                for (position, (id, dep)) in missing.iter().enumerate() {
+                    if position > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "`{}` -> `{}`", id, dep)?;
+                }
+                Ok(())
+            }
+            TopsortError::UnorderedDependency(id, dep) => {
+                write!(f, "`{}` is ordered before its dependency `{}`", id, dep)
+            }
+            TopsortError::Cancelled => write!(f, "sort was cancelled"),
+            TopsortError::DepthLimitExceeded(id) => write!(f, "`{}` exceeds the depth limit", id),
+        }
+    }
+}
+
+fn write_ids<Id>(f: &mut fmt::Formatter<'_>, ids: &[Id], separator: &str) -> fmt::Result
+where
+    Id: fmt::Display,
+{
+    for (position, id) in ids.iter().enumerate() {
+        if position > 0 {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "`{}`", id)?;
+    }
+    Ok(())
+}
+
+impl<Id> core::error::Error for TopsortError<Id> where Id: fmt::Debug + fmt::Display {}
+
+/// error of a sort driven by a fallible callback, see [`crate::sort_try_cb`]
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrySortError<Id, E> {
+    /// * `TopsortError<Id>` - the sort itself failed
+    Sort(TopsortError<Id>),
+    /// * `E` - error returned by the callback
+    Callback(E),
+}
+
+impl<Id, E> From<TopsortError<Id>> for TrySortError<Id, E> {
+    fn from(err: TopsortError<Id>) -> Self {
+        TrySortError::Sort(err)
+    }
+}
+
+impl<Id, E> fmt::Display for TrySortError<Id, E>
+where
+    Id: fmt::Display,
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySortError::Sort(err) => err.fmt(f),
+            TrySortError::Callback(err) => write!(f, "callback failed: {}", err),
+        }
+    }
+}
+
+impl<Id, E> core::error::Error for TrySortError<Id, E>
+where
+    Id: fmt::Debug + fmt::Display + 'static,
+    E: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            TrySortError::Sort(err) => Some(err),
+            TrySortError::Callback(err) => Some(err),
+        }
+    }
+}
+
+/// error of a sort together with the path that led to it, see [`crate::sort_detailed`]
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetailedError<Id> {
+    pub error: TopsortError<Id>,
+    /// ids from the node the sort started from to the node that ran into the error, empty if the sort
+    /// failed before visiting any node
+    pub path: Vec<Id>,
+}
+
+impl<Id> DetailedError<Id> {
+    /// node whose dependency is missing or closes a cycle, the last one on the path
+    pub fn requested_by(&self) -> Option<&Id> {
+        self.path.last()
+    }
+}
+
+impl<Id> From<TopsortError<Id>> for DetailedError<Id> {
+    fn from(error: TopsortError<Id>) -> Self {
+        Self {
+            error,
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<Id> fmt::Display for DetailedError<Id>
+where
+    Id: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)?;
+        if let Some(id) = self.requested_by() {
+            write!(f, ", required by `{}` through ", id)?;
+            write_ids(f, &self.path, " -> ")?;
+        }
+        Ok(())
+    }
+}
+
+impl<Id> core::error::Error for DetailedError<Id>
+where
+    Id: fmt::Debug + fmt::Display + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// problem found by a lenient sort which didn't stop it, see [`crate::sort_lenient`]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Warning<Id> {
+    /// * `(Id, Id)` - node and its dependency missing from the domain, treated as a leaf
+    MissingDependency(Id, Id),
+    /// * `(Id, Id)` - node and its optional dependency missing from the domain
+    MissingOptionalDependency(Id, Id),
+}
+
+impl<Id> fmt::Display for Warning<Id>
+where
+    Id: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::MissingDependency(id, dep) => {
+                write!(f, "`{}` depends on missing `{}`", id, dep)
+            }
+            Warning::MissingOptionalDependency(id, dep) => {
+                write!(f, "`{}` optionally depends on missing `{}`", id, dep)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            TopsortError::TargetNotFound("wood").to_string(),
+            "target `wood` not found"
+        );
+        assert_eq!(
+            TopsortError::CyclicDependency(vec![1, 2, 1]).to_string(),
+            "cyclic dependency: `1` -> `2` -> `1`"
+        );
+        assert_eq!(
+            TopsortError::SelfDependency(1).to_string(),
+            "`1` depends on itself"
+        );
+        assert_eq!(
+            TopsortError::CyclicNodes(vec![1, 2]).to_string(),
+            "cyclic dependency between `1`, `2`"
+        );
+        assert_eq!(
+            TopsortError::DuplicateId("wood").to_string(),
+            "duplicate id `wood`"
+        );
+        assert_eq!(
+            TopsortError::MissingDependencies(vec![("pickaxe", "sticks"), ("planks", "wood")])
+                .to_string(),
+            "missing dependencies: `pickaxe` -> `sticks`, `planks` -> `wood`"
+        );
+        assert_eq!(
+            TopsortError::UnorderedDependency(1, 2).to_string(),
+            "`1` is ordered before its dependency `2`"
+        );
+    }
+
+    #[test]
+    fn warning_display() {
+        assert_eq!(
+            Warning::MissingDependency("planks", "wood").to_string(),
+            "`planks` depends on missing `wood`"
+        );
+        assert_eq!(
+            Warning::MissingOptionalDependency("pickaxe", "gloves").to_string(),
+            "`pickaxe` optionally depends on missing `gloves`"
+        );
+    }
+
+    #[test]
+    fn try_sort_error_display() {
+        let err: TrySortError<i32, std::fmt::Error> = TopsortError::TargetNotFound(4).into();
+        assert_eq!(err.to_string(), "target `4` not found");
+        assert_eq!(
+            TrySortError::<i32, _>::Callback(std::fmt::Error).to_string(),
+            "callback failed: an error occurred when formatting an argument"
+        );
+    }
+
+    #[test]
+    fn detailed_error_display() {
+        let err = DetailedError {
+            error: TopsortError::TargetNotFound(4),
+            path: vec![1, 2, 3],
+        };
+        assert_eq!(err.requested_by(), Some(&3));
+        assert_eq!(
+            err.to_string(),
+            "target `4` not found, required by `3` through `1` -> `2` -> `3`"
+        );
+        let err: DetailedError<i32> = TopsortError::TargetNotFound(4).into();
+        assert_eq!(err.to_string(), "target `4` not found");
+    }
+
+    #[test]
+    fn error_boxes_into_dyn_error() {
+        fn run() -> Result<Vec<&'static str>, Box<dyn std::error::Error>> {
+            Ok(crate::sort(&[crate::Node::new(1, vec![2], "hello")], 1)?)
+        }
+        assert_eq!(run().unwrap_err().to_string(), "target `2` not found");
+    }
+}
@@ -0,0 +1,573 @@
+//! Execution of tasks in dependency order
+//!
+//! every node becomes a task which is started as soon as tasks of all of its dependencies have
+//! finished; `run` runs tasks on a rayon thread pool (`rayon` feature), `run_async` awaits
+//! futures concurrently on any executor (`futures` feature); tasks get whole nodes, so they can read
+//! per-node settings from [`Node::attributes`]
+
+use crate::traversal::Traversal;
+use crate::{Node, TopsortError};
+use std::fmt;
+use std::hash::Hash;
+#[cfg(feature = "rayon")]
+use std::sync::Mutex;
+
+/// error of running tasks in dependency order
+#[derive(PartialEq, Debug)]
+pub enum ExecError<Id, E> {
+    /// * `TopsortError<Id>` - the tasks couldn't be ordered, no task was started
+    Sort(TopsortError<Id>),
+    /// * `Id` - node whose task failed first
+    /// * `E` - error returned by the task
+    Task(Id, E),
+}
+
+impl<Id, E> From<TopsortError<Id>> for ExecError<Id, E> {
+    fn from(err: TopsortError<Id>) -> Self {
+        ExecError::Sort(err)
+    }
+}
+
+impl<Id, E> fmt::Display for ExecError<Id, E>
+where
+    Id: fmt::Display,
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecError::Sort(err) => err.fmt(f),
+            ExecError::Task(id, err) => write!(f, "task `{}` failed: {}", id, err),
+        }
+    }
+}
+
+impl<Id, E> std::error::Error for ExecError<Id, E>
+where
+    Id: fmt::Debug + fmt::Display + 'static,
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExecError::Sort(err) => Some(err),
+            ExecError::Task(_, err) => Some(err),
+        }
+    }
+}
+
+/// tasks to run for a target, see [`plan`]
+struct Plan {
+    /// positions of nodes in the dependency closure of the target, in topological order
+    order: Vec<usize>,
+    /// number of dependencies of each node whose tasks haven't finished yet
+    pending: Vec<usize>,
+    /// positions of nodes depending on each node, once per listed dependency
+    dependents: Vec<Vec<usize>>,
+}
+
+fn plan<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> Result<Plan, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut order = Vec::new();
+    traversal.visit(index, &mut |index| order.push(index))?;
+
+    let mut pending = vec![0; domain.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); domain.len()];
+    for &index in order.iter() {
+        for dep in traversal.dep_indices(index) {
+            pending[index] += 1;
+            dependents[dep?].push(index);
+        }
+    }
+
+    Ok(Plan {
+        order,
+        pending,
+        dependents,
+    })
+}
+
+/// pairs `results` of nodes with their ids, in topological order
+fn collect<Id, Item, T>(
+    domain: &[Node<Id, Item>],
+    order: Vec<usize>,
+    mut results: Vec<Option<T>>,
+) -> Vec<(Id, T)>
+where
+    Id: Clone + Eq + Hash,
+{
+    order
+        .into_iter()
+        .map(|index| {
+            let result = results[index]
+                .take()
+                .expect("every task finished successfully");
+            (domain[index].id.clone(), result)
+        })
+        .collect()
+}
+
+/// runs `task` for the node with id of `target` and all of its dependencies on the current rayon
+/// thread pool, starting each task once tasks of all its dependencies succeeded
+///
+/// returns results paired with ids in topological order; after the first failure no new tasks are
+/// started, tasks already running are waited for and the failure is returned
+///
+/// # Examples
+/// ```
+///     use szyk::Node;
+///     use szyk::exec;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], 1),
+///         Node::new("planks", vec!["wood"], 4),
+///         Node::new("sticks", vec!["planks"], 4),
+///         Node::new("wood", vec![], 1),
+///     ];
+///     let result = exec::run(&domain, "wooden pickaxe", |node| -> Result<_, ()> {
+///         Ok(node.value * 10)
+///     });
+///     assert_eq!(
+///         result,
+///         Ok(vec![("wood", 10), ("planks", 40), ("sticks", 40), ("wooden pickaxe", 10)])
+///     );
+/// ```
+#[cfg(feature = "rayon")]
+pub fn run<Id, Item, T, E, F>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    task: F,
+) -> Result<Vec<(Id, T)>, ExecError<Id, E>>
+where
+    Id: Clone + Eq + Hash + Sync,
+    Item: Sync,
+    T: Send,
+    E: Send,
+    F: Fn(&Node<Id, Item>) -> Result<T, E> + Sync,
+{
+    let Plan {
+        order,
+        pending,
+        dependents,
+    } = plan(domain, target)?;
+
+    // collected up front, spawned tasks lower `pending` while the scope below is still spawning
+    let ready: Vec<usize> = order
+        .iter()
+        .copied()
+        .filter(|&index| pending[index] == 0)
+        .collect();
+    let context = Context {
+        domain,
+        dependents,
+        task,
+        state: Mutex::new(State {
+            results: (0..domain.len()).map(|_| None).collect(),
+            failure: None,
+            pending,
+        }),
+    };
+    rayon::scope(|scope| {
+        for index in ready {
+            spawn(scope, &context, index);
+        }
+    });
+
+    let state = context.state.into_inner().unwrap();
+    if let Some((index, err)) = state.failure {
+        return Err(ExecError::Task(domain[index].id.clone(), err));
+    }
+
+    Ok(collect(domain, order, state.results))
+}
+
+/// same as [`run`], but uses `pool` instead of the current thread pool
+///
+/// # Examples
+/// ```
+///     use szyk::Node;
+///     use szyk::exec;
+///
+///     let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     let result = exec::run_in(&pool, &domain, "cat", |node| {
+///         if node.id == "cat" {
+///             Err("no cats allowed")
+///         } else {
+///             Ok(())
+///         }
+///     });
+///     assert_eq!(result, Err(exec::ExecError::Task("cat", "no cats allowed")));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn run_in<Id, Item, T, E, F>(
+    pool: &rayon::ThreadPool,
+    domain: &[Node<Id, Item>],
+    target: Id,
+    task: F,
+) -> Result<Vec<(Id, T)>, ExecError<Id, E>>
+where
+    Id: Clone + Eq + Hash + Send + Sync,
+    Item: Sync,
+    T: Send,
+    E: Send,
+    F: Fn(&Node<Id, Item>) -> Result<T, E> + Sync + Send,
+{
+    pool.install(|| run(domain, target, task))
+}
+
+#[cfg(feature = "rayon")]
+struct Context<'a, Id, Item, T, E, F>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    dependents: Vec<Vec<usize>>,
+    task: F,
+    state: Mutex<State<T, E>>,
+}
+
+#[cfg(feature = "rayon")]
+struct State<T, E> {
+    results: Vec<Option<T>>,
+    /// position of a node whose task failed first, together with its error
+    failure: Option<(usize, E)>,
+    pending: Vec<usize>,
+}
+
+#[cfg(feature = "rayon")]
+fn spawn<'s, Id, Item, T, E, F>(
+    scope: &rayon::Scope<'s>,
+    context: &'s Context<'s, Id, Item, T, E, F>,
+    index: usize,
+) where
+    Id: Clone + Eq + Hash + Sync,
+    Item: Sync,
+    T: Send,
+    E: Send,
+    F: Fn(&Node<Id, Item>) -> Result<T, E> + Sync,
+{
+    scope.spawn(move |scope| {
+        if context.state.lock().unwrap().failure.is_some() {
+            return;
+        }
+
+        let result = (context.task)(&context.domain[index]);
+
+        let mut ready = Vec::new();
+        {
+            let mut state = context.state.lock().unwrap();
+            match result {
+                Ok(value) => {
+                    state.results[index] = Some(value);
+                    for &dependent in context.dependents[index].iter() {
+                        state.pending[dependent] -= 1;
+                        if state.pending[dependent] == 0 {
+                            ready.push(dependent);
+                        }
+                    }
+                }
+                Err(err) => {
+                    if state.failure.is_none() {
+                        state.failure = Some((index, err));
+                    }
+                    return;
+                }
+            }
+        }
+
+        for dependent in ready {
+            spawn(scope, context, dependent);
+        }
+    });
+}
+
+/// awaits `task` for the node with id of `target` and all of its dependencies, starting each task once
+/// tasks of all its dependencies succeeded, with at most `limit` tasks (but at least one) in flight
+///
+/// tasks are polled concurrently by the returned future, so it works on any executor and tasks don't
+/// have to be `Send`; results are paired with ids in topological order, on the first failure tasks still
+/// in flight are dropped and the failure is returned
+///
+/// # Examples
+/// ```
+///     use szyk::Node;
+///     use szyk::exec;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     let result = futures::executor::block_on(exec::run_async(&domain, "cat", 4, |node| {
+///         let name = node.value;
+///         async move { Ok::<_, ()>(name.len()) }
+///     }));
+///     assert_eq!(result, Ok(vec![("dog", 4), ("cat", 8)]));
+/// ```
+#[cfg(feature = "futures")]
+pub async fn run_async<Id, Item, T, E, F, Fut>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    limit: usize,
+    mut task: F,
+) -> Result<Vec<(Id, T)>, ExecError<Id, E>>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>) -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    use futures::stream::{FuturesUnordered, StreamExt};
+    use std::collections::VecDeque;
+
+    let Plan {
+        order,
+        mut pending,
+        dependents,
+    } = plan(domain, target)?;
+
+    let mut ready: VecDeque<usize> = order
+        .iter()
+        .copied()
+        .filter(|&index| pending[index] == 0)
+        .collect();
+    let mut results: Vec<Option<T>> = (0..domain.len()).map(|_| None).collect();
+    let mut in_flight = FuturesUnordered::new();
+
+    loop {
+        while in_flight.len() < limit.max(1) {
+            let index = match ready.pop_front() {
+                Some(index) => index,
+                None => break,
+            };
+            let future = task(&domain[index]);
+            in_flight.push(async move { (index, future.await) });
+        }
+
+        let (index, result) = match in_flight.next().await {
+            Some(finished) => finished,
+            None => break,
+        };
+        match result {
+            Ok(value) => {
+                results[index] = Some(value);
+                for &dependent in dependents[index].iter() {
+                    pending[dependent] -= 1;
+                    if pending[dependent] == 0 {
+                        ready.push_back(dependent);
+                    }
+                }
+            }
+            Err(err) => return Err(ExecError::Task(domain[index].id.clone(), err)),
+        }
+    }
+
+    Ok(collect(domain, order, results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "rayon")]
+    use std::sync::Barrier;
+
+    #[cfg(feature = "rayon")]
+    fn pool(threads: usize) -> rayon::ThreadPool {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn tasks_read_attributes() {
+        let domain = [
+            Node::new("app", vec!["lib"], ()).with_attribute("command", "cargo build"),
+            Node::new("lib", vec![], ()).with_attribute("command", "cargo check"),
+        ];
+        let result = run_in(&pool(2), &domain, "app", |node| -> Result<_, ()> {
+            Ok(node.attributes["command"].clone())
+        });
+        assert_eq!(
+            result,
+            Ok(vec![
+                ("lib", String::from("cargo check")),
+                ("app", String::from("cargo build")),
+            ])
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn respects_dependencies() {
+        let domain: Vec<_> = (0..50)
+            .map(|i| Node::new(i, (0..i).filter(|dep| i % (dep + 1) == 0).collect(), i))
+            .collect();
+        let finished: Vec<_> = (0..50).map(|_| AtomicUsize::new(0)).collect();
+        let clock = AtomicUsize::new(1);
+
+        let result = run_in(&pool(4), &domain, 49, |node| -> Result<_, ()> {
+            for dep in node.deps.iter() {
+                assert_ne!(finished[*dep].load(Ordering::SeqCst), 0);
+            }
+            finished[node.id].store(clock.fetch_add(1, Ordering::SeqCst), Ordering::SeqCst);
+            Ok(node.value * 2)
+        });
+
+        let expected: Vec<_> = crate::sort(&domain, 49)
+            .unwrap()
+            .into_iter()
+            .map(|i| (i, i * 2))
+            .collect();
+        assert_eq!(result, Ok(expected));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn runs_each_task_once() {
+        // leaves are ordered before their dependents, so they finish while the loop over the initial
+        // ready nodes is still running
+        let leaves = 5000;
+        let mut domain: Vec<_> = (0..leaves).map(|i| Node::new(i, vec![], ())).collect();
+        domain.extend((0..leaves).map(|i| Node::new(leaves + i, vec![i], ())));
+        domain.push(Node::new(2 * leaves, (0..2 * leaves).collect(), ()));
+        let calls: Vec<_> = domain.iter().map(|_| AtomicUsize::new(0)).collect();
+
+        let result = run_in(&pool(8), &domain, 2 * leaves, |node| -> Result<_, ()> {
+            calls[node.id].fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+        assert_eq!(result.map(|results| results.len()), Ok(domain.len()));
+        for count in calls.iter() {
+            assert_eq!(count.load(Ordering::SeqCst), 1);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn runs_independent_tasks_in_parallel() {
+        let domain = [
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![], ()),
+            Node::new(3, vec![], ()),
+        ];
+        // both leaves have to be running at the same time to get past the barrier
+        let barrier = Barrier::new(2);
+        let result = run_in(&pool(2), &domain, 1, |node| -> Result<_, ()> {
+            if node.id != 1 {
+                barrier.wait();
+            }
+            Ok(node.id)
+        });
+        assert_eq!(result, Ok(vec![(2, 2), (3, 3), (1, 1)]));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn stops_on_failure() {
+        let domain = [
+            Node::new(1, vec![2], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![], ()),
+        ];
+        let started = AtomicUsize::new(0);
+        let result = run_in(&pool(2), &domain, 1, |node| {
+            started.fetch_add(1, Ordering::SeqCst);
+            if node.id == 2 {
+                Err("broken")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err(ExecError::Task(2, "broken")));
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn sort_error_runs_nothing() {
+        let domain = [Node::new(1, vec![2], ()), Node::new(2, vec![1], ())];
+        let result = run(&domain, 1, |_| -> Result<(), ()> {
+            panic!("no task should run")
+        });
+        assert_eq!(
+            result,
+            Err(ExecError::Sort(TopsortError::CyclicDependency(vec![
+                1, 2, 1
+            ])))
+        );
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn run_async_respects_limit() {
+        use std::cell::Cell;
+
+        let domain: Vec<_> = (0..10)
+            .map(|i| Node::new(i, if i == 9 { (0..9).collect() } else { vec![] }, i))
+            .collect();
+        let running = Cell::new(0);
+        let max_running = Cell::new(0);
+
+        let result = futures::executor::block_on(run_async(&domain, 9, 3, |node| {
+            let value = node.value;
+            let (running, max_running) = (&running, &max_running);
+            async move {
+                running.set(running.get() + 1);
+                max_running.set(max_running.get().max(running.get()));
+                // let other tasks start before finishing
+                pending_once().await;
+                running.set(running.get() - 1);
+                Ok::<_, ()>(value * 2)
+            }
+        }));
+
+        assert_eq!(result, Ok((0..10).map(|i| (i, i * 2)).collect::<Vec<_>>()));
+        assert_eq!(max_running.get(), 3);
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn run_async_stops_on_failure() {
+        let domain = [
+            Node::new(1, vec![2], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![], ()),
+        ];
+        let started = AtomicUsize::new(0);
+        let result = futures::executor::block_on(run_async(&domain, 1, 2, |node| {
+            started.fetch_add(1, Ordering::SeqCst);
+            let id = node.id;
+            async move {
+                if id == 2 {
+                    Err("broken")
+                } else {
+                    Ok(())
+                }
+            }
+        }));
+        assert_eq!(result, Err(ExecError::Task(2, "broken")));
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+    }
+
+    /// future that returns pending once before completing
+    #[cfg(feature = "futures")]
+    fn pending_once() -> impl std::future::Future<Output = ()> {
+        let mut polled = false;
+        futures::future::poll_fn(move |cx| {
+            if polled {
+                std::task::Poll::Ready(())
+            } else {
+                polled = true;
+                cx.waker().wake_by_ref();
+                std::task::Poll::Pending
+            }
+        })
+    }
+}
@@ -0,0 +1,225 @@
+use crate::scc::components;
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::hash::Hash;
+
+/// returns pairs of ids of nodes from `domain` and their dependencies which, once removed, leave the
+/// domain without cycles
+///
+/// the set is small but not always the smallest possible, it's found with the greedy heuristic of
+/// Eades, Lin and Smyth run separately on every strongly connected component; self dependencies are
+/// always included, the pairs follow the order of `domain` and `deps`, fails on missing dependencies
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec!["wooden pickaxe"], "Wood"),
+///     ];
+///     assert_eq!(suggest_edge_removals(&domain), Ok(vec![("planks", "wood")]));
+/// ```
+pub fn suggest_edge_removals<Id, Item>(
+    domain: &[Node<Id, Item>],
+) -> Result<Vec<(Id, Id)>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let graph = CompactGraph::new(domain)?;
+
+    // place of every node in an order breaking as few dependencies as the heuristic manages
+    let mut place = vec![0; graph.len()];
+    let mut component_of = vec![0; graph.len()];
+    for (component, members) in components(&graph).into_iter().enumerate() {
+        for &position in members.iter() {
+            component_of[position] = component;
+        }
+        if members.len() > 1 {
+            for (nth, position) in greedy_order(&graph, &members, &component_of)
+                .into_iter()
+                .enumerate()
+            {
+                place[position] = nth;
+            }
+        }
+    }
+
+    let mut removals: Vec<(usize, usize)> = Vec::new();
+    for position in 0..graph.len() {
+        for dep in graph.dep_positions(position) {
+            let broken = dep == position
+                || (component_of[dep] == component_of[position] && place[dep] > place[position]);
+            if broken && !removals.contains(&(position, dep)) {
+                removals.push((position, dep));
+            }
+        }
+    }
+
+    Ok(removals
+        .into_iter()
+        .map(|(position, dep)| (domain[position].id.clone(), domain[dep].id.clone()))
+        .collect())
+}
+
+/// orders `members` of a strongly connected component so that few of their dependencies come after
+/// them, taking nodes without remaining dependents to the end and nodes without remaining
+/// dependencies to the front, and otherwise the node with the most dependents over dependencies
+fn greedy_order<Id>(
+    graph: &CompactGraph<Id>,
+    members: &[usize],
+    component_of: &[usize],
+) -> Vec<usize>
+where
+    Id: Clone + Eq + Hash,
+{
+    let component = component_of[members[0]];
+    let mut deps: Vec<Vec<usize>> = vec![Vec::new(); graph.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); graph.len()];
+    for &position in members.iter() {
+        for dep in graph.dep_positions(position) {
+            if dep != position && component_of[dep] == component {
+                deps[position].push(dep);
+                dependents[dep].push(position);
+            }
+        }
+    }
+
+    let mut remaining_deps: Vec<usize> = deps.iter().map(Vec::len).collect();
+    let mut remaining_dependents: Vec<usize> = dependents.iter().map(Vec::len).collect();
+    let mut removed = vec![false; graph.len()];
+    let delta = |dependents: &[usize], deps: &[usize], position: usize| {
+        dependents[position] as isize - deps[position] as isize
+    };
+
+    let mut sinks: Vec<usize> = Vec::new();
+    let mut sources: Vec<usize> = Vec::new();
+    let mut heap: BinaryHeap<(isize, Reverse<usize>)> = members
+        .iter()
+        .map(|&position| {
+            let delta = delta(&remaining_dependents, &remaining_deps, position);
+            (delta, Reverse(position))
+        })
+        .collect();
+
+    let mut front = Vec::with_capacity(members.len());
+    let mut back = Vec::new();
+    let mut left = members.len();
+    while left > 0 {
+        let (position, to_front) = if let Some(position) = sinks.pop() {
+            (position, false)
+        } else if let Some(position) = sources.pop() {
+            (position, true)
+        } else {
+            match heap.pop() {
+                Some((stale, Reverse(position)))
+                    if removed[position]
+                        || stale != delta(&remaining_dependents, &remaining_deps, position) =>
+                {
+                    continue
+                }
+                Some((_, Reverse(position))) => (position, true),
+                None => break,
+            }
+        };
+        if removed[position] {
+            continue;
+        }
+
+        removed[position] = true;
+        left -= 1;
+        if to_front {
+            front.push(position);
+        } else {
+            back.push(position);
+        }
+
+        for &dep in deps[position].iter() {
+            if !removed[dep] {
+                remaining_dependents[dep] -= 1;
+                if remaining_dependents[dep] == 0 {
+                    sinks.push(dep);
+                }
+                heap.push((
+                    delta(&remaining_dependents, &remaining_deps, dep),
+                    Reverse(dep),
+                ));
+            }
+        }
+        for &dependent in dependents[position].iter() {
+            if !removed[dependent] {
+                remaining_deps[dependent] -= 1;
+                if remaining_deps[dependent] == 0 {
+                    sources.push(dependent);
+                }
+                heap.push((
+                    delta(&remaining_dependents, &remaining_deps, dependent),
+                    Reverse(dependent),
+                ));
+            }
+        }
+    }
+
+    front.extend(back.into_iter().rev());
+    front
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggest_edge_removals_works() {
+        let mut domain = vec![
+            Node::new(1, vec![2, 4], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2, 5], "cat"),
+            Node::new(4, vec![5, 1], "dog"),
+            Node::new(5, vec![], "mouse"),
+            Node::new(6, vec![6, 6], "bird"),
+        ];
+        let removals = suggest_edge_removals(&domain).unwrap();
+        assert_eq!(removals.len(), 3);
+        assert!(removals.contains(&(6, 6)));
+
+        for (id, dep) in removals {
+            let node = domain.iter_mut().find(|node| node.id == id).unwrap();
+            node.deps.retain(|other| *other != dep);
+        }
+        assert!(crate::sort_all(&domain).is_ok());
+    }
+
+    #[test]
+    fn suggest_edge_removals_acyclic() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+        ];
+        assert_eq!(suggest_edge_removals(&domain), Ok(vec![]));
+        assert_eq!(
+            suggest_edge_removals(&[Node::new(1, vec![2], "hello")]),
+            Err(TopsortError::TargetNotFound(2))
+        );
+    }
+
+    #[test]
+    fn suggest_edge_removals_dense() {
+        // every node depends on every other one, so any order breaks half of the dependencies
+        let mut domain: Vec<_> = (0..6)
+            .map(|id| Node::new(id, (0..6).filter(|&dep| dep != id).collect(), ()))
+            .collect();
+        let removals = suggest_edge_removals(&domain).unwrap();
+        assert_eq!(removals.len(), 15);
+
+        for (id, dep) in removals {
+            domain[id].deps.retain(|other| *other != dep);
+        }
+        assert!(crate::sort_all(&domain).is_ok());
+    }
+}
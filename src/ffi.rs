@@ -0,0 +1,242 @@
+//! C API over a [`Graph`] of integer ids
+//!
+//! build the crate as a C library with `cargo rustc --release --features ffi --crate-type cdylib`
+//! (or `staticlib`) and include `include/szyk.h`; graphs are opaque handles created with
+//! [`szyk_graph_new`] and destroyed with [`szyk_graph_free`], sorts fill a [`SzykIds`] array which
+//! has to be released with [`szyk_ids_free`]
+//!
+//! # Examples
+//! ```c
+//!     SzykGraph *graph = szyk_graph_new();
+//!     szyk_graph_add_node(graph, 1);
+//!     szyk_graph_add_node(graph, 2);
+//!     szyk_graph_add_edge(graph, 1, 2);
+//!
+//!     SzykIds ids;
+//!     if (szyk_graph_sort(graph, 1, &ids) == SZYK_OK) {
+//!         // ids.ids[0] == 2, ids.ids[1] == 1
+//!     }
+//!     szyk_ids_free(ids);
+//!     szyk_graph_free(graph);
+//! ```
+
+use crate::{Graph, TopsortError};
+use std::ptr;
+
+/// opaque handle to a graph of `u64` ids
+#[derive(Debug, Default)]
+pub struct SzykGraph {
+    /// values are the ids, so sorts return them directly
+    graph: Graph<u64, u64>,
+}
+
+/// result of a call, [`SzykStatus::Ok`] on success
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SzykStatus {
+    Ok = 0,
+    /// a pointer argument was null
+    NullPointer = 1,
+    /// the ids hold the id that wasn't found
+    TargetNotFound = 2,
+    /// the ids hold the cycle in dependency order, starting and ending on the same id
+    CyclicDependency = 3,
+    /// the ids hold the node depending on itself
+    SelfDependency = 4,
+    /// sorting failed for any other reason, the ids are empty
+    SortFailed = 5,
+}
+
+/// array of ids owned by the library, released with [`szyk_ids_free`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct SzykIds {
+    pub ids: *mut u64,
+    pub len: usize,
+}
+
+impl SzykIds {
+    fn from_vec(ids: Vec<u64>) -> Self {
+        let len = ids.len();
+        let ids = Box::into_raw(ids.into_boxed_slice()) as *mut u64;
+        Self { ids, len }
+    }
+}
+
+/// empty array with a null pointer
+impl Default for SzykIds {
+    fn default() -> Self {
+        Self {
+            ids: ptr::null_mut(),
+            len: 0,
+        }
+    }
+}
+
+/// creates an empty graph, released with [`szyk_graph_free`]
+#[no_mangle]
+pub extern "C" fn szyk_graph_new() -> *mut SzykGraph {
+    Box::into_raw(Box::default())
+}
+
+/// releases a graph created with [`szyk_graph_new`], does nothing for null
+///
+/// # Safety
+/// `graph` has to be null or returned by [`szyk_graph_new`], and not used after this call
+#[no_mangle]
+pub unsafe extern "C" fn szyk_graph_free(graph: *mut SzykGraph) {
+    if !graph.is_null() {
+        drop(Box::from_raw(graph));
+    }
+}
+
+/// adds a node without dependencies, does nothing if it's already in the graph
+///
+/// # Safety
+/// `graph` has to be null or a live graph returned by [`szyk_graph_new`]
+#[no_mangle]
+pub unsafe extern "C" fn szyk_graph_add_node(graph: *mut SzykGraph, id: u64) -> SzykStatus {
+    match graph.as_mut() {
+        Some(graph) => {
+            if !graph.graph.contains(&id) {
+                graph.graph.add_node(id, id);
+            }
+            SzykStatus::Ok
+        }
+        None => SzykStatus::NullPointer,
+    }
+}
+
+/// makes `from` depend on `to`, see [`Graph::add_edge`]
+///
+/// fails with [`SzykStatus::TargetNotFound`] if `from` isn't in the graph
+///
+/// # Safety
+/// `graph` has to be null or a live graph returned by [`szyk_graph_new`]
+#[no_mangle]
+pub unsafe extern "C" fn szyk_graph_add_edge(
+    graph: *mut SzykGraph,
+    from: u64,
+    to: u64,
+) -> SzykStatus {
+    match graph.as_mut() {
+        Some(graph) => match graph.graph.add_edge(from, to) {
+            Ok(()) => SzykStatus::Ok,
+            Err(_) => SzykStatus::TargetNotFound,
+        },
+        None => SzykStatus::NullPointer,
+    }
+}
+
+/// fills `out` with ids in topological order, ending on `target`, see [`Graph::sort`]
+///
+/// on failure `out` holds the ids described by the returned status, `out` has to be released with
+/// [`szyk_ids_free`] whatever the status, unless it is [`SzykStatus::NullPointer`]
+///
+/// # Safety
+/// `graph` has to be null or a live graph returned by [`szyk_graph_new`], `out` has to be null or
+/// valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn szyk_graph_sort(
+    graph: *const SzykGraph,
+    target: u64,
+    out: *mut SzykIds,
+) -> SzykStatus {
+    let (graph, out) = match (graph.as_ref(), out.as_mut()) {
+        (Some(graph), Some(out)) => (graph, out),
+        _ => return SzykStatus::NullPointer,
+    };
+
+    let (status, ids) = status_of(graph.graph.sort(target));
+    *out = SzykIds::from_vec(ids);
+    status
+}
+
+/// status of a sort together with the ids it describes, panicking here would abort the host process
+fn status_of(result: Result<Vec<u64>, TopsortError<u64>>) -> (SzykStatus, Vec<u64>) {
+    match result {
+        Ok(ids) => (SzykStatus::Ok, ids),
+        Err(TopsortError::TargetNotFound(id)) => (SzykStatus::TargetNotFound, vec![id]),
+        Err(TopsortError::CyclicDependency(cycle)) => (SzykStatus::CyclicDependency, cycle),
+        Err(TopsortError::SelfDependency(id)) => (SzykStatus::SelfDependency, vec![id]),
+        Err(_) => (SzykStatus::SortFailed, Vec::new()),
+    }
+}
+
+/// releases ids filled by [`szyk_graph_sort`], does nothing for an empty array
+///
+/// # Safety
+/// `ids` has to be filled by [`szyk_graph_sort`] and not used after this call
+#[no_mangle]
+pub unsafe extern "C" fn szyk_ids_free(ids: SzykIds) {
+    if !ids.ids.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            ids.ids, ids.len,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn sorted(graph: *const SzykGraph, target: u64) -> (SzykStatus, Vec<u64>) {
+        let mut out = SzykIds::default();
+        let status = szyk_graph_sort(graph, target, &mut out);
+        let ids = std::slice::from_raw_parts(out.ids, out.len).to_vec();
+        szyk_ids_free(out);
+        (status, ids)
+    }
+
+    #[test]
+    fn sort_works() {
+        unsafe {
+            let graph = szyk_graph_new();
+            for id in 1..=3 {
+                assert_eq!(szyk_graph_add_node(graph, id), SzykStatus::Ok);
+            }
+            assert_eq!(szyk_graph_add_edge(graph, 1, 2), SzykStatus::Ok);
+            assert_eq!(szyk_graph_add_edge(graph, 2, 3), SzykStatus::Ok);
+            assert_eq!(szyk_graph_add_edge(graph, 4, 3), SzykStatus::TargetNotFound);
+            assert_eq!(sorted(graph, 1), (SzykStatus::Ok, vec![3, 2, 1]));
+
+            assert_eq!(szyk_graph_add_edge(graph, 3, 1), SzykStatus::Ok);
+            assert_eq!(
+                sorted(graph, 1),
+                (SzykStatus::CyclicDependency, vec![1, 2, 3, 1])
+            );
+            assert_eq!(sorted(graph, 5), (SzykStatus::TargetNotFound, vec![5]));
+            szyk_graph_free(graph);
+        }
+    }
+
+    #[test]
+    fn other_errors_fail_without_ids() {
+        assert_eq!(
+            status_of(Err(TopsortError::Cancelled)),
+            (SzykStatus::SortFailed, vec![])
+        );
+        assert_eq!(
+            status_of(Err(TopsortError::DepthLimitExceeded(1))),
+            (SzykStatus::SortFailed, vec![])
+        );
+    }
+
+    #[test]
+    fn null_pointers() {
+        unsafe {
+            let mut out = SzykIds::default();
+            assert_eq!(
+                szyk_graph_add_node(ptr::null_mut(), 1),
+                SzykStatus::NullPointer
+            );
+            assert_eq!(
+                szyk_graph_sort(ptr::null(), 1, &mut out),
+                SzykStatus::NullPointer
+            );
+            assert!(out.ids.is_null());
+            szyk_ids_free(out);
+            szyk_graph_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::HasDependencies;
+use core::fmt;
+use core::ops::Deref;
+
+/// error of [`sort_fixed`], holding no allocations
+#[derive(PartialEq, Debug, Clone)]
+pub enum FixedError<Id> {
+    /// * `Id` - target that wasn't found
+    TargetNotFound(Id),
+    /// * `(Id, Id)` - node and its dependency closing a cycle
+    CyclicDependency(Id, Id),
+    /// * `Id` - node listing itself in its dependencies
+    SelfDependency(Id),
+    /// the domain has more nodes than the capacity of the sort
+    CapacityExceeded,
+}
+
+impl<Id> fmt::Display for FixedError<Id>
+where
+    Id: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixedError::TargetNotFound(id) => write!(f, "target `{}` not found", id),
+            FixedError::CyclicDependency(id, dep) => {
+                write!(f, "cyclic dependency: `{}` -> `{}` closes a cycle", id, dep)
+            }
+            FixedError::SelfDependency(id) => write!(f, "`{}` depends on itself", id),
+            FixedError::CapacityExceeded => write!(f, "domain exceeds the capacity of the sort"),
+        }
+    }
+}
+
+impl<Id> core::error::Error for FixedError<Id> where Id: fmt::Debug + fmt::Display {}
+
+/// positions of nodes in topological order stored inline, dereferences to a slice of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedOrder<const N: usize> {
+    positions: [usize; N],
+    len: usize,
+}
+
+impl<const N: usize> Deref for FixedOrder<N> {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        &self.positions[..self.len]
+    }
+}
+
+/// returns positions of items of `domain` in topological order, ending on the item with id of
+/// `target`, without allocating
+///
+/// all state lives in arrays of `N` elements on the stack, the domain can have at most `N` items, ids
+/// are looked up linearly so the sort takes O(N · deps) time; ids are expected to be unique, the first
+/// item with an id is used; otherwise the order is the one of [`crate::sort`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let steps = [
+///         Node::new("uart", vec!["clocks"], ()),
+///         Node::new("clocks", vec![], ()),
+///         Node::new("logger", vec!["uart"], ()),
+///     ];
+///     let order = sort_fixed::<_, 8>(&steps, &"logger").unwrap();
+///     assert_eq!(&order[..], &[1, 0, 2]);
+/// ```
+pub fn sort_fixed<T, const N: usize>(
+    domain: &[T],
+    target: &T::Id,
+) -> Result<FixedOrder<N>, FixedError<T::Id>>
+where
+    T: HasDependencies,
+{
+    let mut sort = FixedSort::<T, N>::new(domain)?;
+    match find(domain, target) {
+        Some(position) => sort.visit(position)?,
+        None => return Err(FixedError::TargetNotFound(target.clone())),
+    }
+    Ok(sort.order)
+}
+
+/// returns positions of every item of `domain` in topological order like [`crate::sort_all`], without
+/// allocating, see [`sort_fixed`]
+pub fn sort_all_fixed<T, const N: usize>(domain: &[T]) -> Result<FixedOrder<N>, FixedError<T::Id>>
+where
+    T: HasDependencies,
+{
+    let mut sort = FixedSort::<T, N>::new(domain)?;
+    for position in 0..domain.len() {
+        sort.visit(position)?;
+    }
+    Ok(sort.order)
+}
+
+/// position of the first item of `domain` with id of `id`
+fn find<T>(domain: &[T], id: &T::Id) -> Option<usize>
+where
+    T: HasDependencies,
+{
+    domain.iter().position(|item| item.id() == id)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Unvisited,
+    OnPath,
+    Done,
+}
+
+/// state of a depth-first traversal kept in arrays of `N` elements
+struct FixedSort<'a, T, const N: usize> {
+    domain: &'a [T],
+    state: [State; N],
+    /// explicit work stack of (item position, position of the next dependency to visit)
+    stack: [(usize, usize); N],
+    order: FixedOrder<N>,
+}
+
+impl<'a, T, const N: usize> FixedSort<'a, T, N>
+where
+    T: HasDependencies,
+{
+    fn new(domain: &'a [T]) -> Result<Self, FixedError<T::Id>> {
+        if domain.len() > N {
+            return Err(FixedError::CapacityExceeded);
+        }
+        Ok(Self {
+            domain,
+            state: [State::Unvisited; N],
+            stack: [(0, 0); N],
+            order: FixedOrder {
+                positions: [0; N],
+                len: 0,
+            },
+        })
+    }
+
+    /// appends positions of the item at `root` and its dependencies not visited yet to the order
+    fn visit(&mut self, root: usize) -> Result<(), FixedError<T::Id>> {
+        if self.state[root] == State::Done {
+            return Ok(());
+        }
+
+        let mut depth = 1;
+        self.stack[0] = (root, 0);
+        self.state[root] = State::OnPath;
+        while depth > 0 {
+            let (position, next_dep) = self.stack[depth - 1];
+            let item = &self.domain[position];
+            match item.deps().get(next_dep) {
+                Some(dep) => {
+                    self.stack[depth - 1].1 += 1;
+                    let dep_position = match find(self.domain, dep) {
+                        Some(dep_position) => dep_position,
+                        None if item.is_optional(dep) => continue,
+                        None => return Err(FixedError::TargetNotFound(dep.clone())),
+                    };
+                    match self.state[dep_position] {
+                        State::Done => {}
+                        State::OnPath if dep_position == position => {
+                            return Err(FixedError::SelfDependency(dep.clone()))
+                        }
+                        State::OnPath => {
+                            return Err(FixedError::CyclicDependency(
+                                item.id().clone(),
+                                dep.clone(),
+                            ))
+                        }
+                        State::Unvisited => {
+                            // every item is on the path at most once, so the stack can't overflow
+                            self.state[dep_position] = State::OnPath;
+                            self.stack[depth] = (dep_position, 0);
+                            depth += 1;
+                        }
+                    }
+                }
+                None => {
+                    depth -= 1;
+                    self.state[position] = State::Done;
+                    self.order.positions[self.order.len] = position;
+                    self.order.len += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[test]
+    fn sort_fixed_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        let order = sort_fixed::<_, 4>(&domain, &1).unwrap();
+        assert_eq!(&order[..], &[2, 1, 0]);
+        assert_eq!(&sort_all_fixed::<_, 4>(&domain).unwrap()[..], &[2, 1, 0, 3]);
+        assert_eq!(
+            sort_fixed::<_, 4>(&domain, &5),
+            Err(FixedError::TargetNotFound(5))
+        );
+        assert_eq!(
+            sort_fixed::<_, 3>(&domain, &1),
+            Err(FixedError::CapacityExceeded)
+        );
+    }
+
+    #[test]
+    fn sort_fixed_errors() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![1], "cat"),
+            Node::new(4, vec![4], "dog"),
+            Node::new(5, vec![6], "mouse"),
+        ];
+        assert_eq!(
+            sort_fixed::<_, 8>(&domain, &1),
+            Err(FixedError::CyclicDependency(3, 1))
+        );
+        assert_eq!(
+            sort_fixed::<_, 8>(&domain, &4),
+            Err(FixedError::SelfDependency(4))
+        );
+        assert_eq!(
+            sort_fixed::<_, 8>(&domain, &5),
+            Err(FixedError::TargetNotFound(6))
+        );
+        assert_eq!(
+            FixedError::CyclicDependency(3, 1).to_string(),
+            "cyclic dependency: `3` -> `1` closes a cycle"
+        );
+    }
+}
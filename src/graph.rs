@@ -0,0 +1,977 @@
+use crate::collections::HashMap;
+use crate::node::to_deps;
+use crate::traversal::Traversal;
+use crate::{Edge, Node, TopsortError};
+use alloc::borrow::ToOwned;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+/// what [`Graph::merge`] does with nodes present in both graphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// fails with [`TopsortError::DuplicateId`], leaving the graph unchanged
+    Error,
+    /// keeps the node already in the graph, dropping the other one
+    KeepFirst,
+    /// keeps the value already in the graph, adding dependencies, edge data and attributes of the other
+    /// node missing from it
+    CombineDeps,
+}
+
+/// dependency graph built incrementally, keeps an index of its nodes between sorts
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut graph = Graph::new();
+///     graph.add_node("cat", "Garfield");
+///     graph.add_node("dog", "Odie");
+///     graph.add_edge("cat", "dog").unwrap();
+///
+///     assert_eq!(graph.sort("cat"), Ok(vec!["Odie", "Garfield"]));
+/// ```
+#[derive(Debug)]
+pub struct Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    nodes: Vec<Node<Id, Item>>,
+    index: HashMap<Id, usize>,
+    /// number of nodes listing each id as a dependency, ids not listed by any node are left out
+    in_degrees: HashMap<Id, usize>,
+}
+
+impl<Id, Item> Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            in_degrees: HashMap::new(),
+        }
+    }
+
+    /// adds a node without dependencies
+    ///
+    /// if a node with the same `id` already exists its value is replaced and returned,
+    /// its dependencies are kept
+    pub fn add_node(&mut self, id: Id, value: Item) -> Option<Item> {
+        match self.index.get(&id) {
+            Some(&index) => Some(core::mem::replace(&mut self.nodes[index].value, value)),
+            None => {
+                self.index.insert(id.clone(), self.nodes.len());
+                self.nodes.push(Node::new(id, Vec::new(), value));
+                None
+            }
+        }
+    }
+
+    /// makes `from` depend on `to`
+    ///
+    /// `to` doesn't have to be in the graph yet, adding an existing edge does nothing
+    pub fn add_edge(&mut self, from: Id, to: Id) -> Result<(), TopsortError<Id>> {
+        let index = match self.index.get(&from) {
+            Some(&index) => index,
+            None => return Err(TopsortError::TargetNotFound(from)),
+        };
+
+        let deps = &mut self.nodes[index].deps;
+        if !deps.contains(&to) {
+            add_dependent(&mut self.in_degrees, &to);
+            deps.push(to);
+        }
+
+        Ok(())
+    }
+
+    /// makes `from` depend on `to` like [`Graph::add_edge`], attaching `edge` to the edge between them
+    /// and replacing data attached before
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node("app", ());
+    ///     graph.add_edge_with("app", "openssl", Edge::new().with_kind("runtime")).unwrap();
+    ///     assert_eq!(
+    ///         graph.edge(&"app", &"openssl").and_then(|edge| edge.kind.as_deref()),
+    ///         Some("runtime")
+    ///     );
+    /// ```
+    pub fn add_edge_with(&mut self, from: Id, to: Id, edge: Edge) -> Result<(), TopsortError<Id>> {
+        let index = match self.index.get(&from) {
+            Some(&index) => index,
+            None => return Err(TopsortError::TargetNotFound(from)),
+        };
+
+        let node = &mut self.nodes[index];
+        if !node.deps.contains(&to) {
+            add_dependent(&mut self.in_degrees, &to);
+            node.deps.push(to.clone());
+        }
+        match node.edges.iter_mut().find(|(dep, _)| *dep == to) {
+            Some((_, data)) => *data = edge,
+            None => node.edges.push((to, edge)),
+        }
+
+        Ok(())
+    }
+
+    /// returns data attached to the edge from `from` to `to`, see [`Edge`]
+    pub fn edge<Q>(&self, from: &Q, to: &Q) -> Option<&Edge>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(from).and_then(|node| node.edge(to))
+    }
+
+    /// makes `from` depend on `to` like [`Graph::add_edge`], unless it would create a cycle
+    ///
+    /// an edge closing a cycle is rejected with [`TopsortError::CyclicDependency`] holding the shortest
+    /// cycle it would create, starting and ending on `from`, or [`TopsortError::SelfDependency`] for a
+    /// self-loop, and leaves the graph unchanged
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node("wooden pickaxe", "Pickaxe");
+    ///     graph.add_node("planks", "Planks");
+    ///     graph.add_node("wood", "Wood");
+    ///     graph.try_add_edge("wooden pickaxe", "planks").unwrap();
+    ///     graph.try_add_edge("planks", "wood").unwrap();
+    ///
+    ///     assert_eq!(
+    ///         graph.try_add_edge("wood", "wooden pickaxe"),
+    ///         Err(TopsortError::CyclicDependency(vec!["wood", "wooden pickaxe", "planks", "wood"]))
+    ///     );
+    /// ```
+    pub fn try_add_edge(&mut self, from: Id, to: Id) -> Result<(), TopsortError<Id>> {
+        let index = match self.index.get(&from) {
+            Some(&index) => index,
+            None => return Err(TopsortError::TargetNotFound(from)),
+        };
+        if from == to {
+            return Err(TopsortError::SelfDependency(from));
+        }
+
+        if let Some(&start) = self.index.get(&to) {
+            // breadth-first search for the shortest chain of dependencies leading from `to` back to `from`
+            let mut previous: Vec<Option<usize>> = vec![None; self.nodes.len()];
+            let mut queue = VecDeque::new();
+            previous[start] = Some(start);
+            queue.push_back(start);
+            while let Some(position) = queue.pop_front() {
+                if position == index {
+                    let mut cycle = vec![self.nodes[index].id.clone()];
+                    let mut current = index;
+                    while current != start {
+                        current = previous[current].expect("reached nodes have a previous node");
+                        cycle.push(self.nodes[current].id.clone());
+                    }
+                    cycle.push(from);
+                    cycle.reverse();
+                    return Err(TopsortError::CyclicDependency(cycle));
+                }
+
+                for dep in self.nodes[position].deps.iter() {
+                    if let Some(&dep) = self.index.get(dep) {
+                        if previous[dep].is_none() {
+                            previous[dep] = Some(position);
+                            queue.push_back(dep);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.add_edge(from, to)
+    }
+
+    /// removes the node with id of `id` together with all edges pointing at it, returning its value
+    pub fn remove_node<Q>(&mut self, id: &Q) -> Option<Item>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.index.remove(id)?;
+        let node = self.nodes.swap_remove(index);
+        if let Some(moved) = self.nodes.get(index) {
+            self.index.insert(moved.id.clone(), index);
+        }
+        for dep in node.deps.iter() {
+            remove_dependent(&mut self.in_degrees, dep);
+        }
+        self.in_degrees.remove(id);
+
+        for other in self.nodes.iter_mut() {
+            other
+                .deps
+                .retain(|dep| <Id as Borrow<Q>>::borrow(dep) != id);
+            other
+                .edges
+                .retain(|(dep, _)| <Id as Borrow<Q>>::borrow(dep) != id);
+        }
+
+        Some(node.value)
+    }
+
+    /// moves all nodes of `other` into the graph, resolving nodes present in both according to `policy`
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node("wooden pickaxe", "Pickaxe");
+    ///     graph.add_edge("wooden pickaxe", "planks").unwrap();
+    ///
+    ///     let mut plugin = Graph::new();
+    ///     plugin.add_node("wooden pickaxe", "Fancy pickaxe");
+    ///     plugin.add_node("planks", "Planks");
+    ///     plugin.add_node("sticks", "Sticks");
+    ///     plugin.add_edge("wooden pickaxe", "sticks").unwrap();
+    ///
+    ///     graph.merge(plugin, MergePolicy::CombineDeps).unwrap();
+    ///     assert_eq!(graph.sort("wooden pickaxe"), Ok(vec!["Planks", "Sticks", "Pickaxe"]));
+    /// ```
+    pub fn merge(
+        &mut self,
+        other: Graph<Id, Item>,
+        policy: MergePolicy,
+    ) -> Result<(), TopsortError<Id>> {
+        if policy == MergePolicy::Error {
+            if let Some(node) = other.nodes.iter().find(|node| self.contains(&node.id)) {
+                return Err(TopsortError::DuplicateId(node.id.clone()));
+            }
+        }
+
+        for node in other.nodes {
+            let index = match self.index.get(&node.id) {
+                Some(&index) => index,
+                None => {
+                    for dep in node.deps.iter() {
+                        add_dependent(&mut self.in_degrees, dep);
+                    }
+                    self.index.insert(node.id.clone(), self.nodes.len());
+                    self.nodes.push(node);
+                    continue;
+                }
+            };
+
+            if policy == MergePolicy::CombineDeps {
+                let existing = &mut self.nodes[index];
+                for dep in node.deps {
+                    if !existing.deps.contains(&dep) {
+                        add_dependent(&mut self.in_degrees, &dep);
+                        existing.deps.push(dep);
+                    }
+                }
+                combine(&mut existing.optional_deps, node.optional_deps);
+                combine(&mut existing.after, node.after);
+                combine(&mut existing.before, node.before);
+                for (dep, edge) in node.edges {
+                    if existing.edge(&dep).is_none() {
+                        existing.edges.push((dep, edge));
+                    }
+                }
+                for (key, value) in node.attributes {
+                    existing.attributes.entry(key).or_insert(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// returns the node with id of `id`, which can be any borrowed form of the id type
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node(String::from("cat"), "Garfield");
+    ///     assert_eq!(graph.get("cat").map(|node| node.value), Some("Garfield"));
+    ///     assert!(graph.contains("cat"));
+    /// ```
+    pub fn get<Q>(&self, id: &Q) -> Option<&Node<Id, Item>>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(id).map(|&index| &self.nodes[index])
+    }
+
+    /// sets the attribute `key` of the node with id of `id` to `value`, returning the previous value;
+    /// does nothing and returns `None` when there is no such node, see [`Node::attributes`]
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node("planks", "Planks");
+    ///     graph.set_attribute(&"planks", "color", "brown");
+    ///     assert_eq!(
+    ///         graph.get(&"planks").and_then(|node| node.attributes.get("color")),
+    ///         Some(&String::from("brown"))
+    ///     );
+    /// ```
+    pub fn set_attribute<Q>(
+        &mut self,
+        id: &Q,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = *self.index.get(id)?;
+        self.nodes[index]
+            .attributes
+            .insert(key.into(), value.into())
+    }
+
+    pub fn contains<Q>(&self, id: &Q) -> bool
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(id)
+    }
+
+    /// returns the number of nodes directly depending on `id`, whether or not `id` is in the graph
+    ///
+    /// counts are kept up to date as the graph changes, so this doesn't scan the graph
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node("planks", "Planks");
+    ///     graph.add_node("sticks", "Sticks");
+    ///     graph.add_node("wooden pickaxe", "Pickaxe");
+    ///     graph.add_edge("sticks", "planks").unwrap();
+    ///     graph.add_edge("wooden pickaxe", "planks").unwrap();
+    ///     graph.add_edge("wooden pickaxe", "sticks").unwrap();
+    ///
+    ///     assert_eq!(graph.in_degree(&"planks"), 2);
+    ///     assert_eq!(graph.out_degree(&"wooden pickaxe"), 2);
+    ///     assert_eq!(graph.in_degree(&"wooden pickaxe"), 0);
+    /// ```
+    pub fn in_degree<Q>(&self, id: &Q) -> usize
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.in_degrees.get(id).copied().unwrap_or(0)
+    }
+
+    /// returns the number of direct dependencies of the node with id of `id`, 0 if there is no such
+    /// node, see [`Graph::in_degree`]
+    pub fn out_degree<Q>(&self, id: &Q) -> usize
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(id).map_or(0, |node| node.deps.len())
+    }
+
+    /// position of the node with id of `id` in [`Graph::nodes`]
+    pub(crate) fn position<Q>(&self, id: &Q) -> Option<usize>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(id).copied()
+    }
+
+    /// all nodes in the graph, in no particular order
+    pub fn nodes(&self) -> &[Node<Id, Item>] {
+        &self.nodes
+    }
+
+    /// consumes the graph, returning all of its nodes
+    pub fn into_nodes(self) -> Vec<Node<Id, Item>> {
+        self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// calls `cb` with nodes in topological order, ending on the node with id of `target`
+    pub fn sort_cb<F>(&self, target: Id, cb: &mut F) -> Result<(), TopsortError<Id>>
+    where
+        F: FnMut(&Node<Id, Item>),
+    {
+        let mut traversal = Traversal::with_index(&self.nodes, &self.index);
+        let index = traversal.find_index(&target)?;
+        traversal.visit(index, &mut |index| cb(&self.nodes[index]))
+    }
+
+    /// returns values of nodes in topological order, ending on the node with id of `target`
+    pub fn sort(&self, target: Id) -> Result<Vec<Item>, TopsortError<Id>>
+    where
+        Item: Clone,
+    {
+        let mut out = Vec::new();
+        self.sort_cb(target, &mut |node: &Node<_, _>| {
+            out.push(node.value.clone());
+        })?;
+
+        Ok(out)
+    }
+
+    /// returns values of nodes in topological order like [`Graph::sort`], naming the target by any
+    /// borrowed form of the id type, e.g. `&str` for `String` ids
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut graph = Graph::new();
+    ///     graph.add_node(String::from("cat"), "Garfield");
+    ///     graph.add_node(String::from("dog"), "Odie");
+    ///     graph.add_edge(String::from("cat"), String::from("dog")).unwrap();
+    ///     assert_eq!(graph.sort_borrowed("cat"), Ok(vec!["Odie", "Garfield"]));
+    /// ```
+    pub fn sort_borrowed<Q>(&self, target: &Q) -> Result<Vec<Item>, TopsortError<Id>>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+        Item: Clone,
+    {
+        let mut traversal = Traversal::with_index(&self.nodes, &self.index);
+        let index = traversal.find_index_borrowed(target)?;
+        let mut out = Vec::new();
+        traversal.visit(index, &mut |index| {
+            out.push(self.nodes[index].value.clone())
+        })?;
+
+        Ok(out)
+    }
+}
+
+/// appends ids from `other` missing from `ids`
+fn combine<Id>(ids: &mut Vec<Id>, other: Vec<Id>)
+where
+    Id: Eq,
+{
+    for id in other {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+}
+
+/// counts one more node depending on `dep`
+fn add_dependent<Id>(in_degrees: &mut HashMap<Id, usize>, dep: &Id)
+where
+    Id: Clone + Eq + Hash,
+{
+    match in_degrees.get_mut(dep) {
+        Some(count) => *count += 1,
+        None => {
+            in_degrees.insert(dep.clone(), 1);
+        }
+    }
+}
+
+/// counts one node less depending on `dep`, forgetting it once none does
+fn remove_dependent<Id>(in_degrees: &mut HashMap<Id, usize>, dep: &Id)
+where
+    Id: Clone + Eq + Hash,
+{
+    if let Some(count) = in_degrees.get_mut(dep) {
+        *count -= 1;
+        if *count == 0 {
+            in_degrees.remove(dep);
+        }
+    }
+}
+
+/// serialized as a sequence of nodes
+#[cfg(feature = "serde")]
+impl<Id, Item> serde::Serialize for Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash + serde::Serialize,
+    Item: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.nodes.serialize(serializer)
+    }
+}
+
+/// deserialized from a sequence of nodes, fails on duplicate ids
+#[cfg(feature = "serde")]
+impl<'de, Id, Item> serde::Deserialize<'de> for Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash + serde::Deserialize<'de>,
+    Item: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut nodes: Vec<Node<Id, Item>> = serde::Deserialize::deserialize(deserializer)?;
+        let mut index = HashMap::with_capacity(nodes.len());
+        for (position, node) in nodes.iter_mut().enumerate() {
+            node.dedup_deps();
+            if index.insert(node.id.clone(), position).is_some() {
+                return Err(serde::de::Error::custom("duplicate node id"));
+            }
+        }
+
+        let mut in_degrees = HashMap::new();
+        for dep in nodes.iter().flat_map(|node| node.deps.iter()) {
+            add_dependent(&mut in_degrees, dep);
+        }
+
+        Ok(Self {
+            nodes,
+            index,
+            in_degrees,
+        })
+    }
+}
+
+/// builds a graph from a map of ids to their dependencies, ids only listed as dependencies get nodes
+/// without dependencies and repeated dependencies are dropped like in [`Node::new`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::collections::HashMap;
+///
+///     let mut map = HashMap::new();
+///     map.insert("wooden pickaxe", vec!["planks", "sticks"]);
+///     map.insert("planks", vec!["wood"]);
+///     map.insert("sticks", vec!["planks"]);
+///
+///     let graph = Graph::from(map);
+///     assert!(graph.contains(&"wood"));
+///     assert_eq!(graph.sort("wooden pickaxe"), Ok(vec![(), (), (), ()]));
+/// ```
+impl<Id, S> From<HashMap<Id, Vec<Id>, S>> for Graph<Id, ()>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn from(map: HashMap<Id, Vec<Id>, S>) -> Self {
+        let mut graph = Graph::new();
+        for (id, deps) in map {
+            graph.add_node(id.clone(), ());
+            if let Some(&index) = graph.index.get(&id) {
+                graph.nodes[index].deps = to_deps(deps);
+                graph.nodes[index].dedup_deps();
+                for dep in graph.nodes[index].deps.iter() {
+                    add_dependent(&mut graph.in_degrees, dep);
+                }
+            }
+        }
+
+        let missing: Vec<Id> = graph
+            .nodes
+            .iter()
+            .flat_map(|node| node.deps.iter())
+            .filter(|&dep| !graph.index.contains_key(dep))
+            .cloned()
+            .collect();
+        for id in missing {
+            graph.add_node(id, ());
+        }
+
+        graph
+    }
+}
+
+/// adds nodes from an iterator, a node with an id already in the graph replaces the existing one
+impl<Id, Item> Extend<Node<Id, Item>> for Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn extend<I>(&mut self, nodes: I)
+    where
+        I: IntoIterator<Item = Node<Id, Item>>,
+    {
+        for node in nodes {
+            for dep in node.deps.iter() {
+                add_dependent(&mut self.in_degrees, dep);
+            }
+            match self.index.get(&node.id) {
+                Some(&index) => {
+                    let replaced = core::mem::replace(&mut self.nodes[index], node);
+                    for dep in replaced.deps.iter() {
+                        remove_dependent(&mut self.in_degrees, dep);
+                    }
+                }
+                None => {
+                    self.index.insert(node.id.clone(), self.nodes.len());
+                    self.nodes.push(node);
+                }
+            }
+        }
+    }
+}
+
+/// collects nodes into a graph like [`Graph::extend`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let graph: Graph<_, _> = (1..=3)
+///         .map(|id| Node::new(id, (1..id).collect(), id * 10))
+///         .collect();
+///     assert_eq!(graph.sort(3), Ok(vec![10, 20, 30]));
+/// ```
+impl<Id, Item> core::iter::FromIterator<Node<Id, Item>> for Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn from_iter<I>(nodes: I) -> Self
+    where
+        I: IntoIterator<Item = Node<Id, Item>>,
+    {
+        let mut graph = Graph::new();
+        graph.extend(nodes);
+        graph
+    }
+}
+
+impl<Id, Item> Default for Graph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pickaxe() -> Graph<&'static str, &'static str> {
+        let mut graph = Graph::new();
+        graph.add_node("wooden pickaxe", "Pickaxe");
+        graph.add_node("planks", "Planks");
+        graph.add_node("sticks", "Sticks");
+        graph.add_node("wood", "Wood");
+        graph.add_edge("wooden pickaxe", "planks").unwrap();
+        graph.add_edge("wooden pickaxe", "sticks").unwrap();
+        graph.add_edge("planks", "wood").unwrap();
+        graph.add_edge("sticks", "planks").unwrap();
+        graph
+    }
+
+    #[test]
+    fn sort_works() {
+        let graph = pickaxe();
+        assert_eq!(
+            graph.sort("wooden pickaxe"),
+            Ok(vec!["Wood", "Planks", "Sticks", "Pickaxe"])
+        );
+        assert_eq!(graph.sort("sticks"), Ok(vec!["Wood", "Planks", "Sticks"]));
+    }
+
+    #[test]
+    fn add_node_replaces_value() {
+        let mut graph = pickaxe();
+        assert_eq!(graph.add_node("wood", "Oak"), Some("Wood"));
+        assert_eq!(graph.sort("planks"), Ok(vec!["Oak", "Planks"]));
+    }
+
+    #[test]
+    fn add_edge_from_missing_node() {
+        let mut graph = pickaxe();
+        assert_eq!(
+            graph.add_edge("stone pickaxe", "sticks"),
+            Err(TopsortError::TargetNotFound("stone pickaxe"))
+        );
+    }
+
+    #[test]
+    fn edge_to_missing_node() {
+        let mut graph = pickaxe();
+        graph.add_edge("wood", "tree").unwrap();
+        assert_eq!(
+            graph.sort("wooden pickaxe"),
+            Err(TopsortError::TargetNotFound("tree"))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let graph = pickaxe();
+        let json = serde_json::to_string(&graph).unwrap();
+        let graph: Graph<String, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            graph.sort(String::from("sticks")),
+            Ok(vec![
+                String::from("Wood"),
+                String::from("Planks"),
+                String::from("Sticks")
+            ])
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_duplicate_id() {
+        let json = r#"[{"id":1,"deps":[],"value":1},{"id":1,"deps":[],"value":2}]"#;
+        assert!(serde_json::from_str::<Graph<i32, i32>>(json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_drops_repeated_deps() {
+        let json = r#"[{"id":1,"deps":[2,2],"value":1},{"id":2,"deps":[],"value":2}]"#;
+        let graph: Graph<i32, i32> = serde_json::from_str(json).unwrap();
+        assert_eq!(graph.get(&1).map(|node| node.deps.len()), Some(1));
+        assert_eq!(graph.in_degree(&2), 1);
+    }
+
+    #[test]
+    fn try_add_edge_rejects_cycles() {
+        let mut graph = pickaxe();
+        assert_eq!(
+            graph.try_add_edge("wood", "wooden pickaxe"),
+            Err(TopsortError::CyclicDependency(vec![
+                "wood",
+                "wooden pickaxe",
+                "planks",
+                "wood"
+            ]))
+        );
+        assert_eq!(
+            graph.try_add_edge("wood", "wood"),
+            Err(TopsortError::SelfDependency("wood"))
+        );
+        assert_eq!(
+            graph.try_add_edge("tree", "wood"),
+            Err(TopsortError::TargetNotFound("tree"))
+        );
+        assert_eq!(graph.get(&"wood").map(|node| node.deps.len()), Some(0));
+
+        graph.try_add_edge("wood", "tree").unwrap();
+        graph.try_add_edge("sticks", "wood").unwrap();
+        assert_eq!(graph.get(&"sticks").map(|node| node.deps.len()), Some(2));
+    }
+
+    #[test]
+    fn remove_node_drops_edges() {
+        let mut graph = pickaxe();
+        assert_eq!(graph.remove_node(&"planks"), Some("Planks"));
+        assert_eq!(graph.remove_node(&"planks"), None);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.sort("wooden pickaxe"), Ok(vec!["Sticks", "Pickaxe"]));
+        assert_eq!(graph.get(&"wood").map(|node| node.value), Some("Wood"));
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let mut graph = Graph::new();
+        graph.add_node(String::from("planks"), "Planks");
+        graph.add_node(String::from("sticks"), "Sticks");
+        graph
+            .add_edge_with(
+                String::from("sticks"),
+                String::from("planks"),
+                Edge::new().with_label("2x"),
+            )
+            .unwrap();
+        assert!(graph.contains("sticks"));
+        assert_eq!(
+            graph
+                .edge("sticks", "planks")
+                .and_then(|edge| edge.label.as_deref()),
+            Some("2x")
+        );
+        assert_eq!(graph.sort_borrowed("sticks"), Ok(vec!["Planks", "Sticks"]));
+        assert_eq!(
+            graph.sort_borrowed("wood"),
+            Err(TopsortError::TargetNotFound(String::from("wood")))
+        );
+        assert_eq!(graph.remove_node("planks"), Some("Planks"));
+        assert_eq!(graph.get("sticks").map(|node| node.deps.len()), Some(0));
+    }
+
+    #[test]
+    fn edge_data() {
+        let mut graph = pickaxe();
+        graph
+            .add_edge_with("sticks", "planks", Edge::new().with_label("2x"))
+            .unwrap();
+        graph
+            .add_edge_with("wooden pickaxe", "stone", Edge::new().with_weight(3.0))
+            .unwrap();
+        assert_eq!(
+            graph.edge(&"sticks", &"planks"),
+            Some(&Edge::new().with_label("2x"))
+        );
+        assert_eq!(graph.edge(&"planks", &"wood"), None);
+        assert_eq!(
+            graph.add_edge_with("stone", "wood", Edge::new()),
+            Err(TopsortError::TargetNotFound("stone"))
+        );
+
+        graph.remove_node(&"planks");
+        assert_eq!(graph.edge(&"sticks", &"planks"), None);
+        assert_eq!(graph.get(&"sticks").map(|node| node.edges.len()), Some(0));
+    }
+
+    #[test]
+    fn degrees_follow_changes() {
+        let mut graph = pickaxe();
+        assert_eq!(graph.in_degree(&"planks"), 2);
+        assert_eq!(graph.out_degree(&"wooden pickaxe"), 2);
+        assert_eq!(graph.out_degree(&"stone"), 0);
+
+        graph.add_edge("wooden pickaxe", "planks").unwrap();
+        graph.add_edge("wooden pickaxe", "stone").unwrap();
+        assert_eq!(graph.in_degree(&"planks"), 2);
+        assert_eq!(graph.in_degree(&"stone"), 1);
+
+        graph.remove_node(&"sticks");
+        assert_eq!(graph.in_degree(&"planks"), 1);
+        assert_eq!(graph.in_degree(&"sticks"), 0);
+
+        graph.extend(vec![Node::new("wooden pickaxe", vec!["wood"], "Pickaxe")]);
+        assert_eq!(graph.in_degree(&"planks"), 0);
+        assert_eq!(graph.in_degree(&"stone"), 0);
+        assert_eq!(graph.in_degree(&"wood"), 2);
+
+        graph
+            .merge(stone_tools(), MergePolicy::CombineDeps)
+            .unwrap();
+        assert_eq!(graph.in_degree(&"stone"), 2);
+        assert_eq!(graph.in_degree(&"sticks"), 2);
+
+        let mut map = HashMap::new();
+        map.insert("wooden pickaxe", vec!["planks", "sticks"]);
+        map.insert("sticks", vec!["planks", "planks"]);
+        let graph = Graph::from(map);
+        assert_eq!(graph.in_degree(&"planks"), 2);
+        assert_eq!(graph.in_degree(&"sticks"), 1);
+        assert_eq!(graph.in_degree(&"wooden pickaxe"), 0);
+        assert_eq!(graph.out_degree(&"sticks"), 1);
+        assert_eq!(graph.get(&"sticks").map(|node| node.deps.len()), Some(1));
+    }
+
+    #[test]
+    fn set_attribute_works() {
+        let mut graph = pickaxe();
+        assert_eq!(graph.set_attribute(&"planks", "color", "brown"), None);
+        assert_eq!(
+            graph.set_attribute(&"planks", "color", "oak"),
+            Some(String::from("brown"))
+        );
+        assert_eq!(graph.set_attribute(&"stone", "color", "gray"), None);
+        assert_eq!(
+            graph
+                .get(&"planks")
+                .map(|node| node.attributes.get("color").cloned()),
+            Some(Some(String::from("oak")))
+        );
+    }
+
+    fn stone_tools() -> Graph<&'static str, &'static str> {
+        let mut graph = Graph::new();
+        graph.add_node("wooden pickaxe", "Fancy pickaxe");
+        graph.add_node("stone pickaxe", "Stone pickaxe");
+        graph.add_node("stone", "Stone");
+        graph.add_edge("stone pickaxe", "stone").unwrap();
+        graph.add_edge("stone pickaxe", "sticks").unwrap();
+        graph.add_edge("wooden pickaxe", "sticks").unwrap();
+        graph.add_edge("wooden pickaxe", "stone").unwrap();
+        graph
+    }
+
+    #[test]
+    fn merge_works() {
+        let mut graph = pickaxe();
+        assert_eq!(
+            graph.merge(stone_tools(), MergePolicy::Error),
+            Err(TopsortError::DuplicateId("wooden pickaxe"))
+        );
+        assert_eq!(graph.len(), 4);
+
+        graph.merge(stone_tools(), MergePolicy::KeepFirst).unwrap();
+        assert_eq!(graph.len(), 6);
+        assert_eq!(
+            graph.sort("wooden pickaxe"),
+            Ok(vec!["Wood", "Planks", "Sticks", "Pickaxe"])
+        );
+        assert_eq!(
+            graph.sort("stone pickaxe"),
+            Ok(vec!["Stone", "Wood", "Planks", "Sticks", "Stone pickaxe"])
+        );
+
+        let mut graph = pickaxe();
+        graph
+            .merge(stone_tools(), MergePolicy::CombineDeps)
+            .unwrap();
+        assert_eq!(
+            graph.sort("wooden pickaxe"),
+            Ok(vec!["Wood", "Planks", "Sticks", "Stone", "Pickaxe"])
+        );
+    }
+
+    #[test]
+    fn from_adjacency_map() {
+        let mut map = HashMap::new();
+        map.insert(1, vec![2, 3]);
+        map.insert(3, vec![2, 4]);
+        let graph = Graph::from(map);
+        assert_eq!(graph.len(), 4);
+        assert_eq!(
+            graph.get(&3).map(|node| node.deps.to_vec()),
+            Some(vec![2, 4])
+        );
+        assert_eq!(graph.get(&4).map(|node| node.deps.len()), Some(0));
+
+        let mut ids = Vec::new();
+        graph
+            .sort_cb(1, &mut |node: &Node<_, _>| ids.push(node.id))
+            .unwrap();
+        assert_eq!(ids, vec![2, 4, 3, 1]);
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let mut graph: Graph<_, _> = vec![
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![], "world"),
+        ]
+        .into_iter()
+        .collect();
+        graph.extend(vec![
+            Node::new(2, vec![3], "cat"),
+            Node::new(3, vec![], "dog"),
+        ]);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.sort(1), Ok(vec!["dog", "cat", "hello"]));
+    }
+}
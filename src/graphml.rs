@@ -0,0 +1,288 @@
+//! GraphML export and import
+//!
+//! # Examples
+//! ```
+//!     use szyk::Node;
+//!     use szyk::graphml;
+//!
+//!     let domain = [
+//!         Node::new("cat", vec!["dog"], "Garfield"),
+//!         Node::new("dog", vec![], "Odie"),
+//!     ];
+//!     let xml = graphml::to_graphml(&domain);
+//!     assert!(xml.contains("<edge source=\"cat\" target=\"dog\"/>"));
+//!
+//!     let graph = graphml::parse(&xml).unwrap();
+//!     assert_eq!(graph.sort(String::from("cat")).unwrap().len(), 2);
+//! ```
+
+use crate::collections::HashSet;
+use crate::traversal::first_index;
+use crate::{Graph, Node};
+use core::fmt::{self, Display, Write};
+use core::hash::Hash;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// renders `domain` as a GraphML document with edges pointing from nodes to their dependencies
+///
+/// dependencies missing from `domain` are declared as extra nodes after the others
+pub fn to_graphml<Id, Item>(domain: &[Node<Id, Item>]) -> String
+where
+    Id: Clone + Eq + Hash + Display,
+{
+    let mut out = String::new();
+    write_graphml(&mut out, domain).expect("writing to a String doesn't fail");
+    out
+}
+
+fn write_graphml<Id, Item, W>(out: &mut W, domain: &[Node<Id, Item>]) -> fmt::Result
+where
+    Id: Clone + Eq + Hash + Display,
+    W: Write,
+{
+    let index = first_index(domain);
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        out,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )?;
+    writeln!(out, "  <graph id=\"G\" edgedefault=\"directed\">")?;
+    for (position, node) in domain.iter().enumerate() {
+        if index.get(&node.id) == Some(&position) {
+            writeln!(out, "    <node id=\"{}\"/>", attribute(&node.id))?;
+        }
+    }
+
+    let mut missing = HashSet::new();
+    for dep in domain.iter().flat_map(|node| node.deps.iter()) {
+        if !index.contains_key(dep) && missing.insert(dep) {
+            writeln!(out, "    <node id=\"{}\"/>", attribute(dep))?;
+        }
+    }
+
+    for node in domain.iter() {
+        for dep in node.deps.iter() {
+            writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                attribute(&node.id),
+                attribute(dep)
+            )?;
+        }
+    }
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")
+}
+
+/// escapes `id` for use in a quoted XML attribute
+fn attribute<Id>(id: &Id) -> String
+where
+    Id: Display,
+{
+    escape(id.to_string().as_str()).into_owned()
+}
+
+/// error returned when GraphML input can't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphmlParseError {
+    /// byte offset in the input the error was found at
+    pub position: u64,
+    pub message: String,
+}
+
+impl Display for GraphmlParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte {}: {}", self.position, self.message)
+    }
+}
+
+impl core::error::Error for GraphmlParseError {}
+
+/// parses a GraphML document into a [`Graph`], an edge from `a` to `b` makes `a` depend on `b`
+///
+/// nodes are added in the order they are declared or first used by an edge, nested graphs are
+/// flattened and data, ports and hyperedges are ignored
+///
+/// # Examples
+/// ```
+///     use szyk::graphml;
+///
+///     let graph = graphml::parse(r#"
+///         <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+///             <graph edgedefault="directed">
+///                 <node id="planks"/>
+///                 <node id="wood"/>
+///                 <edge source="planks" target="wood"/>
+///                 <edge source="sticks" target="planks"/>
+///             </graph>
+///         </graphml>
+///     "#).unwrap();
+///     let order: Vec<_> = graph.nodes().iter().map(|node| node.id.as_str()).collect();
+///     assert_eq!(order, vec!["planks", "wood", "sticks"]);
+/// ```
+pub fn parse(input: &str) -> Result<Graph<String, ()>, GraphmlParseError> {
+    let mut reader = Reader::from_str(input);
+    let mut graph = Graph::new();
+    let mut root = false;
+
+    loop {
+        let event = reader.read_event().map_err(|err| GraphmlParseError {
+            position: reader.error_position(),
+            message: err.to_string(),
+        })?;
+        let position = reader.buffer_position();
+        let error = |message: String| GraphmlParseError { position, message };
+
+        let element = match event {
+            Event::Start(element) | Event::Empty(element) => element,
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        match element.local_name().as_ref() {
+            b"graphml" => root = true,
+            _ if !root => return Err(error(String::from("expected a `graphml` element"))),
+            b"node" => {
+                let id = required(&element, "id").map_err(error)?;
+                if !graph.contains(&id) {
+                    graph.add_node(id, ());
+                }
+            }
+            b"edge" => {
+                let source = required(&element, "source").map_err(error)?;
+                let target = required(&element, "target").map_err(error)?;
+                for id in [&source, &target] {
+                    if !graph.contains(id) {
+                        graph.add_node(id.clone(), ());
+                    }
+                }
+                graph
+                    .add_edge(source, target)
+                    .expect("both endpoints were just added");
+            }
+            _ => {}
+        }
+    }
+
+    if !root {
+        return Err(GraphmlParseError {
+            position: reader.buffer_position(),
+            message: String::from("expected a `graphml` element"),
+        });
+    }
+    Ok(graph)
+}
+
+/// parses a GraphML document into nodes, see [`parse`]
+pub fn from_graphml(input: &str) -> Result<Vec<Node<String, ()>>, GraphmlParseError> {
+    parse(input).map(Graph::into_nodes)
+}
+
+/// unescaped value of the attribute `name` of `element`
+fn required(element: &BytesStart<'_>, name: &str) -> Result<String, String> {
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|err| err.to_string())?;
+        if attribute.key.local_name().as_ref() == name.as_bytes() {
+            return attribute
+                .unescape_value()
+                .map(|value| value.into_owned())
+                .map_err(|err| err.to_string());
+        }
+    }
+
+    Err(format!(
+        "`{}` is missing the `{}` attribute",
+        String::from_utf8_lossy(element.local_name().as_ref()),
+        name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_graphml_works() {
+        let domain = [
+            Node::new("a", vec!["b", "<c>"], ()),
+            Node::new("b", vec!["\"d\""], ()),
+        ];
+        assert_eq!(
+            to_graphml(&domain),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <graph id=\"G\" edgedefault=\"directed\">\n    \
+             <node id=\"a\"/>\n    \
+             <node id=\"b\"/>\n    \
+             <node id=\"&lt;c&gt;\"/>\n    \
+             <node id=\"&quot;d&quot;\"/>\n    \
+             <edge source=\"a\" target=\"b\"/>\n    \
+             <edge source=\"a\" target=\"&lt;c&gt;\"/>\n    \
+             <edge source=\"b\" target=\"&quot;d&quot;\"/>\n  \
+             </graph>\n\
+             </graphml>\n"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let domain = vec![
+            Node::new(String::from("a & b"), vec![String::from("<c>")], ()),
+            Node::new(String::from("<c>"), vec![], ()),
+        ];
+        assert_eq!(from_graphml(&to_graphml(&domain)), Ok(domain));
+    }
+
+    #[test]
+    fn parse_flattens_nested_graphs() {
+        let graph = parse(
+            r#"<?xml version="1.0"?>
+            <graphml>
+                <key id="d0" for="node" attr.name="color" attr.type="string"/>
+                <graph id="G" edgedefault="directed">
+                    <node id="a"><data key="d0">red</data></node>
+                    <node id="b">
+                        <graph id="b:" edgedefault="directed">
+                            <node id="b::c"/>
+                        </graph>
+                    </node>
+                    <edge source="a" target="b::c"/>
+                    <edge source="a" target="b"/>
+                </graph>
+            </graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            graph.into_nodes(),
+            vec![
+                Node::new(
+                    String::from("a"),
+                    vec![String::from("b::c"), String::from("b")],
+                    ()
+                ),
+                Node::new(String::from("b"), vec![], ()),
+                Node::new(String::from("b::c"), vec![], ()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            from_graphml("<graph/>").map_err(|err| err.message),
+            Err(String::from("expected a `graphml` element"))
+        );
+        assert_eq!(
+            from_graphml("").map_err(|err| err.message),
+            Err(String::from("expected a `graphml` element"))
+        );
+        assert_eq!(
+            from_graphml("<graphml><graph><edge source=\"a\"/></graph></graphml>")
+                .map_err(|err| err.message),
+            Err(String::from("`edge` is missing the `target` attribute"))
+        );
+        assert!(from_graphml("<graphml><graph></graphml>").is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::collections::HashMap;
+use crate::traversal::Traversal;
+use crate::{sort_all_cb, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+
+/// error of [`sort_grouped`]
+#[derive(PartialEq, Debug)]
+pub enum GroupError<Id, G> {
+    /// * `TopsortError<Id>` - the nodes couldn't be ordered
+    Sort(TopsortError<Id>),
+    /// * `Vec<G>` - groups depending on each other in a cycle, starting and ending on the same group,
+    ///   while their members don't
+    CyclicGroups(Vec<G>),
+}
+
+impl<Id, G> From<TopsortError<Id>> for GroupError<Id, G> {
+    fn from(err: TopsortError<Id>) -> Self {
+        GroupError::Sort(err)
+    }
+}
+
+impl<Id, G> fmt::Display for GroupError<Id, G>
+where
+    Id: fmt::Display,
+    G: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupError::Sort(err) => err.fmt(f),
+            GroupError::CyclicGroups(groups) => {
+                write!(f, "cyclic dependency between groups: ")?;
+                for (nth, group) in groups.iter().enumerate() {
+                    if nth > 0 {
+                        write!(f, " -> ")?;
+                    }
+                    write!(f, "`{}`", group)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<Id, G> core::error::Error for GroupError<Id, G>
+where
+    Id: fmt::Debug + fmt::Display + 'static,
+    G: fmt::Debug + fmt::Display,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            GroupError::Sort(err) => Some(err),
+            GroupError::CyclicGroups(_) => None,
+        }
+    }
+}
+
+/// groups paired with values of their members
+type Batches<G, Item> = Vec<(G, Vec<Item>)>;
+
+/// returns values of all nodes from `domain` batched into the groups `group_of` assigns them to
+///
+/// groups are in topological order, each one after all groups its members depend on, and members of
+/// each group are in topological order like in [`crate::sort_all`]; groups that don't depend on each
+/// other follow the order their first members appear in `domain`; fails when groups depend on each
+/// other in a cycle, even if their members don't
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("users", vec![], "create users"),
+///         Node::new("posts", vec!["users"], "create posts"),
+///         Node::new("seed admin", vec!["users"], "insert admin"),
+///         Node::new("posts index", vec!["posts"], "index posts"),
+///     ];
+///     let batches = sort_grouped(&domain, |node: &Node<_, _>| {
+///         if node.id.starts_with("seed") { "data" } else { "schema" }
+///     });
+///     assert_eq!(
+///         batches,
+///         Ok(vec![
+///             ("schema", vec!["create users", "create posts", "index posts"]),
+///             ("data", vec!["insert admin"]),
+///         ])
+///     );
+/// ```
+pub fn sort_grouped<Id, Item, G, F>(
+    domain: &[Node<Id, Item>],
+    mut group_of: F,
+) -> Result<Batches<G, Item>, GroupError<Id, G>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+    G: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>) -> G,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let mut order = Vec::with_capacity(domain.len());
+    for index in 0..domain.len() {
+        traversal.visit(index, &mut |index| order.push(index))?;
+    }
+
+    let mut groups: Vec<G> = Vec::new();
+    let mut group_index: HashMap<G, usize> = HashMap::new();
+    let mut member_of = Vec::with_capacity(domain.len());
+    for node in domain.iter() {
+        let group = group_of(node);
+        let index = *group_index.entry(group.clone()).or_insert_with(|| {
+            groups.push(group);
+            groups.len() - 1
+        });
+        member_of.push(index);
+    }
+
+    let mut group_nodes: Vec<Node<usize, ()>> = (0..groups.len())
+        .map(|group| Node::new(group, Vec::new(), ()))
+        .collect();
+    for position in 0..domain.len() {
+        let group = member_of[position];
+        for dep in traversal.dep_indices(position) {
+            let dep_group = member_of[dep?];
+            if dep_group != group && !group_nodes[group].deps.contains(&dep_group) {
+                group_nodes[group].deps.push(dep_group);
+            }
+        }
+    }
+
+    let mut group_order = Vec::with_capacity(groups.len());
+    sort_all_cb(&group_nodes, &mut |node: &Node<usize, ()>| {
+        group_order.push(node.id)
+    })
+    .map_err(|err| match err {
+        TopsortError::CyclicDependency(cycle) => GroupError::CyclicGroups(
+            cycle
+                .into_iter()
+                .map(|group| groups[group].clone())
+                .collect(),
+        ),
+        _ => unreachable!("groups only depend on other groups"),
+    })?;
+
+    let mut members: Vec<Vec<Item>> = vec![Vec::new(); groups.len()];
+    for position in order {
+        members[member_of[position]].push(domain[position].value.clone());
+    }
+
+    let mut batches: Vec<Option<(G, Vec<Item>)>> =
+        groups.into_iter().zip(members).map(Some).collect();
+    Ok(group_order
+        .into_iter()
+        .map(|group| batches[group].take().expect("every group is sorted once"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn sort_grouped_works() {
+        let domain = [
+            Node::new(1, vec![2, 5], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![3], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ];
+        // odd ids in one group, even ones in the other
+        assert_eq!(
+            sort_grouped(&domain, |node: &Node<i32, _>| node.id % 2),
+            Err(GroupError::CyclicGroups(vec![1, 0, 1]))
+        );
+        assert_eq!(
+            sort_grouped(&domain, |node: &Node<i32, _>| node.id > 2),
+            Ok(vec![
+                (true, vec!["cat", "mouse", "dog"]),
+                (false, vec!["world", "hello"]),
+            ])
+        );
+        assert_eq!(
+            sort_grouped(&domain[..1], |_: &Node<i32, _>| ()),
+            Err(GroupError::Sort(TopsortError::TargetNotFound(2)))
+        );
+    }
+
+    #[test]
+    fn cyclic_groups_display() {
+        let err: GroupError<i32, &str> = GroupError::CyclicGroups(vec!["data", "schema", "data"]);
+        assert_eq!(
+            err.to_string(),
+            "cyclic dependency between groups: `data` -> `schema` -> `data`"
+        );
+    }
+}
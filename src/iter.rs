@@ -0,0 +1,154 @@
+use crate::traversal::Traversal;
+use crate::{Node, TopsortError};
+use core::hash::Hash;
+use core::iter::FusedIterator;
+
+/// lazy iterator over nodes in topological order, created by [`topo_iter`]
+///
+/// yields an error at most once, after which the iteration ends
+pub struct TopoIter<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    /// missing if the traversal couldn't be set up, `pending` holds the reason
+    traversal: Option<Traversal<'a, Node<Id, Item>>>,
+    /// error to yield on the next call, e.g. target that wasn't found
+    pending: Option<TopsortError<Id>>,
+    done: bool,
+}
+
+impl<'a, Id, Item> Iterator for TopoIter<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    type Item = Result<&'a Node<Id, Item>, TopsortError<Id>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if let Some(err) = self.pending.take() {
+            self.done = true;
+            return Some(Err(err));
+        }
+
+        match self.traversal.as_mut()?.next_finished() {
+            Some(Ok(index)) => Some(Ok(&self.domain[index])),
+            Some(Err(err)) => {
+                self.done = true;
+                Some(Err(err))
+            }
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl<'a, Id, Item> FusedIterator for TopoIter<'a, Id, Item> where Id: Clone + Eq + Hash {}
+
+/// returns an iterator over nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// nodes are visited on demand, so stopping early skips the rest of the traversal
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     let first = topo_iter(&domain, "cat").next();
+///     assert_eq!(first.map(|node| node.map(|node| node.value)), Some(Ok("Odie")));
+///
+///     let ids: Result<Vec<_>, _> = topo_iter(&domain, "cat").map(|node| node.map(|node| node.id)).collect();
+///     assert_eq!(ids, Ok(vec!["dog", "cat"]));
+/// ```
+pub fn topo_iter<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> TopoIter<'_, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    let setup = Traversal::new(domain).and_then(|mut traversal| {
+        let index = traversal.find_index(&target)?;
+        traversal.start(index);
+        Ok(traversal)
+    });
+    let (traversal, pending) = match setup {
+        Ok(traversal) => (Some(traversal), None),
+        Err(err) => (None, Some(err)),
+    };
+
+    TopoIter {
+        domain,
+        traversal,
+        pending,
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yields_in_order() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+        ];
+        let values: Vec<_> = topo_iter(&domain, 1)
+            .map(|node| node.unwrap().value)
+            .collect();
+        assert_eq!(values, vec!["world", "cat", "hello"]);
+    }
+
+    #[test]
+    fn short_circuits() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4], "cat"),
+        ];
+        // the missing dependency of 3 is never reached
+        let values: Vec<_> = topo_iter(&domain, 1)
+            .take(1)
+            .map(|node| node.unwrap().value)
+            .collect();
+        assert_eq!(values, vec!["world"]);
+    }
+
+    #[test]
+    fn error_ends_iteration() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![1], "cat"),
+        ];
+        let mut iter = topo_iter(&domain, 1);
+        assert_eq!(
+            iter.next().map(|node| node.map(|node| node.id)),
+            Some(Ok(2))
+        );
+        assert_eq!(
+            iter.next().map(|node| node.map(|node| node.id)),
+            Some(Err(TopsortError::CyclicDependency(vec![1, 3, 1])))
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn target_not_found() {
+        let domain: [Node<i32, i32>; 0] = [];
+        let mut iter = topo_iter(&domain, 1);
+        assert_eq!(
+            iter.next().map(|node| node.map(|node| node.id)),
+            Some(Err(TopsortError::TargetNotFound(1)))
+        );
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,200 @@
+//! JSON node-link import and export
+//!
+//! the format is the one used by d3 and networkx: `{ "nodes": [{ "id": .. }], "links": [{ "source": .., "target": .. }] }`,
+//! a link from `source` to `target` makes `source` depend on `target`
+//!
+//! # Examples
+//! ```
+//!     use szyk::Node;
+//!     use szyk::json;
+//!
+//!     let domain = [
+//!         Node::new("cat", vec!["dog"], "Garfield"),
+//!         Node::new("dog", vec![], "Odie"),
+//!     ];
+//!     let json = json::to_json(&domain).unwrap();
+//!     assert_eq!(
+//!         json,
+//!         r#"{"nodes":[{"id":"cat"},{"id":"dog"}],"links":[{"source":"cat","target":"dog"}]}"#
+//!     );
+//!
+//!     let graph = json::parse::<String>(&json).unwrap();
+//!     assert_eq!(graph.sort(String::from("cat")).unwrap().len(), 2);
+//! ```
+
+use crate::collections::HashSet;
+use crate::traversal::first_index;
+use crate::{Graph, Node};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct NodeLink<Id> {
+    nodes: Vec<JsonNode<Id>>,
+    /// networkx names this field `edges` since 3.4
+    #[serde(alias = "edges", default = "Vec::new")]
+    links: Vec<Link<Id>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonNode<Id> {
+    id: Id,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Link<Id> {
+    source: Id,
+    target: Id,
+}
+
+/// serializes `domain` as a node-link document
+///
+/// dependencies missing from `domain` are listed as extra nodes after the others
+pub fn to_json<Id, Item>(domain: &[Node<Id, Item>]) -> Result<String, serde_json::Error>
+where
+    Id: Clone + Eq + Hash + Serialize,
+{
+    serde_json::to_string(&node_link(domain))
+}
+
+/// serializes `domain` as an indented node-link document, see [`to_json`]
+pub fn to_json_pretty<Id, Item>(domain: &[Node<Id, Item>]) -> Result<String, serde_json::Error>
+where
+    Id: Clone + Eq + Hash + Serialize,
+{
+    serde_json::to_string_pretty(&node_link(domain))
+}
+
+fn node_link<Id, Item>(domain: &[Node<Id, Item>]) -> NodeLink<&Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    let index = first_index(domain);
+    let mut nodes: Vec<JsonNode<&Id>> = domain
+        .iter()
+        .enumerate()
+        .filter(|(position, node)| index.get(&node.id) == Some(position))
+        .map(|(_, node)| JsonNode { id: &node.id })
+        .collect();
+
+    let mut missing = HashSet::new();
+    for dep in domain.iter().flat_map(|node| node.deps.iter()) {
+        if !index.contains_key(dep) && missing.insert(dep) {
+            nodes.push(JsonNode { id: dep });
+        }
+    }
+
+    let links = domain
+        .iter()
+        .flat_map(|node| {
+            node.deps.iter().map(move |dep| Link {
+                source: &node.id,
+                target: dep,
+            })
+        })
+        .collect();
+    NodeLink { nodes, links }
+}
+
+/// parses a node-link document into a [`Graph`]
+///
+/// nodes are added in the order they are listed or first used by a link, other fields are ignored
+///
+/// # Examples
+/// ```
+///     use szyk::json;
+///
+///     let graph = json::parse::<u32>(r#"{
+///         "directed": true,
+///         "nodes": [{ "id": 1, "label": "planks" }, { "id": 2 }],
+///         "links": [{ "source": 1, "target": 2 }, { "source": 3, "target": 1 }]
+///     }"#).unwrap();
+///     let order: Vec<_> = graph.nodes().iter().map(|node| node.id).collect();
+///     assert_eq!(order, vec![1, 2, 3]);
+/// ```
+pub fn parse<Id>(input: &str) -> Result<Graph<Id, ()>, serde_json::Error>
+where
+    Id: Clone + Eq + Hash + DeserializeOwned,
+{
+    let node_link: NodeLink<Id> = serde_json::from_str(input)?;
+    let mut graph = Graph::new();
+    for node in node_link.nodes {
+        if !graph.contains(&node.id) {
+            graph.add_node(node.id, ());
+        }
+    }
+
+    for link in node_link.links {
+        for id in [&link.source, &link.target] {
+            if !graph.contains(id) {
+                graph.add_node(id.clone(), ());
+            }
+        }
+        // can't fail, both endpoints were just added
+        let _ = graph.add_edge(link.source, link.target);
+    }
+    Ok(graph)
+}
+
+/// parses a node-link document into nodes, see [`parse`]
+pub fn from_json<Id>(input: &str) -> Result<Vec<Node<Id, ()>>, serde_json::Error>
+where
+    Id: Clone + Eq + Hash + DeserializeOwned,
+{
+    parse(input).map(Graph::into_nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(1, vec![], ()),
+        ];
+        assert_eq!(
+            to_json(&domain).unwrap(),
+            r#"{"nodes":[{"id":1},{"id":2},{"id":3}],"links":[{"source":1,"target":2},{"source":1,"target":3},{"source":2,"target":3}]}"#
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let domain = vec![
+            Node::new(String::from("a"), vec![String::from("b")], ()),
+            Node::new(String::from("b"), vec![], ()),
+        ];
+        assert_eq!(
+            from_json(&to_json_pretty(&domain).unwrap()).unwrap(),
+            domain
+        );
+    }
+
+    #[test]
+    fn parse_edges_alias() {
+        let nodes = from_json::<String>(
+            r#"{"nodes": [], "edges": [{"source": "a", "target": "b", "weight": 2}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            nodes,
+            vec![
+                Node::new(String::from("a"), vec![String::from("b")], ()),
+                Node::new(String::from("b"), vec![], ()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(from_json::<String>(r#"{"links": []}"#).is_err());
+        assert!(from_json::<u32>(r#"{"nodes": [{"id": "a"}]}"#).is_err());
+        assert!(from_json::<u32>(r#"{"nodes": [{"id": 1}], "links": [{"source": 1}]}"#).is_err());
+    }
+}
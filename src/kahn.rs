@@ -0,0 +1,913 @@
+use crate::traversal::build_index;
+use crate::{HasDependencies, Node, TopsortError};
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::hash::Hash;
+use core::iter::FusedIterator;
+use core::num::NonZeroUsize;
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// using Kahn's algorithm instead of a depth-first search
+///
+/// nodes become ready once all of their dependencies are emitted and are emitted in the order they became ready,
+/// nodes ready at the start follow the order of `domain`; on cycles all nodes involved in them are reported
+/// as [`TopsortError::CyclicNodes`], unless a node depends on itself
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     assert_eq!(sort_kahn(&domain, "cat"), Ok(vec!["Odie", "Garfield"]));
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec!["mouse"], "Odie"),
+///         Node::new("mouse", vec!["dog"], "Jerry"),
+///     ];
+///     assert_eq!(sort_kahn(&domain, "cat"), Err(TopsortError::CyclicNodes(vec!["dog", "mouse"])));
+/// ```
+pub fn sort_kahn<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let order = kahn(domain, target, &mut VecDeque::new())?;
+    Ok(order
+        .into_iter()
+        .map(|position| domain[position].value.clone())
+        .collect())
+}
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// always emitting the smallest id out of the nodes with all dependencies emitted
+///
+/// the result doesn't depend on the order of `domain` or `deps`, on cycles all nodes involved in them
+/// are reported as [`TopsortError::CyclicNodes`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["mouse", "dog"], "Garfield"),
+///         Node::new("mouse", vec![], "Jerry"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     assert_eq!(sort_lexicographic(&domain, "cat"), Ok(vec!["Odie", "Jerry", "Garfield"]));
+/// ```
+pub fn sort_lexicographic<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Ord,
+    Item: Clone,
+{
+    let order = kahn(domain, target, &mut SmallestId::new(domain))?;
+    Ok(order
+        .into_iter()
+        .map(|position| domain[position].value.clone())
+        .collect())
+}
+
+/// returns values of nodes from `domain` in a random topological order picked with `seed`, ending on
+/// the node with id of `target`
+///
+/// every step emits a random node out of the ones with all dependencies emitted, so any valid order
+/// can come out, though not all with the same probability; the same seed gives the same order on the
+/// same platform and version of `rand`, on cycles all nodes involved in them are reported as
+/// [`TopsortError::CyclicNodes`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["wood"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     let result = sort_random(&domain, "wooden pickaxe", 7).unwrap();
+///     assert_eq!(result.first(), Some(&"Wood"));
+///     assert_eq!(result.last(), Some(&"Pickaxe"));
+///     assert_eq!(sort_random(&domain, "wooden pickaxe", 7), Ok(result));
+/// ```
+#[cfg(feature = "rand")]
+pub fn sort_random<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    seed: u64,
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let order = kahn(domain, target, &mut RandomPick::new(seed))?;
+    Ok(order
+        .into_iter()
+        .map(|position| domain[position].value.clone())
+        .collect())
+}
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// emitting the smallest node according to `cmp` out of the nodes with all dependencies emitted
+///
+/// ties are broken by the position in `domain`, finding the smallest node takes time linear in the
+/// number of ready nodes; on cycles all nodes involved in them are reported as
+/// [`TopsortError::CyclicNodes`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks", "string"], 1),
+///         Node::new("planks", vec![], 2),
+///         Node::new("sticks", vec![], 5),
+///         Node::new("string", vec![], 3),
+///     ];
+///     // most expensive first
+///     let result = sort_by(&domain, "wooden pickaxe", |a, b| b.value.cmp(&a.value));
+///     assert_eq!(result, Ok(vec![5, 3, 2, 1]));
+/// ```
+pub fn sort_by<Id, Item, F>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    cmp: F,
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+    F: FnMut(&Node<Id, Item>, &Node<Id, Item>) -> Ordering,
+{
+    let order = kahn(domain, target, &mut Compared::new(domain, cmp))?;
+    Ok(order
+        .into_iter()
+        .map(|position| domain[position].value.clone())
+        .collect())
+}
+
+/// nodes with all dependencies emitted, waiting to be emitted by [`kahn`]
+trait ReadyQueue {
+    fn push(&mut self, position: usize);
+    fn pop(&mut self) -> Option<usize>;
+}
+
+/// emits nodes in the order they became ready
+impl ReadyQueue for VecDeque<usize> {
+    fn push(&mut self, position: usize) {
+        self.push_back(position);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.pop_front()
+    }
+}
+
+/// emits the ready node with the smallest id, ties are broken by the position in the domain
+struct SmallestId<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    heap: BinaryHeap<Reverse<(&'a Id, usize)>>,
+}
+
+impl<'a, Id, Item> SmallestId<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn new(domain: &'a [Node<Id, Item>]) -> Self {
+        Self {
+            domain,
+            heap: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<'a, Id, Item> ReadyQueue for SmallestId<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash + Ord,
+{
+    fn push(&mut self, position: usize) {
+        self.heap
+            .push(Reverse((&self.domain[position].id, position)));
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.heap.pop().map(|Reverse((_, position))| position)
+    }
+}
+
+/// emits the smallest ready node according to `cmp`, ties are broken by the position in the domain
+struct Compared<'a, Id, Item, F>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    ready: Vec<usize>,
+    cmp: F,
+}
+
+impl<'a, Id, Item, F> Compared<'a, Id, Item, F>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn new(domain: &'a [Node<Id, Item>], cmp: F) -> Self {
+        Self {
+            domain,
+            ready: Vec::new(),
+            cmp,
+        }
+    }
+}
+
+impl<'a, Id, Item, F> ReadyQueue for Compared<'a, Id, Item, F>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>, &Node<Id, Item>) -> Ordering,
+{
+    fn push(&mut self, position: usize) {
+        self.ready.push(position);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let mut smallest = 0;
+        for nth in 1..self.ready.len() {
+            let (candidate, current) = (self.ready[nth], self.ready[smallest]);
+            let order = (self.cmp)(&self.domain[candidate], &self.domain[current])
+                .then(candidate.cmp(&current));
+            if order == Ordering::Less {
+                smallest = nth;
+            }
+        }
+
+        if self.ready.is_empty() {
+            None
+        } else {
+            Some(self.ready.swap_remove(smallest))
+        }
+    }
+}
+
+/// emits a random ready node
+#[cfg(feature = "rand")]
+struct RandomPick {
+    ready: Vec<usize>,
+    rng: rand::rngs::SmallRng,
+}
+
+#[cfg(feature = "rand")]
+impl RandomPick {
+    fn new(seed: u64) -> Self {
+        Self {
+            ready: Vec::new(),
+            rng: rand::SeedableRng::seed_from_u64(seed),
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl ReadyQueue for RandomPick {
+    fn push(&mut self, position: usize) {
+        self.ready.push(position);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        if self.ready.is_empty() {
+            return None;
+        }
+        let nth = rand::Rng::random_range(&mut self.rng, 0..self.ready.len());
+        Some(self.ready.swap_remove(nth))
+    }
+}
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// split into chunks of at most `size` nodes with every node in a later chunk than all of its dependencies
+///
+/// chunks are filled greedily with nodes whose dependencies are all in earlier chunks, in the order they
+/// became ready like in [`sort_kahn`]; on cycles all nodes involved in them are reported as
+/// [`TopsortError::CyclicNodes`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::num::NonZeroUsize;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["wood"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///         Node::new("stone", vec![], "Stone"),
+///     ];
+///     let result = sort_chunks(&domain, "wooden pickaxe", NonZeroUsize::new(2).unwrap());
+///     assert_eq!(
+///         result,
+///         Ok(vec![vec!["Wood"], vec!["Planks", "Sticks"], vec!["Pickaxe"]])
+///     );
+/// ```
+pub fn sort_chunks<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    size: NonZeroUsize,
+) -> Result<Vec<Vec<Item>>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut closure = Closure::new(domain, target)?;
+    let mut ready: VecDeque<usize> = closure.ready().collect();
+    let mut chunks = Vec::new();
+    while !ready.is_empty() {
+        let chunk: Vec<usize> = ready.drain(..size.get().min(ready.len())).collect();
+        // dependents are released only once the chunk is closed, so they land in a later one
+        for &position in chunk.iter() {
+            closure.emit(position, &mut |dependent| ready.push_back(dependent));
+        }
+        chunks.push(
+            chunk
+                .into_iter()
+                .map(|position| domain[position].value.clone())
+                .collect(),
+        );
+    }
+
+    closure.check(domain)?;
+    Ok(chunks)
+}
+
+/// lazy iterator over generations of nodes in topological order, created by [`generations`]
+///
+/// yields an error at most once, after which the iteration ends
+pub struct Generations<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    /// missing if the closure couldn't be built, `pending` holds the reason
+    closure: Option<Closure>,
+    /// positions of nodes of the next generation
+    ready: Vec<usize>,
+    /// error to yield on the next call, e.g. target that wasn't found
+    pending: Option<TopsortError<Id>>,
+    done: bool,
+}
+
+impl<'a, Id, Item> Iterator for Generations<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    type Item = Result<Vec<&'a Node<Id, Item>>, TopsortError<Id>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if let Some(err) = self.pending.take() {
+            self.done = true;
+            return Some(Err(err));
+        }
+
+        let closure = self.closure.as_mut()?;
+        if self.ready.is_empty() {
+            self.done = true;
+            return closure.check(self.domain).err().map(Err);
+        }
+
+        let generation = core::mem::take(&mut self.ready);
+        // dependents are released only once the generation is complete, so they land in the next one
+        let ready = &mut self.ready;
+        for &position in generation.iter() {
+            closure.emit(position, &mut |dependent| ready.push(dependent));
+        }
+        Some(Ok(generation
+            .into_iter()
+            .map(|position| &self.domain[position])
+            .collect()))
+    }
+}
+
+impl<'a, Id, Item> FusedIterator for Generations<'a, Id, Item> where Id: Clone + Eq + Hash {}
+
+/// returns an iterator over generations of nodes from `domain` ending on the node with id of `target`,
+/// every generation holding the nodes whose dependencies are all in earlier ones
+///
+/// nodes of one generation don't depend on each other, so each can run in parallel once the previous
+/// one finished; the first generation follows the order of `domain`, later ones the order their nodes
+/// became ready like in [`sort_kahn`]; on cycles the generations before them are yielded, then nodes
+/// involved in them are reported as [`TopsortError::CyclicNodes`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["wood"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     let waves: Vec<Vec<_>> = generations(&domain, "wooden pickaxe")
+///         .map(|generation| generation.unwrap().iter().map(|node| node.value).collect())
+///         .collect();
+///     assert_eq!(waves, vec![vec!["Wood"], vec!["Planks", "Sticks"], vec!["Pickaxe"]]);
+/// ```
+pub fn generations<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> Generations<'_, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    match Closure::new(domain, target) {
+        Ok(closure) => Generations {
+            domain,
+            ready: closure.ready().collect(),
+            closure: Some(closure),
+            pending: None,
+            done: false,
+        },
+        Err(err) => Generations {
+            domain,
+            closure: None,
+            ready: Vec::new(),
+            pending: Some(err),
+            done: false,
+        },
+    }
+}
+
+/// dependency closure of a target with dependencies resolved to positions in the domain, soft
+/// constraints between its nodes are added as dependencies
+struct Closure {
+    in_closure: Vec<bool>,
+    deps: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+    /// number of dependencies of every node which weren't emitted yet
+    pending: Vec<usize>,
+}
+
+impl Closure {
+    fn new<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> Result<Self, TopsortError<Id>>
+    where
+        Id: Clone + Eq + Hash,
+    {
+        let index = build_index(domain)?;
+        let find = |id: &Id| match index.get(id) {
+            Some(&position) => Ok(position),
+            None => Err(TopsortError::TargetNotFound(id.clone())),
+        };
+
+        // collect the dependency closure of `target`
+        let mut in_closure = vec![false; domain.len()];
+        let mut stack = vec![find(&target)?];
+        while let Some(position) = stack.pop() {
+            if in_closure[position] {
+                continue;
+            }
+            in_closure[position] = true;
+            for dep in domain[position].deps.iter() {
+                if !domain[position].is_optional(dep) || index.contains_key(dep) {
+                    stack.push(find(dep)?);
+                }
+            }
+        }
+
+        // count unemitted dependencies and record dependents of every node in the closure
+        let mut deps: Vec<Vec<usize>> = vec![Vec::new(); domain.len()];
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); domain.len()];
+        for position in (0..domain.len()).filter(|&position| in_closure[position]) {
+            for dep in domain[position].deps.iter() {
+                if domain[position].is_optional(dep) && !index.contains_key(dep) {
+                    continue;
+                }
+                let dep = find(dep)?;
+                if dep == position {
+                    return Err(TopsortError::SelfDependency(domain[position].id.clone()));
+                }
+                deps[position].push(dep);
+                dependents[dep].push(position);
+            }
+        }
+
+        // soft constraints only order nodes already in the closure
+        for position in (0..domain.len()).filter(|&position| in_closure[position]) {
+            let node = &domain[position];
+            let after = node.after.iter().map(|id| (position, id));
+            let before = node.before.iter().map(|id| (position, id));
+            for (dependent, dep) in after
+                .filter_map(|(dependent, id)| Some((dependent, *index.get(id)?)))
+                .chain(before.filter_map(|(dep, id)| Some((*index.get(id)?, dep))))
+            {
+                if dependent != dep && in_closure[dependent] && in_closure[dep] {
+                    deps[dependent].push(dep);
+                    dependents[dep].push(dependent);
+                }
+            }
+        }
+        let pending = deps.iter().map(Vec::len).collect();
+
+        Ok(Self {
+            in_closure,
+            deps,
+            dependents,
+            pending,
+        })
+    }
+
+    /// positions of nodes in the closure without dependencies, in the order of the domain
+    fn ready(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.in_closure.len())
+            .filter(move |&position| self.in_closure[position] && self.pending[position] == 0)
+    }
+
+    /// marks the node at `position` as emitted, calling `ready` with dependents left without pending dependencies
+    fn emit<F>(&mut self, position: usize, ready: &mut F)
+    where
+        F: FnMut(usize),
+    {
+        for &dependent in self.dependents[position].iter() {
+            self.pending[dependent] -= 1;
+            if self.pending[dependent] == 0 {
+                ready(dependent);
+            }
+        }
+    }
+
+    /// fails if any node couldn't be emitted because of a cycle
+    fn check<Id, Item>(&self, domain: &[Node<Id, Item>]) -> Result<(), TopsortError<Id>>
+    where
+        Id: Clone + Eq + Hash,
+    {
+        if self.pending.iter().any(|&count| count > 0) {
+            return Err(TopsortError::CyclicNodes(cyclic_nodes(
+                domain,
+                &self.pending,
+                &self.deps,
+                &self.dependents,
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Kahn's algorithm over the dependency closure of `target`, returns positions of nodes in the order
+/// they were taken out of `queue`
+fn kahn<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    queue: &mut Q,
+) -> Result<Vec<usize>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Q: ReadyQueue,
+{
+    let mut closure = Closure::new(domain, target)?;
+    for position in closure.ready() {
+        queue.push(position);
+    }
+
+    let mut order = Vec::new();
+    while let Some(position) = queue.pop() {
+        order.push(position);
+        closure.emit(position, &mut |dependent| queue.push(dependent));
+    }
+
+    closure.check(domain)?;
+    Ok(order)
+}
+
+/// ids of nodes left with `pending` dependencies which are also depended on by such nodes,
+/// which drops nodes that only depend on cycles without being part of one
+fn cyclic_nodes<Id, Item>(
+    domain: &[Node<Id, Item>],
+    pending: &[usize],
+    deps: &[Vec<usize>],
+    dependents: &[Vec<usize>],
+) -> Vec<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut remaining: Vec<bool> = pending.iter().map(|&count| count > 0).collect();
+    let mut blocked: Vec<usize> = (0..domain.len())
+        .map(|position| {
+            dependents[position]
+                .iter()
+                .filter(|&&dependent| remaining[dependent])
+                .count()
+        })
+        .collect();
+
+    // peel off remaining nodes that no remaining node depends on
+    let mut stack: Vec<usize> = (0..domain.len())
+        .filter(|&position| remaining[position] && blocked[position] == 0)
+        .collect();
+    while let Some(position) = stack.pop() {
+        remaining[position] = false;
+        for &dep in deps[position].iter() {
+            if remaining[dep] {
+                blocked[dep] -= 1;
+                if blocked[dep] == 0 {
+                    stack.push(dep);
+                }
+            }
+        }
+    }
+
+    (0..domain.len())
+        .filter(|&position| remaining[position])
+        .map(|position| domain[position].id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generations_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![4], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ];
+        let ids: Vec<Vec<i32>> = generations(&domain, 1)
+            .map(|generation| generation.unwrap().iter().map(|node| node.id).collect())
+            .collect();
+        assert_eq!(ids, vec![vec![3, 4], vec![2], vec![1]]);
+
+        let mut missing = generations(&domain, 6);
+        assert_eq!(missing.next(), Some(Err(TopsortError::TargetNotFound(6))));
+        assert_eq!(missing.next(), None);
+    }
+
+    #[test]
+    fn generations_cycle() {
+        let domain = [
+            Node::new(1, vec![2, 4], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![2], ()),
+            Node::new(4, vec![], ()),
+        ];
+        let mut generations = generations(&domain, 1);
+        assert_eq!(
+            generations
+                .next()
+                .map(|generation| generation.map(|nodes| nodes.len())),
+            Some(Ok(1))
+        );
+        assert!(matches!(
+            generations.next(),
+            Some(Err(TopsortError::CyclicNodes(_)))
+        ));
+        assert_eq!(generations.next(), None);
+    }
+
+    #[test]
+    fn sort_kahn_works() {
+        let result = sort_kahn(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+                Node::new(4, vec![], "dog"),
+            ],
+            1,
+        );
+        assert_eq!(result, Ok(vec!["world", "cat", "hello"]));
+    }
+
+    #[test]
+    fn sort_kahn_wide() {
+        let result = sort_kahn(
+            &[
+                Node::new(1, vec![4, 3, 2], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![], "cat"),
+                Node::new(4, vec![5], "dog"),
+                Node::new(5, vec![], "mouse"),
+            ],
+            1,
+        );
+        assert_eq!(result, Ok(vec!["world", "cat", "mouse", "dog", "hello"]));
+    }
+
+    #[test]
+    fn sort_kahn_target_not_found() {
+        let result = sort_kahn(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+            ],
+            1,
+        );
+        assert_eq!(result, Err(TopsortError::TargetNotFound(3)));
+    }
+
+    #[test]
+    fn sort_kahn_reports_all_cycles() {
+        let result = sort_kahn(
+            &[
+                Node::new(1, vec![2, 4], "hello"),
+                Node::new(2, vec![3], "world"),
+                Node::new(3, vec![2], "cat"),
+                Node::new(4, vec![5], "dog"),
+                Node::new(5, vec![4, 6], "mouse"),
+                Node::new(6, vec![], "bird"),
+            ],
+            1,
+        );
+        assert_eq!(result, Err(TopsortError::CyclicNodes(vec![2, 3, 4, 5])));
+    }
+
+    #[test]
+    fn sort_chunks_works() {
+        let domain = [
+            Node::new(1, vec![2, 3, 4], 1),
+            Node::new(2, vec![5], 2),
+            Node::new(3, vec![5], 3),
+            Node::new(4, vec![], 4),
+            Node::new(5, vec![], 5),
+            Node::new(6, vec![], 6),
+        ];
+        assert_eq!(
+            sort_chunks(&domain, 1, NonZeroUsize::new(2).unwrap()),
+            Ok(vec![vec![4, 5], vec![2, 3], vec![1]])
+        );
+        assert_eq!(
+            sort_chunks(&domain, 1, NonZeroUsize::new(1).unwrap()),
+            Ok(vec![vec![4], vec![5], vec![2], vec![3], vec![1]])
+        );
+        assert_eq!(
+            sort_chunks(&domain, 1, NonZeroUsize::new(10).unwrap()),
+            Ok(vec![vec![4, 5], vec![2, 3], vec![1]])
+        );
+        assert_eq!(
+            sort_chunks(&domain, 7, NonZeroUsize::new(2).unwrap()),
+            Err(TopsortError::TargetNotFound(7))
+        );
+    }
+
+    #[test]
+    fn sort_chunks_cycle() {
+        let result = sort_chunks(
+            &[
+                Node::new(1, vec![2], ()),
+                Node::new(2, vec![3], ()),
+                Node::new(3, vec![2], ()),
+            ],
+            1,
+            NonZeroUsize::new(3).unwrap(),
+        );
+        assert_eq!(result, Err(TopsortError::CyclicNodes(vec![2, 3])));
+    }
+
+    #[test]
+    fn sort_lexicographic_works() {
+        let result = sort_lexicographic(
+            &[
+                Node::new(1, vec![5, 3], "hello"),
+                Node::new(5, vec![], "world"),
+                Node::new(3, vec![4], "cat"),
+                Node::new(4, vec![], "dog"),
+                Node::new(2, vec![], "mouse"),
+            ],
+            1,
+        );
+        assert_eq!(result, Ok(vec!["dog", "cat", "world", "hello"]));
+    }
+
+    #[test]
+    fn sort_lexicographic_ignores_input_order() {
+        let mut domain = vec![
+            Node::new("c", vec!["b", "a"], 3),
+            Node::new("a", vec![], 1),
+            Node::new("b", vec![], 2),
+            Node::new("d", vec!["a"], 4),
+        ];
+        let expected = sort_lexicographic(&domain, "c");
+        assert_eq!(expected, Ok(vec![1, 2, 3]));
+        domain.reverse();
+        domain[3].deps.reverse();
+        assert_eq!(sort_lexicographic(&domain, "c"), expected);
+    }
+
+    #[test]
+    fn sort_lexicographic_cycle() {
+        let result = sort_lexicographic(
+            &[
+                Node::new(1, vec![2], "hello"),
+                Node::new(2, vec![1], "world"),
+            ],
+            1,
+        );
+        assert_eq!(result, Err(TopsortError::CyclicNodes(vec![1, 2])));
+    }
+
+    #[test]
+    fn sort_by_works() {
+        let domain = [
+            Node::new(1, vec![2, 3, 4], 1),
+            Node::new(2, vec![5], 2),
+            Node::new(3, vec![], 2),
+            Node::new(4, vec![], 9),
+            Node::new(5, vec![], 1),
+        ];
+        // largest value first, a dependency holds back its more valuable dependents
+        let result = sort_by(&domain, 1, |a, b| b.value.cmp(&a.value));
+        assert_eq!(result, Ok(vec![9, 2, 1, 2, 1]));
+        let result = sort_by(&domain, 1, |a, b| a.value.cmp(&b.value));
+        assert_eq!(result, Ok(vec![1, 2, 2, 9, 1]));
+
+        let ids = sort_by(&domain, 1, |_, _| Ordering::Equal);
+        assert_eq!(ids, Ok(vec![2, 9, 1, 2, 1]));
+    }
+
+    #[test]
+    fn sort_by_cycle() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![1], "world"),
+        ];
+        assert_eq!(
+            sort_by(&domain, 1, |a, b| a.value.cmp(b.value)),
+            Err(TopsortError::CyclicNodes(vec![1, 2]))
+        );
+    }
+
+    #[test]
+    fn soft_constraints() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello").with_before(vec![4]),
+            Node::new(2, vec![], "world").with_after(vec![3, 2]),
+            Node::new(3, vec![], "cat").with_before(vec![5]),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![], "mouse").with_before(vec![3]),
+        ];
+        assert_eq!(sort_kahn(&domain, 1), Ok(vec!["cat", "world", "hello"]));
+        assert_eq!(sort_lexicographic(&domain, 2), Ok(vec!["world"]));
+
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world").with_after(vec![3]),
+            Node::new(3, vec![], "cat").with_after(vec![2]),
+        ];
+        assert_eq!(
+            sort_kahn(&domain, 1),
+            Err(TopsortError::CyclicNodes(vec![2, 3]))
+        );
+    }
+
+    #[test]
+    fn sort_kahn_self_dependency() {
+        let result = sort_kahn(&[Node::new(1, vec![1], "hello")], 1);
+        assert_eq!(result, Err(TopsortError::SelfDependency(1)));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sort_random_is_valid() {
+        let domain = [
+            Node::new(1, vec![2, 3, 4], 1),
+            Node::new(2, vec![5], 2),
+            Node::new(3, vec![5], 3),
+            Node::new(4, vec![], 4),
+            Node::new(5, vec![], 5),
+        ];
+        let mut seen = Vec::new();
+        for seed in 0..50 {
+            let order = sort_random(&domain, 1, seed).unwrap();
+            assert_eq!(crate::verify_order(&domain, &order), Ok(()));
+            if !seen.contains(&order) {
+                seen.push(order);
+            }
+        }
+        assert!(seen.len() > 1);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sort_random_cycle() {
+        let result = sort_random(
+            &[
+                Node::new(1, vec![2], "hello"),
+                Node::new(2, vec![1], "world"),
+            ],
+            1,
+            0,
+        );
+        assert_eq!(result, Err(TopsortError::CyclicNodes(vec![1, 2])));
+    }
+}
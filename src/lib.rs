@@ -16,215 +16,165 @@
 //!     );
 //!     assert_eq!(result, Ok(vec!["Wood", "Planks", "Sticks", "Pickaxe"]));
 //! ```
-
-#[derive(Debug, PartialEq)]
-pub struct Node<Id, Item>
-where
-    Id: Copy + Eq,
-{
-    /// unique identifier
-    pub id: Id,
-    /// list of dependencies
-    pub deps: Vec<Id>,
-    /// value stored in the node
-    pub value: Item,
-}
-
-impl<Id, Item> Node<Id, Item>
-where
-    Id: Copy + Eq,
-{
-    pub fn new(id: Id, deps: Vec<Id>, value: Item) -> Self {
-        Self { id, deps, value }
-    }
-}
-
-#[derive(PartialEq, Debug)]
-pub enum TopsortError<Id> {
-    /// * `Id` - target that wasn't found
-    TargetNotFound(Id),
-    /// * `Id` - target that depends on itself
-    CyclicDependency(Id),
-}
-
-fn find_index<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> Result<usize, TopsortError<Id>>
-where
-    Id: Copy + Eq,
-{
-    match domain.iter().position(|node| node.id == target) {
-        Some(index) => Ok(index),
-        None => Err(TopsortError::TargetNotFound(target)),
-    }
-}
-
-fn visit<Id, Item, F>(
-    domain: &[Node<Id, Item>],
-    target: Id,
-    cb: &mut F,
-    visited: &mut Vec<bool>,
-    current_path: &mut Vec<Id>,
-) -> Result<(), TopsortError<Id>>
-where
-    Id: Copy + Eq,
-    F: FnMut(&Node<Id, Item>),
-{
-    let index = find_index(domain, target)?;
-
-    if visited[index] {
-        return Ok(());
-    }
-
-    // detect cyclic dependencies
-    if current_path.contains(&target) {
-        return Err(TopsortError::CyclicDependency(target));
-    }
-
-    // push id to the stack
-    current_path.push(target);
-
-    // visit dependencies
-    for dep in domain[index].deps.iter() {
-        visit(domain, *dep, cb, visited, current_path)?;
-    }
-
-    // call callback
-    cb(&domain[index]);
-    visited[index] = true;
-
-    // pop id from the stack
-    current_path.pop();
-    Ok(())
-}
-
-/// calls `cb` with nodes from `domain` in topological order, ending on the node with id of `target`
+//!
+//! # Ordering
+//! When several valid orderings exist, the depth-first sorts ([`sort`], [`sort_cb`], [`sort_multi`],
+//! [`sort_all`] and their variants) always pick the same one: dependencies are visited in the order
+//! they are listed in `deps`, multiple targets in the order they are given and, for [`sort_all`],
+//! nodes in the order they appear in the domain. The order of unrelated nodes in the domain has no
+//! effect on the result of a single-target sort.
+//!
+//! Soft constraints set with [`Node::with_after`] and [`Node::with_before`] are only respected by
+//! the sorts based on Kahn's algorithm: [`sort_kahn`], [`sort_by`], [`sort_lexicographic`],
+//! [`sort_chunks`], [`generations`] and `sort_random`. The depth-first sorts, [`Graph::sort`] and
+//! [`Sorter`] ignore them.
+//!
+//! # Features
+//! * `alloc` - builds without `std` using `hashbrown` maps, requires disabling default features
+//! * `cli` - the `szyk` binary printing the order of an edge list or a DOT file, run `szyk --help`
+//! * `derive` - `#[derive(TopoNode)]` implementing [`HasDependencies`] for structs
+//! * `ffi` - the `ffi` module with a C API over graphs of integer ids, see `include/szyk.h`
+//! * `futures` - `exec::run_async` awaiting tasks of nodes concurrently
+//! * `graphml` - the `graphml` module reading and writing GraphML documents
+//! * `json` - the `json` module reading and writing the node-link JSON format, enables `serde`
+//! * `miette` - `CycleDiagnostic` rendering cycle errors as a labelled chain of ids
+//! * `petgraph` - conversions between [`Graph`] and `petgraph::graph::DiGraph`, `sort_petgraph`
+//! * `python` - the `python` module, a `pyo3` extension module named `szyk`, build it with `maturin`
+//! * `rand` - `sort_random` picking a random valid order from a seed
+//! * `rayon` - the `exec` module running tasks of nodes in parallel, `par_sort_all`
+//! * `serde` - `Serialize`/`Deserialize` for [`Node`], [`Graph`] and errors
+//! * `smallvec` - [`Deps`] stores up to 4 dependencies of a [`Node`] inline
+//! * `std` - enabled by default, `SharedGraph` sharing a [`Graph`] between threads
+//! * `toml` - `manifest::from_toml` loading nodes from a TOML manifest, enables `serde`
+//! * `wasm` - the `wasm` module exporting a graph of string ids to JavaScript with `wasm-bindgen`
+//! * `yaml` - `manifest::from_yaml` loading nodes from a YAML manifest, enables `serde`
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("either the `std` or the `alloc` feature has to be enabled");
+
+extern crate alloc;
+
+mod analysis;
+mod bitset;
+mod builder;
+mod closure;
+mod collections;
+mod compact;
+mod const_sort;
+mod cycles;
+mod dependency_graph;
+#[cfg(feature = "miette")]
+mod diagnostic;
+mod diff;
+pub mod dot;
+mod dynamic;
+mod error;
+#[cfg(any(feature = "rayon", feature = "futures"))]
+pub mod exec;
+mod feedback;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fixed;
+mod graph;
+#[cfg(feature = "graphml")]
+pub mod graphml;
+mod groups;
+mod iter;
+#[cfg(feature = "json")]
+pub mod json;
+mod kahn;
+mod macros;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub mod manifest;
+pub mod mermaid;
+mod node;
+mod orders;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+mod planner;
+mod provenance;
+#[cfg(feature = "python")]
+pub mod python;
+mod query;
+mod reduction;
+mod scc;
+#[cfg(feature = "std")]
+mod shared;
+mod sort;
+mod sorter;
+mod traversal;
+mod validate;
+mod visitor;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "petgraph")]
+pub use crate::petgraph::sort_petgraph;
+pub use analysis::{critical_path, depths, levels, makespan, stats, Depth, Schedule, Stats};
+pub use bitset::BitSet;
+pub use builder::GraphBuilder;
+pub use closure::TransitiveClosure;
+pub use compact::CompactGraph;
+pub use const_sort::{sort_const, sort_const_named};
+pub use cycles::find_cycles;
+pub use dependency_graph::{sort_graph, sort_graph_all, DependencyGraph};
+#[cfg(feature = "miette")]
+pub use diagnostic::CycleDiagnostic;
+pub use diff::{diff, reordered_nodes, Diff};
+pub use dynamic::{DynamicGraph, Subscription};
+pub use error::{DetailedError, TopsortError, TrySortError, Warning};
+pub use feedback::suggest_edge_removals;
+pub use fixed::{sort_all_fixed, sort_fixed, FixedError, FixedOrder};
+pub use graph::{Graph, MergePolicy};
+pub use groups::{sort_grouped, GroupError};
+pub use iter::{topo_iter, TopoIter};
+#[cfg(feature = "rand")]
+pub use kahn::sort_random;
+pub use kahn::{generations, sort_by, sort_chunks, sort_kahn, sort_lexicographic, Generations};
+pub use node::{Attributes, Deps, Edge, HasDependencies, Node};
+pub use orders::{all_orders, count_orders, AllOrders};
+#[cfg(feature = "rayon")]
+pub use par::par_sort_all;
+pub use planner::Planner;
+pub use provenance::{declared_in, ErrorContext, LocatedError, Provenance};
+pub use query::{
+    ancestors, dependents_of, descendants, edges_of_kind, is_reachable, rebuild_order, roots,
+    shortest_dependency_path, sinks,
+};
+pub use reduction::transitive_reduction;
+pub use scc::{condense, scc, sort_condensed};
+#[cfg(feature = "std")]
+pub use shared::SharedGraph;
+pub use sort::*;
+pub use sorter::Sorter;
+/// implements [`HasDependencies`] for a struct, see the `szyk-derive` crate for its attributes
 ///
 /// # Examples:
 /// ```
 ///     use szyk::*;
 ///
-///     let mut out = Vec::new();
-///     let result = sort_cb(
-///         &[
-///             Node::new("cat", vec!["dog"], "Garfield"),
-///             Node::new("dog", vec![], "Odie"),
-///         ],
-///         "cat",
-///         &mut |node| {
-///             out.push(node.id);
-///         }
-///     );
-///     assert_eq!(result, Ok(()));
-///     assert_eq!(out, vec!["dog", "cat"]);
-/// ```
-pub fn sort_cb<Id, Item, F>(
-    domain: &[Node<Id, Item>],
-    target: Id,
-    cb: &mut F,
-) -> Result<(), TopsortError<Id>>
-where
-    Id: Copy + Eq,
-    F: FnMut(&Node<Id, Item>),
-{
-    let size = domain.into_iter().size_hint().0;
-    let mut visited: Vec<bool> = Vec::with_capacity(size);
-    visited.resize(size, false);
-    let mut current_path: Vec<Id> = Vec::new();
-    visit(domain, target, cb, &mut visited, &mut current_path)
-}
-
-/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`
-///
-/// # Examples:
-/// ```
-///     use szyk::*;
+///     #[derive(TopoNode)]
+///     struct Package {
+///         #[topo(id)]
+///         name: &'static str,
+///         deps: Vec<&'static str>,
+///         #[topo(optional)]
+///         features: Vec<&'static str>,
+///     }
 ///
-///     let result = sort(
-///         &[
-///             Node::new("cat", vec!["dog"], "Garfield"),
-///             Node::new("dog", vec![], "Odie"),
-///         ],
-///         "cat",
-///     );
-///     assert_eq!(result, Ok(vec!["Odie", "Garfield"]));
+///     let packages = [
+///         Package { name: "app", deps: vec!["serde", "log"], features: vec!["log"] },
+///         Package { name: "serde", deps: vec![], features: vec![] },
+///     ];
+///     let sorted = sort_items(&packages, "app").unwrap();
+///     let names: Vec<_> = sorted.iter().map(|package| package.name).collect();
+///     assert_eq!(names, vec!["serde", "app"]);
 /// ```
-pub fn sort<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> Result<Vec<Item>, TopsortError<Id>>
-where
-    Id: Copy + Eq,
-    Item: Clone,
-{
-    let mut out = Vec::new();
-    sort_cb(domain, target, &mut |node: &Node<_, _>| {
-        out.push(node.value.clone());
-    })?;
-
-    Ok(out)
-}
-
-#[cfg(test)]
-mod tests {
-    #[allow(unused_imports)]
-    use super::*;
-
-    #[test]
-    fn sort_cb_works() {
-        let mut out = Vec::new();
-        let result = sort_cb(
-            &[
-                Node::new(1, vec![2, 3], "hello"),
-                Node::new(2, vec![], "world"),
-                Node::new(3, vec![2], "cat"),
-            ],
-            1,
-            &mut |node: &Node<_, _>| {
-                out.push(node.value);
-            },
-        );
-        assert_eq!(result, Ok(()));
-        assert_eq!(out, vec!["world", "cat", "hello"]);
-    }
-
-    #[test]
-    fn sort_works() {
-        let result = sort(
-            &vec![
-                Node::new(1, vec![2, 3], "hello"),
-                Node::new(2, vec![], "world"),
-                Node::new(3, vec![2], "cat"),
-            ],
-            1,
-        );
-        assert_eq!(result, Ok(vec!["world", "cat", "hello"]));
-    }
-
-    #[test]
-    fn target_not_found() {
-        let result = sort(
-            &vec![
-                Node::new(1, vec![2, 3], "hello"),
-                Node::new(2, vec![], "world"),
-                Node::new(3, vec![2, 4], "cat"),
-            ],
-            1,
-        );
-        assert_eq!(result, Err(TopsortError::TargetNotFound(4)));
-    }
-
-    #[test]
-    fn cyclic_dependency() {
-        let result = sort(
-            &vec![
-                Node::new(1, vec![2, 3], "hello"),
-                Node::new(2, vec![1], "world"),
-                Node::new(3, vec![2], "cat"),
-            ],
-            1,
-        );
-        assert_eq!(result, Err(TopsortError::CyclicDependency(1)));
-    }
-
-    #[test]
-    fn empty_domain() {
-        let result = sort(&[] as &[Node<i32, i32>], 1);
-        assert_eq!(result, Err(TopsortError::TargetNotFound(1)));
-    }
-}
+#[cfg(feature = "derive")]
+pub use szyk_derive::TopoNode;
+pub use validate::{duplicate_deps, missing_optional_deps, validate, verify_order};
+pub use visitor::{sort_all_visit, sort_visit, Visitor};
@@ -0,0 +1,57 @@
+/// builds a `Vec` of [`Node`](crate::Node)s from `id => [deps]` entries, each optionally followed by `= value`
+///
+/// nodes without a value get `()`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = graph! {
+///         "planks" => ["wood"],
+///         "wood" => [],
+///     };
+///     assert_eq!(sort(&domain, "planks"), Ok(vec![(), ()]));
+///
+///     let domain = graph! {
+///         "planks" => ["wood"] = "Planks",
+///         "wood" => [] = "Wood",
+///     };
+///     assert_eq!(sort(&domain, "planks"), Ok(vec!["Wood", "Planks"]));
+/// ```
+#[macro_export]
+macro_rules! graph {
+    ($($id:expr => [$($dep:expr),* $(,)?] $(= $value:expr)?),* $(,)?) => {
+        vec![$($crate::Node::new($id, vec![$($dep),*], $crate::graph!(@value $($value)?))),*]
+    };
+    (@value) => {
+        ()
+    };
+    (@value $value:expr) => {
+        $value
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Node;
+
+    #[test]
+    fn graph_macro() {
+        let domain: Vec<Node<i32, ()>> = graph! {};
+        assert!(domain.is_empty());
+
+        let domain = graph! {
+            1 => [2, 3] = "hello",
+            2 => [] = "world",
+            3 => [2,] = "cat",
+        };
+        assert_eq!(
+            domain,
+            vec![
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! manifest loaders for build-style graphs
+//!
+//! a manifest is a list of `nodes`, each declaring an `id`, optional `deps` and any other fields,
+//! which are deserialized as the value of the node
+//!
+//! # Examples
+//! ```
+//!     use szyk::manifest;
+//!     use serde::Deserialize;
+//!
+//!     #[derive(Clone, Deserialize)]
+//!     struct Task {
+//!         run: String,
+//!     }
+//!
+//!     let domain = manifest::from_toml::<String, Task>(r#"
+//!         [[nodes]]
+//!         id = "build"
+//!         deps = ["fetch"]
+//!         run = "cargo build"
+//!
+//!         [[nodes]]
+//!         id = "fetch"
+//!         run = "cargo fetch"
+//!     "#).unwrap();
+//!
+//!     let order = szyk::sort(&domain, String::from("build")).unwrap();
+//!     let commands: Vec<_> = order.iter().map(|task| task.run.as_str()).collect();
+//!     assert_eq!(commands, vec!["cargo fetch", "cargo build"]);
+//! ```
+
+use crate::collections::HashSet;
+use crate::Node;
+use core::hash::Hash;
+use serde::de::{DeserializeOwned, Error};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "Id: Deserialize<'de>, Item: Deserialize<'de>"))]
+struct Manifest<Id, Item> {
+    nodes: Vec<Entry<Id, Item>>,
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "Id: Deserialize<'de>, Item: Deserialize<'de>"))]
+struct Entry<Id, Item> {
+    id: Id,
+    #[serde(default = "Vec::new")]
+    deps: Vec<Id>,
+    #[serde(flatten)]
+    value: Item,
+}
+
+impl<Id, Item> Manifest<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// nodes in the order they are listed, rejecting duplicate ids
+    fn into_domain<E>(self) -> Result<Vec<Node<Id, Item>>, E>
+    where
+        E: Error,
+    {
+        let mut ids = HashSet::new();
+        let mut domain = Vec::with_capacity(self.nodes.len());
+        for entry in self.nodes {
+            if !ids.insert(entry.id.clone()) {
+                return Err(E::custom("duplicate node id"));
+            }
+            domain.push(Node::new(entry.id, entry.deps, entry.value));
+        }
+        Ok(domain)
+    }
+}
+
+/// loads nodes from a TOML manifest with a `[[nodes]]` table for each node
+#[cfg(feature = "toml")]
+pub fn from_toml<Id, Item>(input: &str) -> Result<Vec<Node<Id, Item>>, toml::de::Error>
+where
+    Id: Clone + Eq + Hash + DeserializeOwned,
+    Item: DeserializeOwned,
+{
+    toml::from_str::<Manifest<Id, Item>>(input)?.into_domain()
+}
+
+/// loads nodes from a YAML manifest with a `nodes` sequence
+///
+/// # Examples
+/// ```
+///     use szyk::manifest;
+///     use std::collections::HashMap;
+///
+///     let domain = manifest::from_yaml::<String, HashMap<String, String>>("
+///         nodes:
+///           - id: build
+///             deps: [fetch]
+///             run: cargo build
+///           - id: fetch
+///             run: cargo fetch
+///     ").unwrap();
+///     assert_eq!(domain[0].deps[..], [String::from("fetch")]);
+///     assert_eq!(domain[1].value["run"], "cargo fetch");
+/// ```
+#[cfg(feature = "yaml")]
+pub fn from_yaml<Id, Item>(input: &str) -> Result<Vec<Node<Id, Item>>, serde_yaml::Error>
+where
+    Id: Clone + Eq + Hash + DeserializeOwned,
+    Item: DeserializeOwned,
+{
+    serde_yaml::from_str::<Manifest<Id, Item>>(input)?.into_domain()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn from_toml_works() {
+        let domain = from_toml::<u32, ()>(
+            r#"
+            [[nodes]]
+            id = 1
+            deps = [2, 3]
+
+            [[nodes]]
+            id = 2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            domain,
+            vec![Node::new(1, vec![2, 3], ()), Node::new(2, vec![], ())]
+        );
+
+        let table = from_toml::<String, toml::Table>(
+            r#"
+            [[nodes]]
+            id = "a"
+            env = { RUST_LOG = "debug" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(table[0].value["env"]["RUST_LOG"].as_str(), Some("debug"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn from_toml_errors() {
+        let duplicate = from_toml::<u32, ()>("[[nodes]]\nid = 1\n[[nodes]]\nid = 1\n");
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("duplicate node id"));
+        assert!(from_toml::<u32, ()>("[[nodes]]\ndeps = [1]\n").is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn from_yaml_works() {
+        let domain = from_yaml::<u32, ()>("nodes:\n  - id: 1\n    deps: [2]\n  - id: 2\n").unwrap();
+        assert_eq!(
+            domain,
+            vec![Node::new(1, vec![2], ()), Node::new(2, vec![], ())]
+        );
+
+        let duplicate = from_yaml::<u32, ()>("nodes:\n  - id: 1\n  - id: 1\n");
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("duplicate node id"));
+    }
+}
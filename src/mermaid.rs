@@ -0,0 +1,148 @@
+//! Mermaid flowchart export
+//!
+//! # Examples
+//! ```
+//!     use szyk::Node;
+//!     use szyk::mermaid;
+//!
+//!     let domain = [
+//!         Node::new("cat", vec!["dog"], "Garfield"),
+//!         Node::new("dog", vec![], "Odie"),
+//!     ];
+//!     assert_eq!(
+//!         mermaid::to_mermaid(&domain),
+//!         "graph TD\n    n0[\"cat\"]\n    n1[\"dog\"]\n    n0 --> n1\n"
+//!     );
+//! ```
+
+use crate::traversal::first_index;
+use crate::Node;
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Write};
+use core::hash::Hash;
+
+/// renders `domain` as a top-down Mermaid flowchart with edges pointing from nodes to their dependencies
+///
+/// nodes get generated names and their ids as labels, dependencies missing from `domain` are rendered
+/// as extra nodes; the `label` attribute of a node replaces its id as the label, `style` and `class`
+/// attributes become `style` and `class` statements of the node, see [`Node::attributes`]; labels of
+/// [`crate::Edge`] data are rendered on their edges
+///
+/// # Examples
+/// ```
+///     use szyk::Node;
+///     use szyk::mermaid;
+///
+///     let domain = [Node::new("cat", vec![], "Garfield")
+///         .with_attribute("label", "Garfield")
+///         .with_attribute("style", "fill:#f96")];
+///     assert_eq!(
+///         mermaid::to_mermaid(&domain),
+///         "graph TD\n    n0[\"Garfield\"]\n    style n0 fill:#f96\n"
+///     );
+/// ```
+pub fn to_mermaid<Id, Item>(domain: &[Node<Id, Item>]) -> String
+where
+    Id: Clone + Eq + Hash + Display,
+{
+    let mut index = first_index(domain);
+    let mut next = domain.len();
+    let mut out = String::from("graph TD\n");
+    for (position, node) in domain.iter().enumerate() {
+        if index.get(&node.id) == Some(&position) {
+            let label = match node.attributes.get("label") {
+                Some(text) => label(text),
+                None => label(&node.id),
+            };
+            writeln!(out, "    n{}[{}]", position, label)
+                .expect("writing to a String doesn't fail");
+        }
+    }
+
+    for node in domain.iter() {
+        for dep in node.deps.iter() {
+            if !index.contains_key(dep) {
+                index.insert(dep, next);
+                writeln!(out, "    n{}[{}]", next, label(dep))
+                    .expect("writing to a String doesn't fail");
+                next += 1;
+            }
+        }
+    }
+
+    for node in domain.iter() {
+        for dep in node.deps.iter() {
+            match node.edge(dep).and_then(|edge| edge.label.as_ref()) {
+                Some(text) => writeln!(
+                    out,
+                    "    n{} -->|{}| n{}",
+                    index[&node.id],
+                    label(text),
+                    index[dep]
+                ),
+                None => writeln!(out, "    n{} --> n{}", index[&node.id], index[dep]),
+            }
+            .expect("writing to a String doesn't fail");
+        }
+    }
+
+    for (position, node) in domain.iter().enumerate() {
+        if index.get(&node.id) != Some(&position) {
+            continue;
+        }
+        for statement in ["style", "class"].iter() {
+            if let Some(value) = node.attributes.get(*statement) {
+                writeln!(out, "    {} n{} {}", statement, position, value)
+                    .expect("writing to a String doesn't fail");
+            }
+        }
+    }
+    out
+}
+
+/// quotes `id` as a Mermaid label
+fn label<Id>(id: &Id) -> String
+where
+    Id: Display,
+{
+    let mut label = String::from("\"");
+    label.push_str(&id.to_string().replace('"', "#quot;"));
+    label.push('"');
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_mermaid_works() {
+        let domain = [
+            Node::new("a", vec!["b", "c"], ()),
+            Node::new("b", vec!["c"], ()),
+            Node::new("c", vec!["d"], ()),
+            Node::new("say \"hi\"", vec![], ()),
+        ];
+        assert_eq!(
+            to_mermaid(&domain),
+            "graph TD\n    n0[\"a\"]\n    n1[\"b\"]\n    n2[\"c\"]\n    n3[\"say #quot;hi#quot;\"]\n    \
+             n4[\"d\"]\n    n0 --> n1\n    n0 --> n2\n    n1 --> n2\n    n2 --> n4\n"
+        );
+    }
+
+    #[test]
+    fn styles_nodes() {
+        let domain = [
+            Node::new("a", vec![], ())
+                .with_edge("b", crate::Edge::new().with_label("uses"))
+                .with_attribute("class", "done")
+                .with_attribute("style", "stroke:#333"),
+            Node::new("b", vec![], ()).with_attribute("label", "say \"hi\""),
+        ];
+        assert_eq!(
+            to_mermaid(&domain),
+            "graph TD\n    n0[\"a\"]\n    n1[\"say #quot;hi#quot;\"]\n    n0 -->|\"uses\"| n1\n    \
+             style n0 stroke:#333\n    class n0 done\n"
+        );
+    }
+}
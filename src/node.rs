@@ -0,0 +1,472 @@
+use crate::collections::HashSet;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+/// list of dependencies of a [`Node`]
+#[cfg(not(feature = "smallvec"))]
+pub type Deps<Id> = Vec<Id>;
+
+/// list of dependencies of a [`Node`], up to 4 ids are stored inline without allocating
+#[cfg(feature = "smallvec")]
+pub type Deps<Id> = smallvec::SmallVec<[Id; 4]>;
+
+/// metadata of a [`Node`], string keys mapped to string values, ordered by key
+pub type Attributes = BTreeMap<String, String>;
+
+/// data of a dependency edge, e.g. whether a module is needed at link time or at run time
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let node = Node::new("app", vec!["libc"], ())
+///         .with_edge("openssl", Edge::new().with_kind("runtime").with_weight(2.0));
+///     assert_eq!(node.deps.len(), 2);
+///     assert_eq!(node.edge(&"openssl").and_then(|edge| edge.kind.as_deref()), Some("runtime"));
+///     assert_eq!(node.edge(&"libc"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edge {
+    /// rendered on the edge by exporters
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub label: Option<String>,
+    /// category of the relationship, e.g. `"build"` or `"runtime"`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub kind: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub weight: Option<f64>,
+}
+
+impl Edge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// unique identifier
+    pub id: Id,
+    /// list of dependencies
+    pub deps: Deps<Id>,
+    /// value stored in the node
+    pub value: Item,
+    /// dependencies from `deps` which are skipped instead of failing the sort when missing from the domain
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")
+    )]
+    pub optional_deps: Vec<Id>,
+    /// nodes this one is ordered after when they end up in the same sort, without depending on them;
+    /// respected only by sorts based on Kahn's algorithm, like [`crate::sort_kahn`], depth-first
+    /// sorts ignore it, see [ordering](crate#ordering)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")
+    )]
+    pub after: Vec<Id>,
+    /// nodes this one is ordered before when they end up in the same sort, without them depending on it;
+    /// respected only by sorts based on Kahn's algorithm, like [`crate::sort_kahn`], depth-first
+    /// sorts ignore it, see [ordering](crate#ordering)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")
+    )]
+    pub before: Vec<Id>,
+    /// metadata not used by sorts, rendered by exporters like [`crate::dot`] and [`crate::mermaid`]
+    /// and readable by tasks of [`crate::exec`]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub attributes: Attributes,
+    /// data of edges to dependencies from `deps`, paired with the id of the dependency; edges without
+    /// data aren't listed
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")
+    )]
+    pub edges: Vec<(Id, Edge)>,
+}
+
+impl<Id, Item> Node<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// creates a node, a dependency listed more than once is kept only where it's listed first
+    pub fn new(id: Id, deps: Vec<Id>, value: Item) -> Self {
+        let mut deps = to_deps(deps);
+        dedup(&mut deps);
+        Self {
+            id,
+            deps,
+            value,
+            optional_deps: Vec::new(),
+            after: Vec::new(),
+            before: Vec::new(),
+            attributes: Attributes::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// adds `deps` as optional dependencies, ordered like the others when present in the domain and
+    /// skipped when missing
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let domain = [
+    ///         Node::new("wooden pickaxe", vec!["planks"], "Pickaxe").with_optional_deps(vec!["gloves"]),
+    ///         Node::new("planks", vec![], "Planks"),
+    ///     ];
+    ///     assert_eq!(sort(&domain, "wooden pickaxe"), Ok(vec!["Planks", "Pickaxe"]));
+    ///     assert_eq!(missing_optional_deps(&domain), vec![("wooden pickaxe", "gloves")]);
+    /// ```
+    pub fn with_optional_deps(mut self, deps: Vec<Id>) -> Self {
+        for dep in deps {
+            if !self.deps.contains(&dep) {
+                self.deps.push(dep.clone());
+            }
+            if !self.optional_deps.contains(&dep) {
+                self.optional_deps.push(dep);
+            }
+        }
+        self
+    }
+
+    /// orders the node after nodes with ids in `ids` which are sorted with it, see [`Node::after`]
+    ///
+    /// ignored by depth-first sorts like [`crate::sort`], [`crate::sort_all`] and [`crate::Graph::sort`]
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let domain = [
+    ///         Node::new("server", vec!["config", "logger"], "Server"),
+    ///         Node::new("config", vec![], "Config").with_after(vec!["logger", "metrics"]),
+    ///         Node::new("logger", vec![], "Logger"),
+    ///         Node::new("metrics", vec![], "Metrics"),
+    ///     ];
+    ///     assert_eq!(sort_kahn(&domain, "server"), Ok(vec!["Logger", "Config", "Server"]));
+    /// ```
+    pub fn with_after(mut self, ids: Vec<Id>) -> Self {
+        self.after.extend(ids);
+        self
+    }
+
+    /// orders the node before nodes with ids in `ids` which are sorted with it, see [`Node::before`]
+    ///
+    /// ignored by depth-first sorts like [`crate::sort`], [`crate::sort_all`] and [`crate::Graph::sort`]
+    pub fn with_before(mut self, ids: Vec<Id>) -> Self {
+        self.before.extend(ids);
+        self
+    }
+
+    /// sets the attribute `key` to `value`, see [`Node::attributes`]
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let node = Node::new("planks", vec!["wood"], "Planks").with_attribute("shape", "box");
+    ///     assert_eq!(node.attributes.get("shape").map(String::as_str), Some("box"));
+    /// ```
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    /// makes the node depend on `dep` unless it already does, attaching `edge` to the edge between
+    /// them, see [`Edge`]
+    pub fn with_edge(mut self, dep: Id, edge: Edge) -> Self {
+        if !self.deps.contains(&dep) {
+            self.deps.push(dep.clone());
+        }
+        match self.edges.iter_mut().find(|(id, _)| *id == dep) {
+            Some((_, data)) => *data = edge,
+            None => self.edges.push((dep, edge)),
+        }
+        self
+    }
+
+    /// removes dependencies listed more than once from `deps`, keeping the first of each, and returns the
+    /// removed ones; needed only for nodes built without [`Node::new`], e.g. deserialized, see
+    /// [`crate::duplicate_deps`]
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let mut node = Node::new("sticks", vec!["planks"], ());
+    ///     node.deps.push("wood");
+    ///     node.deps.push("planks");
+    ///     assert_eq!(node.dedup_deps(), vec!["planks"]);
+    ///     assert_eq!(node.deps[..], ["planks", "wood"]);
+    /// ```
+    pub fn dedup_deps(&mut self) -> Vec<Id> {
+        dedup(&mut self.deps)
+    }
+
+    /// returns data of the edge to the dependency `dep`, if any was attached
+    pub fn edge<Q>(&self, dep: &Q) -> Option<&Edge>
+    where
+        Id: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.edges
+            .iter()
+            .find(|(id, _)| id.borrow() == dep)
+            .map(|(_, edge)| edge)
+    }
+}
+
+/// nodes holding [`Cow`] values, so large payloads are borrowed during sorting, sorts clone only the
+/// references, and are turned into owned ones with [`Cow::into_owned`] only when kept
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::borrow::Cow;
+///
+///     let texture = vec![0u8; 1 << 20];
+///     let domain = [
+///         Node::borrowed("material", vec!["texture"], &texture[..]),
+///         Node::owned("texture", vec![], vec![1, 2, 3]),
+///     ];
+///     let sorted = sort(&domain, "material").unwrap();
+///     assert!(matches!(sorted[1], Cow::Borrowed(_)));
+///     let kept: Vec<u8> = sorted[0].clone().into_owned();
+///     assert_eq!(kept, vec![1, 2, 3]);
+/// ```
+impl<'a, Id, T> Node<Id, Cow<'a, T>>
+where
+    Id: Clone + Eq + Hash,
+    T: ToOwned + ?Sized,
+{
+    /// creates a node borrowing its value
+    pub fn borrowed(id: Id, deps: Vec<Id>, value: &'a T) -> Self {
+        Self::new(id, deps, Cow::Borrowed(value))
+    }
+
+    /// creates a node owning its value
+    pub fn owned(id: Id, deps: Vec<Id>, value: T::Owned) -> Self {
+        Self::new(id, deps, Cow::Owned(value))
+    }
+}
+
+/// removes ids listed more than once from `deps`, keeping the first of each, returns the removed ones
+fn dedup<Id>(deps: &mut Deps<Id>) -> Vec<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut removed = Vec::new();
+    if deps.len() < 2 {
+        return removed;
+    }
+
+    let mut seen = HashSet::with_capacity(deps.len());
+    deps.retain(|dep| {
+        let dep: &Id = dep;
+        if seen.insert(dep.clone()) {
+            true
+        } else {
+            removed.push(dep.clone());
+            false
+        }
+    });
+    removed
+}
+
+/// converts `deps` into [`Deps`], a no-op without the `smallvec` feature
+#[allow(clippy::useless_conversion)]
+pub(crate) fn to_deps<Id>(deps: Vec<Id>) -> Deps<Id> {
+    deps.into()
+}
+
+/// item with an id and a list of dependencies, sortable directly with [`crate::sort_items`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     struct Migration {
+///         name: &'static str,
+///         after: Vec<&'static str>,
+///     }
+///
+///     impl HasDependencies for Migration {
+///         type Id = &'static str;
+///
+///         fn id(&self) -> &Self::Id {
+///             &self.name
+///         }
+///
+///         fn deps(&self) -> &[Self::Id] {
+///             &self.after
+///         }
+///     }
+///
+///     let migrations = [
+///         Migration { name: "add index", after: vec!["create table"] },
+///         Migration { name: "create table", after: vec![] },
+///     ];
+///     let names: Vec<_> = sort_items(&migrations, "add index")
+///         .unwrap()
+///         .into_iter()
+///         .map(|migration| migration.name)
+///         .collect();
+///     assert_eq!(names, vec!["create table", "add index"]);
+/// ```
+pub trait HasDependencies {
+    type Id: Clone + Eq + Hash;
+
+    /// unique identifier
+    fn id(&self) -> &Self::Id;
+
+    /// ids of dependencies, visited in this order
+    fn deps(&self) -> &[Self::Id];
+
+    /// whether `dep` is skipped instead of failing the sort when it's missing from the domain
+    fn is_optional(&self, _dep: &Self::Id) -> bool {
+        false
+    }
+}
+
+impl<Id, Item> HasDependencies for Node<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    type Id = Id;
+
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn deps(&self) -> &[Id] {
+        &self.deps
+    }
+
+    fn is_optional(&self, dep: &Id) -> bool {
+        self.optional_deps.contains(dep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn dedups_deps() {
+        let node = Node::new(1, vec![2, 3, 2, 4, 3], ());
+        assert_eq!(node.deps[..], [2, 3, 4]);
+
+        let mut node = Node::new(1, vec![2], ());
+        node.deps.extend([3, 2, 2]);
+        assert_eq!(node.dedup_deps(), vec![2, 2]);
+        assert_eq!(node.deps[..], [2, 3]);
+    }
+
+    #[test]
+    fn cow_values_borrowed() {
+        let hello = String::from("hello");
+        let domain = [
+            Node::borrowed(1, vec![2], hello.as_str()),
+            Node::owned(2, vec![], String::from("world")),
+        ];
+        let sorted = crate::sort(&domain, 1).unwrap();
+        assert!(matches!(sorted[0], Cow::Owned(_)));
+        assert!(
+            matches!(&sorted[1], Cow::Borrowed(value) if core::ptr::eq(*value, hello.as_str()))
+        );
+        assert_eq!(sorted, vec!["world", "hello"]);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn smallvec_deps_inline() {
+        let mut node = Node::new(1, vec![2, 3], ());
+        assert!(!node.deps.spilled());
+        node.deps.extend([4, 5, 6]);
+        assert!(node.deps.spilled());
+        assert_eq!(node.deps[..], [2, 3, 4, 5, 6]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_node_and_error() {
+        let node = Node::new(1, vec![2, 3], String::from("hello"));
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(json, r#"{"id":1,"deps":[2,3],"value":"hello"}"#);
+        assert_eq!(
+            serde_json::from_str::<Node<i32, String>>(&json).unwrap(),
+            node
+        );
+
+        let node = Node::new(1, vec![2], ()).with_optional_deps(vec![3]);
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(
+            json,
+            r#"{"id":1,"deps":[2,3],"value":null,"optional_deps":[3]}"#
+        );
+        assert_eq!(serde_json::from_str::<Node<i32, ()>>(&json).unwrap(), node);
+
+        let node = Node::new(1, vec![], ()).with_attribute("color", "red");
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(
+            json,
+            r#"{"id":1,"deps":[],"value":null,"attributes":{"color":"red"}}"#
+        );
+        assert_eq!(serde_json::from_str::<Node<i32, ()>>(&json).unwrap(), node);
+
+        let node = Node::new(1, vec![], ()).with_edge(2, Edge::new().with_kind("build"));
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(
+            json,
+            r#"{"id":1,"deps":[2],"value":null,"edges":[[2,{"kind":"build"}]]}"#
+        );
+        assert_eq!(serde_json::from_str::<Node<i32, ()>>(&json).unwrap(), node);
+
+        let err = crate::TopsortError::CyclicDependency(vec![1, 2, 1]);
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::TopsortError<i32>>(&json).unwrap(),
+            err
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use crate::bitset::BitSet;
+use crate::collections::HashMap;
+use crate::traversal::Traversal;
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::iter::FusedIterator;
+
+/// returns how many different topological orders of the whole `domain` exist, saturating at `u128::MAX`
+///
+/// unrelated parts of the domain are counted separately, but the count within each of them takes time
+/// proportional to the number of sets of nodes that can be emitted first, so it's only practical for
+/// small or tightly constrained graphs; fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["wood"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(count_orders(&domain), Ok(2));
+/// ```
+pub fn count_orders<Id, Item>(domain: &[Node<Id, Item>]) -> Result<u128, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    for position in 0..domain.len() {
+        traversal.visit(position, &mut |_| {})?;
+    }
+    let graph = CompactGraph::new(domain)?;
+
+    // orders of separate components interleave freely
+    let mut total: u128 = 1;
+    let mut placed = 0;
+    for component in weak_components(&graph) {
+        placed += component.len();
+        total = total
+            .saturating_mul(binomial(placed, component.len()))
+            .saturating_mul(count_component(&graph, &component));
+    }
+
+    Ok(total)
+}
+
+/// iterator over every topological order of the dependency closure of a node, created by [`all_orders`]
+///
+/// keeps a single order being built with the choices left at each step, so it needs memory quadratic in
+/// the size of the closure no matter how many orders exist
+pub struct AllOrders<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    /// positions of nodes in the closure, in the order of `domain`
+    closure: Vec<usize>,
+    /// unemitted dependencies of every node, once per listed dependency
+    pending: Vec<usize>,
+    dependents: Vec<Vec<usize>>,
+    emitted: Vec<bool>,
+    /// nodes that were ready at each step of the current order, with the position of the chosen one
+    choices: Vec<(Vec<usize>, usize)>,
+    order: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, Id, Item> AllOrders<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn emit(&mut self, position: usize) {
+        self.order.push(position);
+        self.emitted[position] = true;
+        for &dependent in self.dependents[position].iter() {
+            self.pending[dependent] -= 1;
+        }
+    }
+
+    fn retract(&mut self) {
+        if let Some(position) = self.order.pop() {
+            self.emitted[position] = false;
+            for &dependent in self.dependents[position].iter() {
+                self.pending[dependent] += 1;
+            }
+        }
+    }
+
+    /// replaces the latest choice that still has alternatives with the next one, dropping the later steps
+    fn advance(&mut self) {
+        while let Some((ready, chosen)) = self.choices.last_mut() {
+            *chosen += 1;
+            let next = ready.get(*chosen).copied();
+            self.retract();
+            match next {
+                Some(position) => {
+                    self.emit(position);
+                    return;
+                }
+                None => {
+                    self.choices.pop();
+                }
+            }
+        }
+
+        self.done = true;
+    }
+}
+
+impl<'a, Id, Item> Iterator for AllOrders<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    type Item = Vec<Id>;
+
+    fn next(&mut self) -> Option<Vec<Id>> {
+        if self.done {
+            return None;
+        }
+
+        while self.order.len() < self.closure.len() {
+            let ready: Vec<usize> = self
+                .closure
+                .iter()
+                .copied()
+                .filter(|&position| !self.emitted[position] && self.pending[position] == 0)
+                .collect();
+            // the closure is acyclic, so some node is always ready
+            let first = ready[0];
+            self.choices.push((ready, 0));
+            self.emit(first);
+        }
+
+        let order = self
+            .order
+            .iter()
+            .map(|&position| self.domain[position].id.clone())
+            .collect();
+        self.advance();
+        Some(order)
+    }
+}
+
+impl<'a, Id, Item> FusedIterator for AllOrders<'a, Id, Item> where Id: Clone + Eq + Hash {}
+
+/// returns an iterator over every topological order of nodes from `domain`, ending on the node with
+/// id of `target`, as lists of ids
+///
+/// orders are produced lazily, preferring nodes earlier in `domain`, so the number of orders taken can
+/// be bounded with [`Iterator::take`]; fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec![], "Planks"),
+///         Node::new("sticks", vec![], "Sticks"),
+///     ];
+///     let orders: Vec<_> = all_orders(&domain, "wooden pickaxe").unwrap().collect();
+///     assert_eq!(
+///         orders,
+///         vec![
+///             vec!["planks", "sticks", "wooden pickaxe"],
+///             vec!["sticks", "planks", "wooden pickaxe"],
+///         ]
+///     );
+/// ```
+pub fn all_orders<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<AllOrders<'_, Id, Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut in_closure = vec![false; domain.len()];
+    traversal.visit(index, &mut |position| in_closure[position] = true)?;
+
+    let mut pending = vec![0; domain.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); domain.len()];
+    for position in (0..domain.len()).filter(|&position| in_closure[position]) {
+        for dep in traversal.dep_indices(position) {
+            pending[position] += 1;
+            dependents[dep?].push(position);
+        }
+    }
+
+    Ok(AllOrders {
+        domain,
+        closure: (0..domain.len())
+            .filter(|&position| in_closure[position])
+            .collect(),
+        pending,
+        dependents,
+        emitted: vec![false; domain.len()],
+        choices: Vec::new(),
+        order: Vec::new(),
+        done: false,
+    })
+}
+
+/// positions of nodes connected by edges in either direction, in the order of their first member
+pub(crate) fn weak_components<Id>(graph: &CompactGraph<Id>) -> Vec<Vec<usize>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut parent: Vec<usize> = (0..graph.len()).collect();
+
+    for position in 0..graph.len() {
+        for dep in graph.dep_positions(position) {
+            let a = find(&mut parent, position);
+            let b = find(&mut parent, dep);
+            parent[a.max(b)] = a.min(b);
+        }
+    }
+
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut component_of: HashMap<usize, usize> = HashMap::new();
+    for position in 0..graph.len() {
+        let root = find(&mut parent, position);
+        let component = *component_of.entry(root).or_insert_with(|| {
+            components.push(Vec::new());
+            components.len() - 1
+        });
+        components[component].push(position);
+    }
+    components
+}
+
+/// representative of the set containing `position` in a union-find forest
+fn find(parent: &mut [usize], mut position: usize) -> usize {
+    while parent[position] != position {
+        parent[position] = parent[parent[position]];
+        position = parent[position];
+    }
+    position
+}
+
+/// number of topological orders of acyclic `members`, counted over the sets of nodes emitted so far
+fn count_component<Id>(graph: &CompactGraph<Id>, members: &[usize]) -> u128
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut local = vec![0; graph.len()];
+    for (nth, &position) in members.iter().enumerate() {
+        local[position] = nth;
+    }
+
+    let mut layer: HashMap<BitSet, u128> = HashMap::new();
+    layer.insert(BitSet::new(members.len()), 1);
+    for _ in 0..members.len() {
+        let mut next: HashMap<BitSet, u128> = HashMap::new();
+        for (emitted, ways) in layer.iter() {
+            for (nth, &position) in members.iter().enumerate() {
+                let ready = !emitted.contains(nth)
+                    && graph
+                        .dep_positions(position)
+                        .all(|dep| emitted.contains(local[dep]));
+                if ready {
+                    let mut emitted = emitted.clone();
+                    emitted.insert(nth);
+                    let count = next.entry(emitted).or_insert(0);
+                    *count = count.saturating_add(*ways);
+                }
+            }
+        }
+        layer = next;
+    }
+
+    layer.values().copied().sum()
+}
+
+/// `n` choose `k`, saturating at `u128::MAX`
+fn binomial(n: usize, k: usize) -> u128 {
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = match result.checked_mul((n - i) as u128) {
+            Some(product) => product / (i as u128 + 1),
+            None => return u128::MAX,
+        };
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_orders_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        // 2 orders of the first three, with the fourth in any of 4 places
+        assert_eq!(count_orders(&domain), Ok(8));
+        assert_eq!(count_orders::<i32, ()>(&[]), Ok(1));
+    }
+
+    #[test]
+    fn count_orders_saturates() {
+        let domain: Vec<_> = (0..100).map(|id| Node::new(id, vec![], ())).collect();
+        assert_eq!(count_orders(&domain), Ok(u128::MAX));
+
+        let domain: Vec<_> = (0..30).map(|id| Node::new(id, vec![], ())).collect();
+        assert_eq!(count_orders(&domain), Ok((1..=30).product()));
+    }
+
+    #[test]
+    fn count_orders_errors() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![1], "world"),
+        ];
+        assert_eq!(
+            count_orders(&domain),
+            Err(TopsortError::CyclicDependency(vec![1, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn all_orders_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![4], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ];
+        let orders: Vec<_> = all_orders(&domain, 1).unwrap().collect();
+        assert_eq!(
+            orders,
+            vec![vec![3, 4, 2, 1], vec![4, 2, 3, 1], vec![4, 3, 2, 1]]
+        );
+        assert_eq!(all_orders(&domain, 4).unwrap().count(), 1);
+
+        for order in all_orders(&domain, 1).unwrap() {
+            assert_eq!(crate::verify_order(&domain, &order), Ok(()));
+        }
+    }
+
+    #[test]
+    fn all_orders_matches_count() {
+        let domain = [
+            Node::new(0, vec![1, 2, 3, 4, 5], ()),
+            Node::new(1, vec![], ()),
+            Node::new(2, vec![1], ()),
+            Node::new(3, vec![], ()),
+            Node::new(4, vec![], ()),
+            Node::new(5, vec![4], ()),
+        ];
+        let orders = all_orders(&domain, 0).unwrap().count();
+        assert_eq!(Ok(orders as u128), count_orders(&domain));
+        assert_eq!(all_orders(&domain, 0).unwrap().take(3).count(), 3);
+    }
+
+    #[test]
+    fn all_orders_errors() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![1], "world"),
+        ];
+        assert!(matches!(
+            all_orders(&domain, 1),
+            Err(TopsortError::CyclicDependency(_))
+        ));
+        assert!(matches!(
+            all_orders(&domain, 3),
+            Err(TopsortError::TargetNotFound(3))
+        ));
+    }
+}
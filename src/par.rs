@@ -0,0 +1,168 @@
+use crate::orders::weak_components;
+use crate::{CompactGraph, Node, TopsortError};
+use rayon::prelude::*;
+use std::hash::Hash;
+
+/// returns values of every node from `domain` in topological order, sorting unrelated parts of the
+/// domain in parallel on the global rayon thread pool
+///
+/// parts of the domain connected by dependencies in either direction come one after another, in the
+/// order of their first node in `domain`, and each of them is ordered like in [`crate::sort_all`];
+/// when several parts fail the error of the first one is returned
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("mouse", vec![], "Jerry"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     assert_eq!(par_sort_all(&domain), Ok(vec!["Odie", "Garfield", "Jerry"]));
+/// ```
+pub fn par_sort_all<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Send + Sync,
+    Item: Clone + Send + Sync,
+{
+    let graph = CompactGraph::new(domain)?;
+    let components = weak_components(&graph);
+
+    // position of every node within its component
+    let mut local = vec![0; domain.len()];
+    for members in components.iter() {
+        for (nth, &position) in members.iter().enumerate() {
+            local[position] = nth;
+        }
+    }
+
+    let sorted: Vec<Result<Vec<usize>, TopsortError<Id>>> = components
+        .par_iter()
+        .map(|members| sort_component(domain, &graph, members, &local))
+        .collect();
+
+    let mut out = Vec::with_capacity(domain.len());
+    for order in sorted {
+        out.extend(
+            order?
+                .into_iter()
+                .map(|position| domain[position].value.clone()),
+        );
+    }
+    Ok(out)
+}
+
+/// depth-first sort of `members` of a weakly connected component, in their order
+fn sort_component<Id, Item>(
+    domain: &[Node<Id, Item>],
+    graph: &CompactGraph<Id>,
+    members: &[usize],
+    local: &[usize],
+) -> Result<Vec<usize>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut visited = vec![false; members.len()];
+    let mut on_path = vec![false; members.len()];
+    let mut order = Vec::with_capacity(members.len());
+
+    for &root in members.iter() {
+        if visited[local[root]] {
+            continue;
+        }
+
+        // explicit work stack of (node position, position of the next dependency to visit)
+        let mut stack = vec![(root, 0)];
+        on_path[local[root]] = true;
+        while let Some((position, next_dep)) = stack.last_mut() {
+            let position = *position;
+            match graph.deps(position as u32).get(*next_dep) {
+                Some(&dep) => {
+                    *next_dep += 1;
+                    let dep = dep as usize;
+                    if visited[local[dep]] {
+                        continue;
+                    }
+                    if dep == position {
+                        return Err(TopsortError::SelfDependency(domain[dep].id.clone()));
+                    }
+                    if on_path[local[dep]] {
+                        let start = stack
+                            .iter()
+                            .position(|&(on_stack, _)| on_stack == dep)
+                            .unwrap_or(0);
+                        return Err(TopsortError::CyclicDependency(
+                            stack[start..]
+                                .iter()
+                                .map(|&(on_stack, _)| domain[on_stack].id.clone())
+                                .chain(std::iter::once(domain[dep].id.clone()))
+                                .collect(),
+                        ));
+                    }
+
+                    on_path[local[dep]] = true;
+                    stack.push((dep, 0));
+                }
+                None => {
+                    stack.pop();
+                    on_path[local[position]] = false;
+                    visited[local[position]] = true;
+                    order.push(position);
+                }
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn par_sort_all_works() {
+        let domain: Vec<_> = (0..1000)
+            .map(|id| {
+                let deps = if id % 10 == 0 {
+                    vec![]
+                } else {
+                    vec![id - 1, id - id % 10]
+                };
+                Node::new(id, deps, id)
+            })
+            .collect();
+        let result = par_sort_all(&domain).unwrap();
+        assert_eq!(result, crate::sort_all(&domain).unwrap());
+
+        // values are the ids, so the result is an order of ids
+        let mut domain = domain;
+        domain.reverse();
+        let result = par_sort_all(&domain).unwrap();
+        assert_eq!(result.len(), domain.len());
+        assert_eq!(crate::verify_order(&domain, &result), Ok(()));
+    }
+
+    #[test]
+    fn par_sort_all_errors() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![4], "dog"),
+        ];
+        assert_eq!(
+            par_sort_all(&domain),
+            Err(TopsortError::CyclicDependency(vec![2, 3, 2]))
+        );
+        assert_eq!(
+            par_sort_all(&domain[3..]),
+            Err(TopsortError::SelfDependency(4))
+        );
+        assert_eq!(
+            par_sort_all(&[Node::new(1, vec![2], "hello")]),
+            Err(TopsortError::TargetNotFound(2))
+        );
+    }
+}
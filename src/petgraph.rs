@@ -0,0 +1,189 @@
+use crate::{Graph, Node, TopsortError};
+use ::petgraph::graph::{DiGraph, NodeIndex};
+use ::petgraph::visit::EdgeRef;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// szyk nodes of `graph`, an edge `a -> b` makes `b` depend on `a` like in [`::petgraph::algo::toposort`]
+///
+/// dependencies are listed in the order their edges were added
+fn nodes<N, E, T, F>(graph: &DiGraph<N, E>, mut value: F) -> Vec<Node<NodeIndex, T>>
+where
+    F: FnMut(NodeIndex, &N) -> T,
+{
+    let mut nodes: Vec<_> = graph
+        .node_indices()
+        .map(|index| Node::new(index, Vec::new(), value(index, &graph[index])))
+        .collect();
+    for edge in graph.edge_references() {
+        nodes[edge.target().index()].deps.push(edge.source());
+    }
+    nodes
+}
+
+/// converts a petgraph digraph, an edge `a -> b` makes `b` depend on `a`
+///
+/// # Examples:
+/// ```
+///     use petgraph::graph::DiGraph;
+///     use szyk::Graph;
+///
+///     let mut digraph = DiGraph::<_, ()>::new();
+///     let dog = digraph.add_node("Odie");
+///     let cat = digraph.add_node("Garfield");
+///     digraph.add_edge(dog, cat, ());
+///
+///     let graph = Graph::from(&digraph);
+///     assert_eq!(graph.sort(cat), Ok(vec!["Odie", "Garfield"]));
+/// ```
+impl<N, E> From<&DiGraph<N, E>> for Graph<NodeIndex, N>
+where
+    N: Clone,
+{
+    fn from(graph: &DiGraph<N, E>) -> Self {
+        let mut out = Graph::new();
+        for node in nodes(graph, |_, weight| weight.clone()) {
+            let id = node.id;
+            out.add_node(id, node.value);
+            for dep in node.deps {
+                out.add_edge(id, dep).expect("node was just added");
+            }
+        }
+        out
+    }
+}
+
+/// converts into a petgraph digraph, a node depending on `b` gets an edge `b -> node`
+///
+/// dependencies missing from the graph are skipped
+///
+/// # Examples:
+/// ```
+///     use petgraph::graph::DiGraph;
+///     use szyk::Graph;
+///
+///     let mut graph = Graph::new();
+///     graph.add_node("cat", "Garfield");
+///     graph.add_node("dog", "Odie");
+///     graph.add_edge("cat", "dog").unwrap();
+///
+///     let digraph = DiGraph::from(&graph);
+///     let order: Vec<_> = petgraph::algo::toposort(&digraph, None)
+///         .unwrap()
+///         .into_iter()
+///         .map(|index| digraph[index])
+///         .collect();
+///     assert_eq!(order, vec!["Odie", "Garfield"]);
+/// ```
+impl<Id, Item> From<&Graph<Id, Item>> for DiGraph<Item, ()>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    fn from(graph: &Graph<Id, Item>) -> Self {
+        let mut out = DiGraph::with_capacity(graph.len(), 0);
+        for node in graph.nodes() {
+            out.add_node(node.value.clone());
+        }
+
+        let positions: crate::collections::HashMap<_, _> = graph
+            .nodes()
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (&node.id, NodeIndex::new(position)))
+            .collect();
+        for (position, node) in graph.nodes().iter().enumerate() {
+            for dep in node.deps.iter() {
+                if let Some(&dep) = positions.get(dep) {
+                    out.add_edge(dep, NodeIndex::new(position), ());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// returns indices of nodes from `graph` in topological order, ending on `target`
+///
+/// an edge `a -> b` makes `b` depend on `a`, like in [`::petgraph::algo::toposort`],
+/// only `target` and the nodes it transitively depends on are returned
+///
+/// # Examples:
+/// ```
+///     use petgraph::graph::DiGraph;
+///     use szyk::sort_petgraph;
+///
+///     let mut digraph = DiGraph::<_, ()>::new();
+///     let wood = digraph.add_node("Wood");
+///     let planks = digraph.add_node("Planks");
+///     let stone = digraph.add_node("Stone");
+///     digraph.add_edge(wood, planks, ());
+///
+///     assert_eq!(sort_petgraph(&digraph, planks), Ok(vec![wood, planks]));
+/// ```
+pub fn sort_petgraph<N, E>(
+    graph: &DiGraph<N, E>,
+    target: NodeIndex,
+) -> Result<Vec<NodeIndex>, TopsortError<NodeIndex>> {
+    crate::sort(&nodes(graph, |index, _| index), target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pickaxe() -> (DiGraph<&'static str, ()>, [NodeIndex; 4]) {
+        let mut digraph = DiGraph::new();
+        let pickaxe = digraph.add_node("Pickaxe");
+        let planks = digraph.add_node("Planks");
+        let sticks = digraph.add_node("Sticks");
+        let wood = digraph.add_node("Wood");
+        digraph.add_edge(planks, pickaxe, ());
+        digraph.add_edge(sticks, pickaxe, ());
+        digraph.add_edge(wood, planks, ());
+        digraph.add_edge(planks, sticks, ());
+        (digraph, [pickaxe, planks, sticks, wood])
+    }
+
+    #[test]
+    fn sort_petgraph_works() {
+        let (digraph, [pickaxe, planks, sticks, wood]) = pickaxe();
+        assert_eq!(
+            sort_petgraph(&digraph, pickaxe),
+            Ok(vec![wood, planks, sticks, pickaxe])
+        );
+    }
+
+    #[test]
+    fn sort_petgraph_cycle() {
+        let (mut digraph, [pickaxe, planks, _, wood]) = pickaxe();
+        digraph.add_edge(pickaxe, wood, ());
+        assert_eq!(
+            sort_petgraph(&digraph, pickaxe),
+            Err(TopsortError::CyclicDependency(vec![
+                pickaxe, planks, wood, pickaxe
+            ]))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let (digraph, [pickaxe, ..]) = pickaxe();
+        let graph = Graph::from(&digraph);
+        assert_eq!(
+            graph.sort(pickaxe),
+            Ok(vec!["Wood", "Planks", "Sticks", "Pickaxe"])
+        );
+
+        let digraph = DiGraph::from(&graph);
+        assert_eq!(digraph.node_count(), 4);
+        assert_eq!(digraph.edge_count(), 4);
+        assert_eq!(
+            sort_petgraph(&digraph, pickaxe).map(|order| order
+                .into_iter()
+                .map(|index| digraph[index])
+                .collect::<Vec<_>>()),
+            Ok(vec!["Wood", "Planks", "Sticks", "Pickaxe"])
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::bitset::BitSet;
+use crate::traversal::Traversal;
+use crate::{Graph, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+/// order of a [`Graph`] kept between runs, so after some nodes change only the nodes affected by them
+/// have to be run again
+///
+/// nodes are marked dirty as they change and [`Planner::replan`] returns the dirty nodes together with
+/// everything depending on them, in the order of the last full sort
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut graph = Graph::new();
+///     graph.add_node("main.o", "cc -c main.c");
+///     graph.add_node("util.o", "cc -c util.c");
+///     graph.add_node("app", "cc main.o util.o");
+///     graph.add_edge("app", "main.o").unwrap();
+///     graph.add_edge("app", "util.o").unwrap();
+///
+///     let mut planner = Planner::new(graph).unwrap();
+///     assert_eq!(planner.order(), vec![&"main.o", &"util.o", &"app"]);
+///
+///     planner.mark_dirty(&"util.o");
+///     assert_eq!(planner.replan(), vec!["util.o", "app"]);
+///     assert_eq!(planner.replan(), Vec::<&str>::new());
+/// ```
+#[derive(Debug)]
+pub struct Planner<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    graph: Graph<Id, Item>,
+    /// positions of every node of the graph in topological order
+    order: Vec<usize>,
+    /// positions of nodes directly depending on every node
+    dependents: Vec<Vec<usize>>,
+    dirty: BitSet,
+}
+
+impl<Id, Item> Planner<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// sorts every node of `graph` like [`crate::sort_all`], failing on missing dependencies and cycles
+    pub fn new(graph: Graph<Id, Item>) -> Result<Self, TopsortError<Id>> {
+        let (order, dependents) = plan(graph.nodes())?;
+        let dirty = BitSet::new(order.len());
+        Ok(Self {
+            graph,
+            order,
+            dependents,
+            dirty,
+        })
+    }
+
+    /// marks the node with id of `id` as changed, returns `false` if it isn't in the graph
+    pub fn mark_dirty<Q>(&mut self, id: &Q) -> bool
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.graph.position(id) {
+            Some(position) => {
+                self.dirty.insert(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_dirty<Q>(&self, id: &Q) -> bool
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.graph
+            .position(id)
+            .is_some_and(|position| self.dirty.contains(position))
+    }
+
+    /// returns ids of dirty nodes and nodes depending on them, directly or not, in topological order,
+    /// and clears the dirty marks
+    pub fn replan(&mut self) -> Vec<Id> {
+        let mut affected = BitSet::new(self.order.len());
+        let mut stack: Vec<usize> = self.dirty.iter().collect();
+        for &position in stack.iter() {
+            affected.insert(position);
+        }
+        while let Some(position) = stack.pop() {
+            for &dependent in self.dependents[position].iter() {
+                if !affected.contains(dependent) {
+                    affected.insert(dependent);
+                    stack.push(dependent);
+                }
+            }
+        }
+        self.dirty.clear();
+
+        let nodes = self.graph.nodes();
+        self.order
+            .iter()
+            .filter(|&&position| affected.contains(position))
+            .map(|&position| nodes[position].id.clone())
+            .collect()
+    }
+
+    /// ids of every node of the graph in topological order
+    pub fn order(&self) -> Vec<&Id> {
+        let nodes = self.graph.nodes();
+        self.order
+            .iter()
+            .map(|&position| &nodes[position].id)
+            .collect()
+    }
+
+    pub fn graph(&self) -> &Graph<Id, Item> {
+        &self.graph
+    }
+
+    /// consumes the planner, returning its graph
+    pub fn into_graph(self) -> Graph<Id, Item> {
+        self.graph
+    }
+}
+
+/// positions of every node in topological order and positions of nodes directly depending on every node
+type Plan = (Vec<usize>, Vec<Vec<usize>>);
+
+fn plan<Id, Item>(nodes: &[Node<Id, Item>]) -> Result<Plan, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(nodes)?;
+    let mut order = Vec::with_capacity(nodes.len());
+    for position in 0..nodes.len() {
+        traversal.visit(position, &mut |position| order.push(position))?;
+    }
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for position in 0..nodes.len() {
+        for dep in traversal.dep_indices(position) {
+            dependents[dep?].push(position);
+        }
+    }
+
+    Ok((order, dependents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> Planner<i32, &'static str> {
+        let mut graph = Graph::new();
+        graph.add_node(1, "hello");
+        graph.add_node(2, "world");
+        graph.add_node(3, "cat");
+        graph.add_node(4, "dog");
+        graph.add_edge(1, 2).unwrap();
+        graph.add_edge(2, 3).unwrap();
+        graph.add_edge(4, 3).unwrap();
+        Planner::new(graph).unwrap()
+    }
+
+    #[test]
+    fn replan_works() {
+        let mut planner = chain();
+        assert_eq!(planner.order(), vec![&3, &2, &1, &4]);
+        assert_eq!(planner.replan(), Vec::<i32>::new());
+
+        assert!(planner.mark_dirty(&2));
+        assert!(!planner.mark_dirty(&5));
+        assert!(planner.is_dirty(&2));
+        assert_eq!(planner.replan(), vec![2, 1]);
+        assert!(!planner.is_dirty(&2));
+
+        planner.mark_dirty(&1);
+        planner.mark_dirty(&3);
+        assert_eq!(planner.replan(), vec![3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn new_errors() {
+        let mut graph = Graph::new();
+        graph.add_node(1, "hello");
+        graph.add_edge(1, 2).unwrap();
+        assert!(matches!(
+            Planner::new(graph),
+            Err(TopsortError::TargetNotFound(2))
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::collections::HashMap;
+use crate::{DetailedError, Node, TopsortError};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+
+/// where a node was declared, e.g. a file or a manifest name and a line in it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+    pub source: String,
+    /// 1-based
+    pub line: Option<usize>,
+}
+
+impl Provenance {
+    pub fn new(source: impl Into<String>, line: Option<usize>) -> Self {
+        Self {
+            source: source.into(),
+            line,
+        }
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}", self.source, line),
+            None => write!(f, "{}", self.source),
+        }
+    }
+}
+
+/// marks every node of `domain` as declared in `source`, for loaders which don't track lines
+pub fn declared_in<Id, Item>(domain: &[Node<Id, Item>], source: &str) -> HashMap<Id, Provenance>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain
+        .iter()
+        .map(|node| (node.id.clone(), Provenance::new(source, None)))
+        .collect()
+}
+
+/// error of a sort with provenance of the nodes involved in it, see [`ErrorContext`]
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocatedError<Id> {
+    pub error: TopsortError<Id>,
+    /// nodes named by the error, then nodes on the path to it from the closest one, each once and
+    /// only if its provenance is known
+    pub locations: Vec<(Id, Provenance)>,
+}
+
+impl<Id> fmt::Display for LocatedError<Id>
+where
+    Id: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)?;
+        for (id, provenance) in self.locations.iter() {
+            write!(f, "\n  `{}` declared at {}", id, provenance)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Id> core::error::Error for LocatedError<Id>
+where
+    Id: fmt::Debug + fmt::Display + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// attaches provenance of nodes to errors of sorts, so they point to where the nodes were declared
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::collections::HashMap;
+///
+///     let domain = [
+///         Node::new("app", vec!["lib"], ()),
+///         Node::new("lib", vec!["app"], ()),
+///     ];
+///     let mut provenances = HashMap::new();
+///     provenances.insert("app", Provenance::new("app/BUILD", Some(3)));
+///     provenances.insert("lib", Provenance::new("lib/BUILD", Some(1)));
+///
+///     let err = sort(&domain, "app").with_provenance(&provenances).unwrap_err();
+///     assert_eq!(
+///         err.to_string(),
+///         "cyclic dependency: `app` -> `lib` -> `app`\n  \
+///          `app` declared at app/BUILD:3\n  \
+///          `lib` declared at lib/BUILD:1"
+///     );
+/// ```
+pub trait ErrorContext<T, Id> {
+    fn with_provenance<S>(
+        self,
+        provenances: &HashMap<Id, Provenance, S>,
+    ) -> Result<T, LocatedError<Id>>
+    where
+        S: BuildHasher;
+}
+
+impl<T, Id> ErrorContext<T, Id> for Result<T, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn with_provenance<S>(
+        self,
+        provenances: &HashMap<Id, Provenance, S>,
+    ) -> Result<T, LocatedError<Id>>
+    where
+        S: BuildHasher,
+    {
+        self.map_err(|error| locate(error, &[], provenances))
+    }
+}
+
+impl<T, Id> ErrorContext<T, Id> for Result<T, DetailedError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn with_provenance<S>(
+        self,
+        provenances: &HashMap<Id, Provenance, S>,
+    ) -> Result<T, LocatedError<Id>>
+    where
+        S: BuildHasher,
+    {
+        self.map_err(|err| locate(err.error, &err.path, provenances))
+    }
+}
+
+fn locate<Id, S>(
+    error: TopsortError<Id>,
+    path: &[Id],
+    provenances: &HashMap<Id, Provenance, S>,
+) -> LocatedError<Id>
+where
+    Id: Clone + Eq + Hash,
+    S: BuildHasher,
+{
+    let named: Vec<&Id> = match &error {
+        TopsortError::TargetNotFound(id)
+        | TopsortError::SelfDependency(id)
+        | TopsortError::DuplicateId(id)
+        | TopsortError::DepthLimitExceeded(id) => vec![id],
+        TopsortError::CyclicDependency(ids) | TopsortError::CyclicNodes(ids) => {
+            ids.iter().collect()
+        }
+        TopsortError::MissingDependencies(missing) => missing
+            .iter()
+            .flat_map(|(id, dep)| core::iter::once(id).chain(core::iter::once(dep)))
+            .collect(),
+        TopsortError::UnorderedDependency(id, dep) => vec![id, dep],
+        TopsortError::Cancelled => Vec::new(),
+    };
+
+    let mut locations: Vec<(Id, Provenance)> = Vec::new();
+    for id in named.into_iter().chain(path.iter().rev()) {
+        if locations.iter().any(|(located, _)| located == id) {
+            continue;
+        }
+        if let Some(provenance) = provenances.get(id) {
+            locations.push((id.clone(), provenance.clone()));
+        }
+    }
+
+    LocatedError { error, locations }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort_detailed;
+
+    #[test]
+    fn with_provenance_works() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+        ];
+        let mut provenances = declared_in(&domain, "nodes.toml");
+        provenances.insert(2, Provenance::new("nodes.toml", Some(7)));
+
+        let err = sort_detailed(&domain, 1)
+            .with_provenance(&provenances)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            LocatedError {
+                error: TopsortError::TargetNotFound(3),
+                locations: vec![
+                    (2, Provenance::new("nodes.toml", Some(7))),
+                    (1, Provenance::new("nodes.toml", None)),
+                ],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "target `3` not found\n  `2` declared at nodes.toml:7\n  `1` declared at nodes.toml"
+        );
+
+        assert_eq!(
+            crate::sort(&domain[..1], 1).with_provenance(&provenances),
+            Err(LocatedError {
+                error: TopsortError::TargetNotFound(2),
+                locations: vec![(2, Provenance::new("nodes.toml", Some(7)))],
+            })
+        );
+        assert_eq!(
+            crate::sort(&domain[..1], 3).with_provenance(&provenances),
+            Err(LocatedError {
+                error: TopsortError::TargetNotFound(3),
+                locations: vec![],
+            })
+        );
+    }
+}
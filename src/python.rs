@@ -0,0 +1,163 @@
+//! Python bindings built with `pyo3`, ids are strings
+//!
+//! build the extension with `maturin build --features python`, the module is named `szyk`; domains are
+//! dicts from ids to lists of their dependencies, sorted in the order of the dict, and errors are raised
+//! as `ValueError`s with the message of the [`TopsortError`]
+//!
+//! # Examples
+//! ```python
+//!     import szyk
+//!
+//!     szyk.sort({"cat": ["dog"], "dog": []}, "cat")  # ["dog", "cat"]
+//!     szyk.find_cycles({"cat": ["dog"], "dog": ["cat"]})  # [["cat", "dog", "cat"]]
+//!
+//!     graph = szyk.Graph()
+//!     graph.add_node("cat")
+//!     graph.add_node("dog")
+//!     graph.add_edge("cat", "dog")
+//!     graph.sort("cat")  # ["dog", "cat"]
+//! ```
+
+use crate::{Graph, Node, TopsortError};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// graph of string ids, exported to Python as `szyk.Graph`
+#[pyclass(name = "Graph")]
+#[derive(Debug, Default)]
+pub struct PyGraph {
+    graph: Graph<String, ()>,
+}
+
+#[pymethods]
+impl PyGraph {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds a node without dependencies, does nothing if it's already in the graph
+    fn add_node(&mut self, id: String) {
+        if !self.graph.contains(&id) {
+            self.graph.add_node(id, ());
+        }
+    }
+
+    /// makes `from` depend on `to`, see [`Graph::add_edge`]
+    fn add_edge(&mut self, from: String, to: String) -> PyResult<()> {
+        self.graph.add_edge(from, to).map_err(error)
+    }
+
+    fn __contains__(&self, id: String) -> bool {
+        self.graph.contains(&id)
+    }
+
+    fn __len__(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// ids in topological order, ending on `target`
+    fn sort(&self, target: String) -> PyResult<Vec<String>> {
+        let mut ids = Vec::new();
+        self.graph
+            .sort_cb(target, &mut |node: &Node<String, ()>| {
+                ids.push(node.id.clone())
+            })
+            .map_err(error)?;
+        Ok(ids)
+    }
+
+    /// ids of every node in topological order, see [`crate::sort_all`]
+    fn sort_all(&self) -> PyResult<Vec<String>> {
+        ids(self.graph.nodes())
+    }
+
+    /// every elementary cycle, see [`crate::find_cycles`]
+    fn find_cycles(&self) -> PyResult<Vec<Vec<String>>> {
+        crate::find_cycles(self.graph.nodes()).map_err(error)
+    }
+}
+
+/// ids in topological order, ending on `target`
+#[pyfunction]
+fn sort(domain: &Bound<'_, PyDict>, target: String) -> PyResult<Vec<String>> {
+    let domain = to_domain(domain)?;
+    let mut ids = Vec::new();
+    crate::sort_cb(&domain, target, &mut |node: &Node<String, ()>| {
+        ids.push(node.id.clone())
+    })
+    .map_err(error)?;
+    Ok(ids)
+}
+
+/// ids of every node in topological order
+#[pyfunction]
+fn sort_all(domain: &Bound<'_, PyDict>) -> PyResult<Vec<String>> {
+    ids(&to_domain(domain)?)
+}
+
+/// every elementary cycle, as ids starting and ending on the same id
+#[pyfunction]
+fn find_cycles(domain: &Bound<'_, PyDict>) -> PyResult<Vec<Vec<String>>> {
+    crate::find_cycles(&to_domain(domain)?).map_err(error)
+}
+
+#[pymodule]
+fn szyk(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGraph>()?;
+    module.add_function(wrap_pyfunction!(sort, module)?)?;
+    module.add_function(wrap_pyfunction!(sort_all, module)?)?;
+    module.add_function(wrap_pyfunction!(find_cycles, module)?)?;
+    Ok(())
+}
+
+/// nodes of a dict from ids to lists of dependencies, in the order of the dict
+fn to_domain(domain: &Bound<'_, PyDict>) -> PyResult<Vec<Node<String, ()>>> {
+    domain
+        .iter()
+        .map(|(id, deps)| Ok(Node::new(id.extract()?, deps.extract::<Vec<String>>()?, ())))
+        .collect()
+}
+
+fn ids(domain: &[Node<String, ()>]) -> PyResult<Vec<String>> {
+    let mut ids = Vec::new();
+    crate::sort_all_cb(domain, &mut |node: &Node<String, ()>| {
+        ids.push(node.id.clone())
+    })
+    .map_err(error)?;
+    Ok(ids)
+}
+
+fn error(err: TopsortError<String>) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_works() {
+        let mut graph = PyGraph::new();
+        for id in ["cat", "dog", "mouse"].iter() {
+            graph.add_node(String::from(*id));
+        }
+        graph.add_node(String::from("cat"));
+        graph
+            .add_edge(String::from("cat"), String::from("dog"))
+            .unwrap();
+        graph
+            .add_edge(String::from("dog"), String::from("mouse"))
+            .unwrap();
+
+        assert_eq!(graph.__len__(), 3);
+        assert!(graph.__contains__(String::from("dog")));
+        assert_eq!(
+            graph.sort(String::from("cat")).unwrap(),
+            vec!["mouse", "dog", "cat"]
+        );
+        assert_eq!(graph.sort_all().unwrap(), vec!["mouse", "dog", "cat"]);
+        assert_eq!(graph.find_cycles().unwrap(), Vec::<Vec<String>>::new());
+    }
+}
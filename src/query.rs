@@ -0,0 +1,660 @@
+use crate::collections::{HashMap, HashSet};
+use crate::traversal::{build_index, first_index};
+use crate::{HasDependencies, Node, TopsortError};
+use alloc::borrow::ToOwned;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+/// returns ids of all nodes from `domain` the node with id of `id` transitively depends on, in the order of `domain`
+///
+/// the node itself is only included if it's part of a cycle, fails if `id` or any of the reached
+/// dependencies is missing; `id` can be any borrowed form of the id type, e.g. `&str` for `String` ids
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(ancestors(&domain, &"sticks"), Ok(vec!["planks", "wood"]));
+/// ```
+pub fn ancestors<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    id: &Q,
+) -> Result<Vec<Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+{
+    let index = build_index(domain)?;
+    let find = |id: &Id| match index.get(id) {
+        Some(&position) => Ok(position),
+        None => Err(TopsortError::TargetNotFound(id.clone())),
+    };
+    let start = match position(domain, id) {
+        Some(start) => start,
+        None => return Err(TopsortError::TargetNotFound(id.to_owned())),
+    };
+
+    let mut reached = vec![false; domain.len()];
+    let mut stack = vec![start];
+    while let Some(position) = stack.pop() {
+        for dep in domain[position].deps.iter() {
+            if domain[position].is_optional(dep) && !index.contains_key(dep) {
+                continue;
+            }
+            let dep = find(dep)?;
+            if !reached[dep] {
+                reached[dep] = true;
+                stack.push(dep);
+            }
+        }
+    }
+
+    Ok(collect(domain, &reached))
+}
+
+/// returns ids of all nodes from `domain` transitively depending on the node with id of `id`, in the order of `domain`
+///
+/// the node itself is only included if it's part of a cycle, fails if `id` is missing;
+/// dependencies missing from `domain` are ignored, `id` can be any borrowed form of the id type
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(descendants(&domain, &"planks"), Ok(vec!["wooden pickaxe", "sticks"]));
+/// ```
+pub fn descendants<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    id: &Q,
+) -> Result<Vec<Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+{
+    let index = build_index(domain)?;
+    let start = match position(domain, id) {
+        Some(start) => start,
+        None => return Err(TopsortError::TargetNotFound(id.to_owned())),
+    };
+
+    let dependents = dependents(domain, &index);
+
+    let mut reached = vec![false; domain.len()];
+    let mut stack = vec![start];
+    while let Some(position) = stack.pop() {
+        for &dependent in dependents[position].iter() {
+            if !reached[dependent] {
+                reached[dependent] = true;
+                stack.push(dependent);
+            }
+        }
+    }
+
+    Ok(collect(domain, &reached))
+}
+
+/// returns ids of the node with id of `changed` and all nodes transitively depending on it, in an order
+/// they can be rebuilt in after a change: every node after all of its dependencies that are affected too
+///
+/// `changed` comes first, fails if it's missing or any of the affected nodes are part of a cycle;
+/// dependencies missing from `domain` are ignored, `changed` can be any borrowed form of the id type
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(
+///         rebuild_order(&domain, &"planks"),
+///         Ok(vec!["planks", "sticks", "wooden pickaxe"])
+///     );
+/// ```
+pub fn rebuild_order<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    changed: &Q,
+) -> Result<Vec<Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+{
+    let index = build_index(domain)?;
+    let start = match position(domain, changed) {
+        Some(start) => start,
+        None => return Err(TopsortError::TargetNotFound(changed.to_owned())),
+    };
+
+    let dependents = dependents(domain, &index);
+
+    // depth-first search along dependents, the reverse of its finishing order has every node
+    // after the ones it depends on
+    let mut finished = vec![false; domain.len()];
+    let mut on_path = vec![false; domain.len()];
+    let mut order = Vec::new();
+    let mut stack = vec![(start, 0)];
+    on_path[start] = true;
+    while let Some((position, next)) = stack.last_mut() {
+        let position = *position;
+        match dependents[position].get(*next) {
+            Some(&dependent) => {
+                *next += 1;
+                if dependent == position {
+                    return Err(TopsortError::SelfDependency(domain[position].id.clone()));
+                }
+                if on_path[dependent] {
+                    // `dependent` depends on `position`, which depends on everything before it on the path
+                    let from = stack
+                        .iter()
+                        .position(|&(on_stack, _)| on_stack == dependent)
+                        .unwrap_or(0);
+                    let cycle = core::iter::once(dependent)
+                        .chain(stack[from..].iter().rev().map(|&(on_stack, _)| on_stack))
+                        .map(|position| domain[position].id.clone())
+                        .collect();
+                    return Err(TopsortError::CyclicDependency(cycle));
+                }
+                if !finished[dependent] {
+                    on_path[dependent] = true;
+                    stack.push((dependent, 0));
+                }
+            }
+            None => {
+                stack.pop();
+                on_path[position] = false;
+                finished[position] = true;
+                order.push(domain[position].id.clone());
+            }
+        }
+    }
+
+    order.reverse();
+    Ok(order)
+}
+
+/// returns ids of nodes from `domain` directly listing `id` in their `deps`, in the order of `domain`
+///
+/// fails if `id` is missing, `id` can be any borrowed form of the id type
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(dependents_of(&domain, &"planks"), Ok(vec!["wooden pickaxe", "sticks"]));
+///     assert_eq!(dependents_of(&domain, &"wood"), Ok(vec!["planks"]));
+/// ```
+pub fn dependents_of<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    id: &Q,
+) -> Result<Vec<Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+{
+    if position(domain, id).is_none() {
+        return Err(TopsortError::TargetNotFound(id.to_owned()));
+    }
+
+    Ok(domain
+        .iter()
+        .filter(|node| node.deps.iter().any(|dep| dep.borrow() == id))
+        .map(|node| node.id.clone())
+        .collect())
+}
+
+/// whether the node with id of `to` is in the dependency closure of the node with id of `from`,
+/// which includes `from` itself
+///
+/// stops as soon as `to` is found, missing ids and dependencies are never reachable and the first
+/// node wins on duplicate ids; ids can be any borrowed form of the id type
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert!(is_reachable(&domain, &"wooden pickaxe", &"wood"));
+///     assert!(!is_reachable(&domain, &"planks", &"sticks"));
+/// ```
+pub fn is_reachable<Id, Item, Q>(domain: &[Node<Id, Item>], from: &Q, to: &Q) -> bool
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    let index = first_index(domain);
+    let start = match position(domain, from) {
+        Some(start) => start,
+        None => return false,
+    };
+
+    let mut reached = vec![false; domain.len()];
+    reached[start] = true;
+    let mut stack = vec![start];
+    while let Some(position) = stack.pop() {
+        if domain[position].id.borrow() == to {
+            return true;
+        }
+        for dep in domain[position].deps.iter() {
+            if let Some(&dep) = index.get(dep) {
+                if !reached[dep] {
+                    reached[dep] = true;
+                    stack.push(dep);
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// returns the shortest chain of dependencies leading from the node with id of `from` to the node
+/// with id of `to`, including both, or `None` if `from` doesn't depend on `to`
+///
+/// missing ids and dependencies are never reachable and the first node wins on duplicate ids, ids
+/// can be any borrowed form of the id type
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(
+///         shortest_dependency_path(&domain, &"wooden pickaxe", &"wood"),
+///         Some(vec!["wooden pickaxe", "planks", "wood"])
+///     );
+///     assert_eq!(shortest_dependency_path(&domain, &"wood", &"planks"), None);
+/// ```
+pub fn shortest_dependency_path<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    from: &Q,
+    to: &Q,
+) -> Option<Vec<Id>>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    let index = first_index(domain);
+    let start = position(domain, from)?;
+
+    // breadth-first search, remembering where every node was reached from
+    let mut previous: Vec<Option<usize>> = vec![None; domain.len()];
+    let mut reached = vec![false; domain.len()];
+    reached[start] = true;
+    let mut queue = VecDeque::from(vec![start]);
+    while let Some(position) = queue.pop_front() {
+        if domain[position].id.borrow() == to {
+            let mut path = vec![domain[position].id.clone()];
+            let mut current = position;
+            while let Some(prev) = previous[current] {
+                path.push(domain[prev].id.clone());
+                current = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for dep in domain[position].deps.iter() {
+            if let Some(&dep) = index.get(dep) {
+                if !reached[dep] {
+                    reached[dep] = true;
+                    previous[dep] = Some(position);
+                    queue.push_back(dep);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// returns ids of nodes from `domain` without dependencies, in the order of `domain`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///         Node::new("stone", vec![], "Stone"),
+///     ];
+///     assert_eq!(roots(&domain), vec!["wood", "stone"]);
+/// ```
+pub fn roots<Id, Item>(domain: &[Node<Id, Item>]) -> Vec<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain
+        .iter()
+        .filter(|node| node.deps.is_empty())
+        .map(|node| node.id.clone())
+        .collect()
+}
+
+/// returns ids of nodes from `domain` no other node depends on, in the order of `domain`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///         Node::new("stone", vec![], "Stone"),
+///     ];
+///     assert_eq!(sinks(&domain), vec!["wooden pickaxe", "stone"]);
+/// ```
+pub fn sinks<Id, Item>(domain: &[Node<Id, Item>]) -> Vec<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    let depended_on: HashSet<&Id> = domain.iter().flat_map(|node| node.deps.iter()).collect();
+    domain
+        .iter()
+        .filter(|node| !depended_on.contains(&node.id))
+        .map(|node| node.id.clone())
+        .collect()
+}
+
+/// returns edges of nodes from `domain` whose [`crate::Edge`] data is of `kind`, as pairs of ids of
+/// the node and its dependency, in the order of `domain` and of dependencies
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("app", vec!["libc"], ())
+///             .with_edge("openssl", Edge::new().with_kind("runtime"))
+///             .with_edge("zlib", Edge::new().with_kind("build")),
+///         Node::new("openssl", vec![], ()).with_edge("libc", Edge::new().with_kind("runtime")),
+///     ];
+///     assert_eq!(
+///         edges_of_kind(&domain, "runtime"),
+///         vec![("app", "openssl"), ("openssl", "libc")]
+///     );
+/// ```
+pub fn edges_of_kind<Id, Item>(domain: &[Node<Id, Item>], kind: &str) -> Vec<(Id, Id)>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain
+        .iter()
+        .flat_map(|node| {
+            node.deps
+                .iter()
+                .filter(move |dep| {
+                    node.edge(dep)
+                        .is_some_and(|edge| edge.kind.as_deref() == Some(kind))
+                })
+                .map(move |dep| (node.id.clone(), dep.clone()))
+        })
+        .collect()
+}
+
+/// position of the first node of `domain` with id of `id`
+///
+/// indices built from `domain` are keyed by references, which only borrow as the id type itself, so a
+/// borrowed `id` is found by scanning instead of allocating an owned one
+fn position<Id, Item, Q>(domain: &[Node<Id, Item>], id: &Q) -> Option<usize>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Eq + ?Sized,
+{
+    domain.iter().position(|node| node.id.borrow() == id)
+}
+
+/// positions of nodes directly depending on every node, dependencies missing from `index` are ignored
+fn dependents<Id, Item>(domain: &[Node<Id, Item>], index: &HashMap<&Id, usize>) -> Vec<Vec<usize>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); domain.len()];
+    for (position, node) in domain.iter().enumerate() {
+        for dep in node.deps.iter() {
+            if let Some(&dep) = index.get(dep) {
+                dependents[dep].push(position);
+            }
+        }
+    }
+    dependents
+}
+
+/// ids of `reached` nodes in the order of `domain`
+fn collect<Id, Item>(domain: &[Node<Id, Item>], reached: &[bool]) -> Vec<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain
+        .iter()
+        .zip(reached.iter())
+        .filter(|(_, &reached)| reached)
+        .map(|(node, _)| node.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebuild_order_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![4], ()),
+            Node::new(3, vec![2, 5], ()),
+            Node::new(4, vec![], ()),
+            Node::new(5, vec![6], ()),
+        ];
+        assert_eq!(rebuild_order(&domain, &4), Ok(vec![4, 2, 3, 1]));
+        assert_eq!(rebuild_order(&domain, &5), Ok(vec![5, 3, 1]));
+        assert_eq!(rebuild_order(&domain, &1), Ok(vec![1]));
+        assert_eq!(
+            rebuild_order(&domain, &6),
+            Err(TopsortError::TargetNotFound(6))
+        );
+    }
+
+    #[test]
+    fn rebuild_order_cycle() {
+        let domain = [
+            Node::new(1, vec![2], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![1], ()),
+            Node::new(4, vec![4], ()),
+        ];
+        assert_eq!(
+            rebuild_order(&domain, &3),
+            Err(TopsortError::CyclicDependency(vec![3, 1, 2, 3]))
+        );
+        assert_eq!(
+            rebuild_order(&domain, &4),
+            Err(TopsortError::SelfDependency(4))
+        );
+    }
+
+    #[test]
+    fn ancestors_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![1], "mouse"),
+        ];
+        assert_eq!(ancestors(&domain, &1), Ok(vec![2, 3, 4]));
+        assert_eq!(ancestors(&domain, &2), Ok(vec![]));
+        assert_eq!(ancestors(&domain, &6), Err(TopsortError::TargetNotFound(6)));
+    }
+
+    #[test]
+    fn descendants_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![], "dog"),
+            Node::new(5, vec![1, 6], "mouse"),
+        ];
+        assert_eq!(descendants(&domain, &4), Ok(vec![1, 3, 5]));
+        assert_eq!(descendants(&domain, &5), Ok(vec![]));
+        assert_eq!(
+            descendants(&domain, &6),
+            Err(TopsortError::TargetNotFound(6))
+        );
+    }
+
+    #[test]
+    fn queries_with_cycles() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2], "cat"),
+        ];
+        assert_eq!(ancestors(&domain, &1), Ok(vec![2, 3]));
+        assert_eq!(ancestors(&domain, &2), Ok(vec![2, 3]));
+        assert_eq!(descendants(&domain, &3), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn ancestors_missing_dependency() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(3, vec![], "world"),
+        ];
+        assert_eq!(ancestors(&domain, &1), Err(TopsortError::TargetNotFound(2)));
+        assert_eq!(ancestors(&domain, &3), Ok(vec![]));
+    }
+
+    #[test]
+    fn dependents_of_works() {
+        let domain = [
+            Node::new(1, vec![2, 3, 2], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![3], "dog"),
+        ];
+        assert_eq!(dependents_of(&domain, &2), Ok(vec![1, 3]));
+        assert_eq!(dependents_of(&domain, &4), Ok(vec![]));
+        assert_eq!(
+            dependents_of(&domain, &5),
+            Err(TopsortError::TargetNotFound(5))
+        );
+    }
+
+    #[test]
+    fn is_reachable_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4, 5], "cat"),
+            Node::new(4, vec![3], "dog"),
+        ];
+        assert!(is_reachable(&domain, &1, &4));
+        assert!(is_reachable(&domain, &4, &3));
+        assert!(is_reachable(&domain, &2, &2));
+        assert!(!is_reachable(&domain, &2, &1));
+        assert!(!is_reachable(&domain, &1, &5));
+        assert!(!is_reachable(&domain, &5, &5));
+    }
+
+    #[test]
+    fn queries_accept_borrowed_ids() {
+        use alloc::string::String;
+
+        let domain = [
+            Node::new(String::from("hello"), vec![String::from("world")], ()),
+            Node::new(String::from("world"), vec![], ()),
+        ];
+        assert_eq!(ancestors(&domain, "hello"), Ok(vec![String::from("world")]));
+        assert_eq!(
+            descendants(&domain, "world"),
+            Ok(vec![String::from("hello")])
+        );
+        assert_eq!(
+            rebuild_order(&domain, "world"),
+            Ok(vec![String::from("world"), String::from("hello")])
+        );
+        assert_eq!(
+            dependents_of(&domain, "cat"),
+            Err(TopsortError::TargetNotFound(String::from("cat")))
+        );
+        assert!(is_reachable(&domain, "hello", "world"));
+        assert_eq!(shortest_dependency_path(&domain, "world", "hello"), None);
+    }
+
+    #[test]
+    fn roots_and_sinks() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![4], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ];
+        assert_eq!(roots(&domain), vec![2, 5]);
+        assert_eq!(sinks(&domain), vec![1, 5]);
+        assert!(roots::<i32, ()>(&[]).is_empty());
+    }
+
+    #[test]
+    fn shortest_dependency_path_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![4], "world"),
+            Node::new(3, vec![5], "cat"),
+            Node::new(4, vec![6], "dog"),
+            Node::new(5, vec![], "mouse"),
+            Node::new(6, vec![5], "bird"),
+        ];
+        assert_eq!(
+            shortest_dependency_path(&domain, &1, &5),
+            Some(vec![1, 3, 5])
+        );
+        assert_eq!(
+            shortest_dependency_path(&domain, &2, &5),
+            Some(vec![2, 4, 6, 5])
+        );
+        assert_eq!(shortest_dependency_path(&domain, &3, &3), Some(vec![3]));
+        assert_eq!(shortest_dependency_path(&domain, &5, &1), None);
+        assert_eq!(shortest_dependency_path(&domain, &7, &1), None);
+    }
+}
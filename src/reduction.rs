@@ -0,0 +1,128 @@
+use crate::bitset::BitSet;
+use crate::traversal::Traversal;
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// returns a copy of `domain` without redundant edges: dependencies already implied by other dependencies
+///
+/// the result has the same order of nodes and the same transitive dependencies, repeated dependencies
+/// are listed once; fails on missing dependencies and cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = transitive_reduction(&[
+///         Node::new("cat", vec!["dog", "mouse"], "Garfield"),
+///         Node::new("dog", vec!["mouse"], "Odie"),
+///         Node::new("mouse", vec![], "Jerry"),
+///     ]);
+///     assert_eq!(
+///         result,
+///         Ok(vec![
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec!["mouse"], "Odie"),
+///             Node::new("mouse", vec![], "Jerry"),
+///         ])
+///     );
+/// ```
+pub fn transitive_reduction<Id, Item>(
+    domain: &[Node<Id, Item>],
+) -> Result<Vec<Node<Id, Item>>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let graph = CompactGraph::new(domain)?;
+    let mut traversal = Traversal::new(domain)?;
+    let mut order = Vec::with_capacity(domain.len());
+    for position in 0..domain.len() {
+        traversal.visit(position, &mut |position| order.push(position))?;
+    }
+
+    // positions reachable from every node, dependencies come first in the order
+    let mut reachable: Vec<BitSet> = vec![BitSet::new(domain.len()); domain.len()];
+    for &position in order.iter() {
+        let mut reach = BitSet::new(domain.len());
+        for dep in graph.dep_positions(position) {
+            reach.insert(dep);
+            reach.union_with(&reachable[dep]);
+        }
+        reachable[position] = reach;
+    }
+
+    Ok(domain
+        .iter()
+        .enumerate()
+        .map(|(position, node)| {
+            let mut kept: Vec<usize> = Vec::new();
+            let deps = graph.deps(position as u32);
+            for (nth, &dep) in deps.iter().enumerate() {
+                let repeated = deps[..nth].contains(&dep);
+                let implied = deps
+                    .iter()
+                    .any(|&other| other != dep && reachable[other as usize].contains(dep as usize));
+                if !repeated && !implied {
+                    kept.push(nth);
+                }
+            }
+
+            Node::new(
+                node.id.clone(),
+                kept.into_iter().map(|nth| node.deps[nth].clone()).collect(),
+                node.value.clone(),
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_implied_edges() {
+        let result = transitive_reduction(&[
+            Node::new(1, vec![2, 3, 4, 5], "hello"),
+            Node::new(2, vec![3, 5], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![5], "dog"),
+            Node::new(5, vec![], "mouse"),
+            Node::new(6, vec![5, 5], "bird"),
+        ]);
+        assert_eq!(
+            result,
+            Ok(vec![
+                Node::new(1, vec![2], "hello"),
+                Node::new(2, vec![3], "world"),
+                Node::new(3, vec![4], "cat"),
+                Node::new(4, vec![5], "dog"),
+                Node::new(5, vec![], "mouse"),
+                Node::new(6, vec![5], "bird"),
+            ])
+        );
+    }
+
+    #[test]
+    fn keeps_independent_edges() {
+        let domain = vec![
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![4], "world"),
+            Node::new(3, vec![4], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        let reduced = transitive_reduction(&domain).unwrap();
+        assert_eq!(reduced, domain);
+    }
+
+    #[test]
+    fn fails_on_cycles() {
+        let result = transitive_reduction(&[
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![1], "world"),
+        ]);
+        assert_eq!(result, Err(TopsortError::CyclicDependency(vec![1, 2, 1])));
+    }
+}
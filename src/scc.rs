@@ -0,0 +1,323 @@
+use crate::{CompactGraph, Node, TopsortError};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// returns strongly connected components of `domain` found with Tarjan's algorithm
+///
+/// components are in topological order, each one after all components it depends on, and their
+/// members follow the order of `domain`; nodes that aren't part of any cycle form single-node components
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = scc(&[
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec!["mouse"], "Odie"),
+///         Node::new("mouse", vec!["dog"], "Jerry"),
+///     ]);
+///     assert_eq!(result, Ok(vec![vec!["dog", "mouse"], vec!["cat"]]));
+/// ```
+pub fn scc<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Vec<Vec<Id>>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let graph = CompactGraph::new(domain)?;
+    Ok(components(&graph)
+        .into_iter()
+        .map(|component| {
+            component
+                .into_iter()
+                .map(|position| domain[position].id.clone())
+                .collect()
+        })
+        .collect())
+}
+
+/// returns the acyclic graph of strongly connected components of `domain`
+///
+/// every component becomes a node identified by its position in the result of [`scc`], depending on
+/// components its members depend on and holding ids of its members as the value
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let condensed = condense(&[
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec!["mouse"], "Odie"),
+///         Node::new("mouse", vec!["dog"], "Jerry"),
+///     ])
+///     .unwrap();
+///     assert_eq!(
+///         condensed,
+///         vec![
+///             Node::new(0, vec![], vec!["dog", "mouse"]),
+///             Node::new(1, vec![0], vec!["cat"]),
+///         ]
+///     );
+///     assert_eq!(sort(&condensed, 1), Ok(vec![vec!["dog", "mouse"], vec!["cat"]]));
+/// ```
+pub fn condense<Id, Item>(
+    domain: &[Node<Id, Item>],
+) -> Result<Vec<Node<usize, Vec<Id>>>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let graph = CompactGraph::new(domain)?;
+    let components = components(&graph);
+
+    let mut component_of = vec![0; domain.len()];
+    for (component, members) in components.iter().enumerate() {
+        for &position in members.iter() {
+            component_of[position] = component;
+        }
+    }
+
+    Ok(components
+        .iter()
+        .enumerate()
+        .map(|(component, members)| {
+            let mut component_deps = Vec::new();
+            for &position in members.iter() {
+                for dep in graph.dep_positions(position) {
+                    let dep = component_of[dep];
+                    if dep != component && !component_deps.contains(&dep) {
+                        component_deps.push(dep);
+                    }
+                }
+            }
+
+            let ids = members
+                .iter()
+                .map(|&position| domain[position].id.clone())
+                .collect();
+            Node::new(component, component_deps, ids)
+        })
+        .collect())
+}
+
+/// returns values of nodes from `domain` grouped into strongly connected components, ending on the
+/// component of the node with id of `target`
+///
+/// components are in topological order and members of each one follow the order of `domain`, so
+/// nodes on a cycle end up together instead of failing the sort; fails on missing dependencies
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec!["mouse"], "Odie"),
+///         Node::new("mouse", vec!["dog", "cheese"], "Jerry"),
+///         Node::new("cheese", vec![], "Cheese"),
+///         Node::new("bird", vec![], "Tweety"),
+///     ];
+///     assert_eq!(
+///         sort_condensed(&domain, "cat"),
+///         Ok(vec![vec!["Cheese"], vec!["Odie", "Jerry"], vec!["Garfield"]])
+///     );
+/// ```
+pub fn sort_condensed<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<Vec<Item>>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let graph = CompactGraph::new(domain)?;
+    let start = match graph.index_of(&target) {
+        Some(start) => start as usize,
+        None => return Err(TopsortError::TargetNotFound(target)),
+    };
+
+    let mut reached = vec![false; domain.len()];
+    reached[start] = true;
+    let mut stack = vec![start];
+    while let Some(position) = stack.pop() {
+        for dep in graph.dep_positions(position) {
+            if !reached[dep] {
+                reached[dep] = true;
+                stack.push(dep);
+            }
+        }
+    }
+
+    // a component is reached as a whole or not at all
+    Ok(components(&graph)
+        .into_iter()
+        .filter(|members| reached[members[0]])
+        .map(|members| {
+            members
+                .into_iter()
+                .map(|position| domain[position].value.clone())
+                .collect()
+        })
+        .collect())
+}
+
+/// Tarjan's algorithm over `graph`, returns positions of members of every component,
+/// components in topological order and members in ascending order
+pub(crate) fn components<Id>(graph: &CompactGraph<Id>) -> Vec<Vec<usize>>
+where
+    Id: Clone + Eq + Hash,
+{
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; graph.len()];
+    let mut lowlink = vec![0; graph.len()];
+    let mut on_stack = vec![false; graph.len()];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+
+    for root in 0..graph.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // explicit call stack of (node, position of the next dependency to visit)
+        let mut frames = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, next_dep)) = frames.last_mut() {
+            let node = *node;
+            match graph.deps(node as u32).get(*next_dep) {
+                Some(&dep) => {
+                    let dep = dep as usize;
+                    *next_dep += 1;
+                    if index[dep] == UNVISITED {
+                        index[dep] = next_index;
+                        lowlink[dep] = next_index;
+                        next_index += 1;
+                        stack.push(dep);
+                        on_stack[dep] = true;
+                        frames.push((dep, 0));
+                    } else if on_stack[dep] {
+                        lowlink[node] = lowlink[node].min(index[dep]);
+                    }
+                }
+                None => {
+                    frames.pop();
+                    if let Some((parent, _)) = frames.last() {
+                        lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+                    }
+
+                    if lowlink[node] == index[node] {
+                        let mut component = Vec::new();
+                        while let Some(member) = stack.pop() {
+                            on_stack[member] = false;
+                            component.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        component.sort_unstable();
+                        components.push(component);
+                    }
+                }
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scc_works() {
+        let result = scc(&[
+            Node::new(1, vec![2, 4], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2, 5], "cat"),
+            Node::new(4, vec![5, 1], "dog"),
+            Node::new(5, vec![], "mouse"),
+            Node::new(6, vec![6], "bird"),
+        ]);
+        assert_eq!(result, Ok(vec![vec![5], vec![2, 3], vec![1, 4], vec![6]]));
+    }
+
+    #[test]
+    fn scc_acyclic_is_topological() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+        ];
+        assert_eq!(scc(&domain), Ok(vec![vec![2], vec![3], vec![1]]));
+    }
+
+    #[test]
+    fn scc_target_not_found() {
+        let result = scc(&[Node::new(1, vec![2], "hello")]);
+        assert_eq!(result, Err(TopsortError::TargetNotFound(2)));
+    }
+
+    #[test]
+    fn condense_works() {
+        let result = condense(&[
+            Node::new(1, vec![2, 4], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2, 5], "cat"),
+            Node::new(4, vec![5, 1, 3], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ]);
+        assert_eq!(
+            result,
+            Ok(vec![
+                Node::new(0, vec![], vec![5]),
+                Node::new(1, vec![0], vec![2, 3]),
+                Node::new(2, vec![1, 0], vec![1, 4]),
+            ])
+        );
+    }
+
+    #[test]
+    fn sort_condensed_works() {
+        let domain = [
+            Node::new(1, vec![2, 4], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2, 5], "cat"),
+            Node::new(4, vec![5, 1], "dog"),
+            Node::new(5, vec![], "mouse"),
+            Node::new(6, vec![6], "bird"),
+        ];
+        assert_eq!(
+            sort_condensed(&domain, 4),
+            Ok(vec![
+                vec!["mouse"],
+                vec!["world", "cat"],
+                vec!["hello", "dog"]
+            ])
+        );
+        assert_eq!(
+            sort_condensed(&domain, 3),
+            Ok(vec![vec!["mouse"], vec!["world", "cat"]])
+        );
+        assert_eq!(sort_condensed(&domain, 6), Ok(vec![vec!["bird"]]));
+        assert_eq!(
+            sort_condensed(&domain, 7),
+            Err(TopsortError::TargetNotFound(7))
+        );
+    }
+
+    #[test]
+    fn deep_chain() {
+        let depth = 100_000;
+        let domain: Vec<_> = (0..depth)
+            .map(|i| Node::new(i, if i + 1 < depth { vec![i + 1] } else { vec![0] }, ()))
+            .collect();
+        let graph = CompactGraph::new(&domain).unwrap();
+        assert_eq!(components(&graph), vec![(0..depth).collect::<Vec<_>>()]);
+    }
+}
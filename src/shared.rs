@@ -0,0 +1,146 @@
+use crate::{Graph, TopsortError};
+use core::borrow::Borrow;
+use core::hash::Hash;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// handle to a [`Graph`] shared between threads, clones of it refer to the same graph
+///
+/// any number of threads can sort and query the graph at once while edits wait for them to finish;
+/// a panic while holding the lock doesn't poison the graph, since its edits don't leave it half done
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///     use std::thread;
+///
+///     let graph = SharedGraph::new(Graph::new());
+///     graph.write().add_node("cat", "Garfield");
+///     graph.write().add_node("dog", "Odie");
+///     graph.write().add_edge("cat", "dog").unwrap();
+///
+///     let handles: Vec<_> = (0..4)
+///         .map(|_| {
+///             let graph = graph.clone();
+///             thread::spawn(move || graph.sort("cat"))
+///         })
+///         .collect();
+///     for handle in handles {
+///         assert_eq!(handle.join().unwrap(), Ok(vec!["Odie", "Garfield"]));
+///     }
+/// ```
+#[derive(Debug)]
+pub struct SharedGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    graph: Arc<RwLock<Graph<Id, Item>>>,
+}
+
+impl<Id, Item> SharedGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    pub fn new(graph: Graph<Id, Item>) -> Self {
+        Self {
+            graph: Arc::new(RwLock::new(graph)),
+        }
+    }
+
+    /// locks the graph for reading, blocking while it's being edited
+    pub fn read(&self) -> RwLockReadGuard<'_, Graph<Id, Item>> {
+        self.graph.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// locks the graph for editing, blocking while it's being read or edited elsewhere
+    pub fn write(&self) -> RwLockWriteGuard<'_, Graph<Id, Item>> {
+        self.graph.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// returns values of nodes in topological order, ending on the node with id of `target`, see
+    /// [`Graph::sort`]
+    pub fn sort(&self, target: Id) -> Result<Vec<Item>, TopsortError<Id>>
+    where
+        Item: Clone,
+    {
+        self.read().sort(target)
+    }
+
+    pub fn contains<Q>(&self, id: &Q) -> bool
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.read().contains(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+}
+
+impl<Id, Item> Clone for SharedGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn clone(&self) -> Self {
+        Self {
+            graph: Arc::clone(&self.graph),
+        }
+    }
+}
+
+impl<Id, Item> From<Graph<Id, Item>> for SharedGraph<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn from(graph: Graph<Id, Item>) -> Self {
+        Self::new(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn edits_are_shared() {
+        let mut graph = Graph::new();
+        graph.add_node(1, "hello");
+        graph.add_node(2, "world");
+        graph.add_edge(1, 2).unwrap();
+        let graph = SharedGraph::from(graph);
+        let other = graph.clone();
+
+        thread::spawn(move || {
+            other.write().add_node(3, "cat");
+            other.write().add_edge(2, 3).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(graph.len(), 3);
+        assert!(graph.contains(&3));
+        assert_eq!(graph.sort(1), Ok(vec!["cat", "world", "hello"]));
+    }
+
+    #[test]
+    fn survives_panics() {
+        let graph = SharedGraph::new(Graph::new());
+        graph.write().add_node(1, "hello");
+
+        let other = graph.clone();
+        let result = thread::spawn(move || {
+            let _guard = other.write();
+            panic!("oops");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert_eq!(graph.sort(1), Ok(vec!["hello"]));
+    }
+}
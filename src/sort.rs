@@ -0,0 +1,1454 @@
+use crate::collections::{HashMap, HashSet};
+use crate::traversal::Traversal;
+use crate::{DetailedError, HasDependencies, Node, TopsortError, TrySortError, Warning};
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::ops::ControlFlow;
+
+/// calls `cb` with nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// dependencies are visited in the order they are listed in `deps`, see the crate level docs on ordering
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut out = Vec::new();
+///     let result = sort_cb(
+///         &[
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///         ],
+///         "cat",
+///         &mut |node| {
+///             out.push(node.id);
+///         }
+///     );
+///     assert_eq!(result, Ok(()));
+///     assert_eq!(out, vec!["dog", "cat"]);
+/// ```
+pub fn sort_cb<Id, Item, F>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    cb: &mut F,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>),
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    traversal.visit(index, &mut |index| cb(&domain[index]))
+}
+
+/// calls fallible `cb` with nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// the first error returned by `cb` stops the sort and is returned as [`TrySortError::Callback`]
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut out = Vec::new();
+///     let result = sort_try_cb(
+///         &[
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///         ],
+///         "cat",
+///         &mut |node| {
+///             if node.id == "cat" {
+///                 return Err("no cats allowed");
+///             }
+///             out.push(node.id);
+///             Ok(())
+///         }
+///     );
+///     assert_eq!(result, Err(TrySortError::Callback("no cats allowed")));
+///     assert_eq!(out, vec!["dog"]);
+/// ```
+pub fn sort_try_cb<Id, Item, E, F>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    cb: &mut F,
+) -> Result<(), TrySortError<Id, E>>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>) -> Result<(), E>,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    traversal.start(index);
+    while let Some(finished) = traversal.next_finished() {
+        cb(&domain[finished?]).map_err(TrySortError::Callback)?;
+    }
+
+    Ok(())
+}
+
+/// calls `cb` with nodes from `domain` in topological order, ending on the node with id of `target`,
+/// until `cb` returns [`ControlFlow::Break`]
+///
+/// returns the value `cb` broke with, nodes past that point are not visited
+///
+/// # Examples:
+/// ```
+///     use std::ops::ControlFlow;
+///     use szyk::*;
+///
+///     let result = sort_cb_while(
+///         &[
+///             Node::new("cat", vec!["dog", "mouse"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///             Node::new("mouse", vec![], "Jerry"),
+///         ],
+///         "cat",
+///         &mut |node| {
+///             if node.id == "dog" {
+///                 ControlFlow::Break(node.value)
+///             } else {
+///                 ControlFlow::Continue(())
+///             }
+///         }
+///     );
+///     assert_eq!(result, Ok(ControlFlow::Break("Odie")));
+/// ```
+pub fn sort_cb_while<Id, Item, B, F>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    cb: &mut F,
+) -> Result<ControlFlow<B>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>) -> ControlFlow<B>,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    traversal.start(index);
+    while let Some(finished) = traversal.next_finished() {
+        if let ControlFlow::Break(value) = cb(&domain[finished?]) {
+            return Ok(ControlFlow::Break(value));
+        }
+    }
+
+    Ok(ControlFlow::Continue(()))
+}
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = sort(
+///         &[
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///         ],
+///         "cat",
+///     );
+///     assert_eq!(result, Ok(vec!["Odie", "Garfield"]));
+/// ```
+pub fn sort<Id, Item>(domain: &[Node<Id, Item>], target: Id) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut out = Vec::new();
+    sort_into(domain, target, &mut out)?;
+
+    Ok(out)
+}
+
+/// fills `out` with values of nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// `out` is cleared first and keeps its allocation, so a hot loop can reuse one buffer for many sorts,
+/// on error it holds the values visited before the error was found
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     let mut out = Vec::new();
+///     sort_into(&domain, "cat", &mut out).unwrap();
+///     assert_eq!(out, vec!["Odie", "Garfield"]);
+///     sort_into(&domain, "dog", &mut out).unwrap();
+///     assert_eq!(out, vec!["Odie"]);
+/// ```
+pub fn sort_into<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    out: &mut Vec<Item>,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    out.clear();
+    sort_cb(domain, target, &mut |node: &Node<_, _>| {
+        out.push(node.value.clone());
+    })
+}
+
+/// returns values of nodes from `domain` in topological order like [`sort`], naming the target by any
+/// borrowed form of the id type, e.g. `&str` for `String` ids
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new(String::from("cat"), vec![String::from("dog")], "Garfield"),
+///         Node::new(String::from("dog"), vec![], "Odie"),
+///     ];
+///     assert_eq!(sort_borrowed(&domain, "cat"), Ok(vec!["Odie", "Garfield"]));
+/// ```
+pub fn sort_borrowed<Id, Item, Q>(
+    domain: &[Node<Id, Item>],
+    target: &Q,
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = Id> + ?Sized,
+    Item: Clone,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index_borrowed(target)?;
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| out.push(domain[index].value.clone()))?;
+
+    Ok(out)
+}
+
+/// fills `out` with ids of nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// works like [`sort_into`], without requiring `Item` to be `Clone`
+pub fn sort_ids_into<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    out: &mut Vec<Id>,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    out.clear();
+    sort_cb(domain, target, &mut |node: &Node<_, _>| {
+        out.push(node.id.clone());
+    })
+}
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// leaving out nodes with ids in `done`
+///
+/// nodes in `done` are treated as already built along with their dependencies, which aren't visited
+/// through them, so only the work left to do is returned; ids in `done` don't have to be in `domain`
+///
+/// # Examples:
+/// ```
+///     use std::collections::HashSet;
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     let done: HashSet<_> = ["planks"].iter().copied().collect();
+///     assert_eq!(
+///         sort_remaining(&domain, "wooden pickaxe", &done),
+///         Ok(vec!["Sticks", "Pickaxe"])
+///     );
+/// ```
+pub fn sort_remaining<Id, Item, S>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    done: &HashSet<Id, S>,
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+    S: BuildHasher,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    for (position, node) in domain.iter().enumerate() {
+        if done.contains(&node.id) {
+            traversal.skip(position);
+        }
+    }
+
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| out.push(domain[index].value.clone()))?;
+
+    Ok(out)
+}
+
+/// returns references to values of nodes from `domain` in topological order, ending on the node with id of `target`
+///
+/// unlike [`sort`] it doesn't require `Item` to be `Clone`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], String::from("Garfield")),
+///         Node::new("dog", vec![], String::from("Odie")),
+///     ];
+///     let result = sort_refs(&domain, "cat");
+///     assert_eq!(result, Ok(vec![&domain[1].value, &domain[0].value]));
+/// ```
+pub fn sort_refs<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<&Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| out.push(&domain[index].value))?;
+
+    Ok(out)
+}
+
+/// returns ids paired with references to values of nodes from `domain` in topological order, ending on
+/// the node with id of `target`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], String::from("Garfield")),
+///         Node::new("dog", vec![], String::from("Odie")),
+///     ];
+///     let result = sort_with_ids(&domain, "cat");
+///     assert_eq!(result, Ok(vec![("dog", &domain[1].value), ("cat", &domain[0].value)]));
+/// ```
+pub fn sort_with_ids<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<(Id, &Item)>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| {
+        out.push((domain[index].id.clone(), &domain[index].value))
+    })?;
+
+    Ok(out)
+}
+
+/// returns positions in `domain` of nodes in topological order, ending on the node with id of `target`
+///
+/// useful for looking nodes up without cloning anything, or for indexing side tables kept in parallel with `domain`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///     ];
+///     let weights = [4.2, 30.0];
+///     let result = sort_indices(&domain, "cat").unwrap();
+///     assert_eq!(result, vec![1, 0]);
+///     assert_eq!(weights[result[0]], 30.0);
+/// ```
+pub fn sort_indices<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<usize>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| out.push(index))?;
+
+    Ok(out)
+}
+
+/// returns references to `items` in topological order, ending on the item with id of `target`
+///
+/// works like [`sort_refs`] for any type implementing [`HasDependencies`], see its docs for an example
+pub fn sort_items<T>(items: &[T], target: T::Id) -> Result<Vec<&T>, TopsortError<T::Id>>
+where
+    T: HasDependencies,
+{
+    let mut traversal = Traversal::new(items)?;
+    let index = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| out.push(&items[index]))?;
+
+    Ok(out)
+}
+
+/// consumes `domain` and returns owned values of nodes in topological order, ending on the node with id of `target`
+///
+/// `domain` can be any iterator of nodes, it's collected before sorting; nodes outside of the dependency
+/// tree of `target` are dropped
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = into_sort(
+///         vec![
+///             Node::new("cat", vec!["dog"], String::from("Garfield")),
+///             Node::new("dog", vec![], String::from("Odie")),
+///         ],
+///         "cat",
+///     );
+///     assert_eq!(result, Ok(vec![String::from("Odie"), String::from("Garfield")]));
+///
+///     let result = into_sort(
+///         (1..=3).map(|id| Node::new(id, (1..id).collect(), id * 10)),
+///         3,
+///     );
+///     assert_eq!(result, Ok(vec![10, 20, 30]));
+/// ```
+pub fn into_sort<Id, Item, I>(domain: I, target: Id) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    I: IntoIterator<Item = Node<Id, Item>>,
+{
+    let domain: Vec<_> = domain.into_iter().collect();
+    let mut order = Vec::new();
+    {
+        let mut traversal = Traversal::new(&domain)?;
+        let index = traversal.find_index(&target)?;
+        traversal.visit(index, &mut |index| order.push(index))?;
+    }
+
+    let mut values: Vec<Option<Item>> = domain.into_iter().map(|node| Some(node.value)).collect();
+    Ok(order
+        .into_iter()
+        .filter_map(|index| values[index].take())
+        .collect())
+}
+
+/// consumes `domain` and returns owned values of every node in topological order like [`sort_all`]
+///
+/// `domain` can be any iterator of nodes, it's collected before sorting
+pub fn into_sort_all<Id, Item, I>(domain: I) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    I: IntoIterator<Item = Node<Id, Item>>,
+{
+    let domain: Vec<_> = domain.into_iter().collect();
+    let mut order = Vec::with_capacity(domain.len());
+    {
+        let mut traversal = Traversal::new(&domain)?;
+        for index in 0..domain.len() {
+            traversal.visit(index, &mut |index| order.push(index))?;
+        }
+    }
+
+    let mut values: Vec<Option<Item>> = domain.into_iter().map(|node| Some(node.value)).collect();
+    Ok(order
+        .into_iter()
+        .filter_map(|index| values[index].take())
+        .collect())
+}
+
+/// returns ids from `edges` in topological order, ending on `target`, where every `(from, to)` pair
+/// makes `from` depend on `to`
+///
+/// nodes are created for every id appearing in `edges`, dependencies are visited in the order of `edges`
+/// and repeated pairs are ignored
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let edges = [
+///         ("wooden pickaxe", "planks"),
+///         ("wooden pickaxe", "sticks"),
+///         ("planks", "wood"),
+///         ("sticks", "planks"),
+///     ];
+///     assert_eq!(
+///         sort_edges(&edges, "wooden pickaxe"),
+///         Ok(vec!["wood", "planks", "sticks", "wooden pickaxe"])
+///     );
+/// ```
+pub fn sort_edges<Id>(edges: &[(Id, Id)], target: Id) -> Result<Vec<Id>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut nodes: Vec<Node<Id, ()>> = Vec::new();
+    let mut index: HashMap<Id, usize> = HashMap::new();
+    for (from, to) in edges.iter() {
+        for id in [from, to] {
+            if !index.contains_key(id) {
+                index.insert(id.clone(), nodes.len());
+                nodes.push(Node::new(id.clone(), Vec::new(), ()));
+            }
+        }
+        let deps = &mut nodes[index[from]].deps;
+        if !deps.contains(to) {
+            deps.push(to.clone());
+        }
+    }
+
+    let mut traversal = Traversal::with_index(&nodes, &index);
+    let start = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal.visit(start, &mut |position| out.push(nodes[position].id.clone()))?;
+
+    Ok(out)
+}
+
+/// calls `cb` with nodes from `domain` in topological order, covering dependencies of all `targets`
+///
+/// dependencies shared between targets are visited only once
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut out = Vec::new();
+///     let result = sort_multi_cb(
+///         &[
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///             Node::new("mouse", vec!["dog"], "Jerry"),
+///         ],
+///         &["cat", "mouse"],
+///         &mut |node| {
+///             out.push(node.id);
+///         }
+///     );
+///     assert_eq!(result, Ok(()));
+///     assert_eq!(out, vec!["dog", "cat", "mouse"]);
+/// ```
+pub fn sort_multi_cb<Id, Item, F>(
+    domain: &[Node<Id, Item>],
+    targets: &[Id],
+    cb: &mut F,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>),
+{
+    let mut traversal = Traversal::new(domain)?;
+    for target in targets {
+        let index = traversal.find_index(target)?;
+        traversal.visit(index, &mut |index| cb(&domain[index]))?;
+    }
+
+    Ok(())
+}
+
+/// returns values of nodes from `domain` in topological order, covering dependencies of all `targets`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = sort_multi(
+///         &[
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///             Node::new("mouse", vec!["dog"], "Jerry"),
+///         ],
+///         &["cat", "mouse"],
+///     );
+///     assert_eq!(result, Ok(vec!["Odie", "Garfield", "Jerry"]));
+/// ```
+pub fn sort_multi<Id, Item>(
+    domain: &[Node<Id, Item>],
+    targets: &[Id],
+) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut out = Vec::new();
+    sort_multi_cb(domain, targets, &mut |node: &Node<_, _>| {
+        out.push(node.value.clone());
+    })?;
+
+    Ok(out)
+}
+
+/// calls `cb` with every node from `domain` in topological order, including disconnected components
+///
+/// nodes that don't depend on each other are visited in the order they appear in `domain`
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut out = Vec::new();
+///     let result = sort_all_cb(
+///         &[
+///             Node::new("cat", vec!["dog"], "Garfield"),
+///             Node::new("dog", vec![], "Odie"),
+///             Node::new("mouse", vec![], "Jerry"),
+///         ],
+///         &mut |node| {
+///             out.push(node.id);
+///         }
+///     );
+///     assert_eq!(result, Ok(()));
+///     assert_eq!(out, vec!["dog", "cat", "mouse"]);
+/// ```
+pub fn sort_all_cb<Id, Item, F>(
+    domain: &[Node<Id, Item>],
+    cb: &mut F,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    F: FnMut(&Node<Id, Item>),
+{
+    let mut traversal = Traversal::new(domain)?;
+    for index in 0..domain.len() {
+        traversal.visit(index, &mut |index| cb(&domain[index]))?;
+    }
+
+    Ok(())
+}
+
+/// returns values of every node from `domain` in topological order, including disconnected components
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = sort_all(&[
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///         Node::new("mouse", vec![], "Jerry"),
+///     ]);
+///     assert_eq!(result, Ok(vec!["Odie", "Garfield", "Jerry"]));
+/// ```
+pub fn sort_all<Id, Item>(domain: &[Node<Id, Item>]) -> Result<Vec<Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut out = Vec::with_capacity(domain.len());
+    sort_all_cb(domain, &mut |node: &Node<_, _>| {
+        out.push(node.value.clone());
+    })?;
+
+    Ok(out)
+}
+
+/// values of sorted nodes with warnings found by a lenient sort
+type Lenient<Id, Item> = (Vec<Item>, Vec<Warning<Id>>);
+
+/// returns values of nodes from `domain` in topological order, ending on the node with id of `target`,
+/// together with warnings about dependencies missing from the domain
+///
+/// missing dependencies are treated as leaves instead of failing the sort, so a best-effort order can be
+/// made from incomplete data; missing targets and cycles still fail
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///     ];
+///     assert_eq!(
+///         sort_lenient(&domain, "wooden pickaxe"),
+///         Ok((
+///             vec!["Planks", "Pickaxe"],
+///             vec![
+///                 Warning::MissingDependency("planks", "wood"),
+///                 Warning::MissingDependency("wooden pickaxe", "sticks"),
+///             ]
+///         ))
+///     );
+/// ```
+pub fn sort_lenient<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Lenient<Id, Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut traversal = Traversal::new(domain)?;
+    traversal.collect_warnings();
+    let index = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal.visit(index, &mut |index| out.push(domain[index].value.clone()))?;
+
+    Ok((out, traversal.take_warnings()))
+}
+
+/// returns values of every node from `domain` in topological order like [`sort_all`], treating missing
+/// dependencies as leaves like [`sort_lenient`]
+pub fn sort_all_lenient<Id, Item>(
+    domain: &[Node<Id, Item>],
+) -> Result<Lenient<Id, Item>, TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut traversal = Traversal::new(domain)?;
+    traversal.collect_warnings();
+    let mut out = Vec::with_capacity(domain.len());
+    for index in 0..domain.len() {
+        traversal.visit(index, &mut |index| out.push(domain[index].value.clone()))?;
+    }
+
+    Ok((out, traversal.take_warnings()))
+}
+
+/// returns values of nodes from `domain` in topological order like [`sort`], on failure reporting the
+/// path from `target` to the node that ran into the error
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///     ];
+///     let err = sort_detailed(&domain, "wooden pickaxe").unwrap_err();
+///     assert_eq!(err.error, TopsortError::TargetNotFound("wood"));
+///     assert_eq!(err.requested_by(), Some(&"planks"));
+///     assert_eq!(err.path, vec!["wooden pickaxe", "planks"]);
+/// ```
+pub fn sort_detailed<Id, Item>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+) -> Result<Vec<Item>, DetailedError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    let mut out = Vec::new();
+    traversal
+        .visit(index, &mut |index| out.push(domain[index].value.clone()))
+        .map_err(|error| DetailedError {
+            error,
+            path: traversal.path(),
+        })?;
+
+    Ok(out)
+}
+
+/// returns values of every node from `domain` in topological order like [`sort_all`], on failure
+/// reporting the path like [`sort_detailed`], starting from the first node of the domain leading to
+/// the error
+pub fn sort_all_detailed<Id, Item>(
+    domain: &[Node<Id, Item>],
+) -> Result<Vec<Item>, DetailedError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    Item: Clone,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let mut out = Vec::with_capacity(domain.len());
+    for index in 0..domain.len() {
+        if let Err(error) =
+            traversal.visit(index, &mut |index| out.push(domain[index].value.clone()))
+        {
+            return Err(DetailedError {
+                error,
+                path: traversal.path(),
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_cb_works() {
+        let mut out = Vec::new();
+        let result = sort_cb(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+            ],
+            1,
+            &mut |node: &Node<_, _>| {
+                out.push(node.value);
+            },
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, vec!["world", "cat", "hello"]);
+    }
+
+    #[test]
+    fn sort_borrowed_works() {
+        let domain = [
+            Node::new(String::from("hello"), vec![String::from("world")], 1),
+            Node::new(String::from("world"), vec![], 2),
+        ];
+        assert_eq!(sort_borrowed(&domain, "hello"), Ok(vec![2, 1]));
+        assert_eq!(
+            sort_borrowed(&domain, "cat"),
+            Err(TopsortError::TargetNotFound(String::from("cat")))
+        );
+    }
+
+    /// counts calls of `to_owned` on [`Key`]
+    static OWNED_KEYS: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+    /// borrowed form of [`Name`], owning it is counted in [`OWNED_KEYS`]
+    #[derive(Debug, Hash, PartialEq, Eq)]
+    struct Key(u32);
+
+    #[derive(Debug, Hash, PartialEq, Eq)]
+    struct Name(Key);
+
+    impl Clone for Name {
+        fn clone(&self) -> Self {
+            Name(Key((self.0).0))
+        }
+    }
+
+    impl Borrow<Key> for Name {
+        fn borrow(&self) -> &Key {
+            &self.0
+        }
+    }
+
+    impl ToOwned for Key {
+        type Owned = Name;
+
+        fn to_owned(&self) -> Name {
+            OWNED_KEYS.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+            Name(Key(self.0))
+        }
+    }
+
+    #[test]
+    fn sort_borrowed_owns_target_only_on_error() {
+        let domain = [
+            Node::new(Name(Key(1)), vec![Name(Key(2))], "hello"),
+            Node::new(Name(Key(2)), vec![], "world"),
+        ];
+        let mut graph = crate::Graph::new();
+        graph.add_node(Name(Key(1)), "hello");
+        graph.add_node(Name(Key(2)), "world");
+        graph.add_edge(Name(Key(1)), Name(Key(2))).unwrap();
+
+        assert_eq!(sort_borrowed(&domain, &Key(1)), Ok(vec!["world", "hello"]));
+        assert_eq!(graph.sort_borrowed(&Key(1)), Ok(vec!["world", "hello"]));
+        assert_eq!(OWNED_KEYS.load(core::sync::atomic::Ordering::SeqCst), 0);
+
+        assert_eq!(
+            sort_borrowed(&domain, &Key(3)),
+            Err(TopsortError::TargetNotFound(Name(Key(3))))
+        );
+        assert_eq!(OWNED_KEYS.load(core::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn depth_first_ignores_soft_constraints() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world").with_after(vec![3]),
+            Node::new(3, vec![], "cat"),
+        ];
+        assert_eq!(sort(&domain, 1), Ok(vec!["world", "cat", "hello"]));
+        assert_eq!(sort_all(&domain), Ok(vec!["world", "cat", "hello"]));
+        assert_eq!(
+            crate::sort_kahn(&domain, 1),
+            Ok(vec!["cat", "world", "hello"])
+        );
+    }
+
+    #[test]
+    fn sort_works() {
+        let result = sort(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+            ],
+            1,
+        );
+        assert_eq!(result, Ok(vec!["world", "cat", "hello"]));
+    }
+
+    #[test]
+    fn sort_into_reuses_buffer() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2, 4], "cat"),
+        ];
+        let mut out = Vec::with_capacity(8);
+        out.push("stale");
+        assert_eq!(sort_into(&domain, 2, &mut out), Ok(()));
+        assert_eq!(out, vec!["world"]);
+        assert_eq!(
+            sort_into(&domain, 1, &mut out),
+            Err(TopsortError::TargetNotFound(4))
+        );
+        assert_eq!(out, vec!["world"]);
+        assert!(out.capacity() >= 8);
+
+        let mut ids = vec![7];
+        assert_eq!(
+            sort_ids_into(&domain, 3, &mut ids),
+            Err(TopsortError::TargetNotFound(4))
+        );
+        assert_eq!(sort_ids_into(&domain[..2], 2, &mut ids), Ok(()));
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn sort_remaining_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![4], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(5, vec![], "mouse"),
+        ];
+        let done: HashSet<_> = [2, 6].iter().copied().collect();
+        // the missing dependency of 2 isn't visited
+        assert_eq!(sort_remaining(&domain, 1, &done), Ok(vec!["cat", "hello"]));
+        assert_eq!(sort_remaining(&domain, 2, &done), Ok(vec![]));
+        assert_eq!(
+            sort_remaining(&domain, 1, &HashSet::new()),
+            Err(TopsortError::TargetNotFound(4))
+        );
+        assert_eq!(
+            sort_remaining(&domain, 6, &done),
+            Err(TopsortError::TargetNotFound(6))
+        );
+    }
+
+    #[test]
+    fn target_not_found() {
+        let result = sort(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2, 4], "cat"),
+            ],
+            1,
+        );
+        assert_eq!(result, Err(TopsortError::TargetNotFound(4)));
+    }
+
+    #[test]
+    fn optional_deps() {
+        let domain = [
+            Node::new(1, vec![2], "hello").with_optional_deps(vec![3, 4]),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat").with_optional_deps(vec![1]),
+        ];
+        assert_eq!(
+            sort(&domain, 1),
+            Err(TopsortError::CyclicDependency(vec![1, 3, 1]))
+        );
+
+        let domain = [
+            Node::new(1, vec![2], "hello").with_optional_deps(vec![3, 4]),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+        ];
+        assert_eq!(sort(&domain, 1), Ok(vec!["world", "cat", "hello"]));
+        assert_eq!(
+            crate::sort_kahn(&domain, 1),
+            Ok(vec!["world", "cat", "hello"])
+        );
+    }
+
+    #[test]
+    fn sort_detailed_works() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3, 4], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![5, 2], "dog"),
+        ];
+        assert_eq!(sort_detailed(&domain, 3), Ok(vec!["cat"]));
+        assert_eq!(
+            sort_detailed(&domain, 1),
+            Err(DetailedError {
+                error: TopsortError::TargetNotFound(5),
+                path: vec![1, 2, 4],
+            })
+        );
+        assert_eq!(
+            sort_detailed(&domain, 6),
+            Err(DetailedError {
+                error: TopsortError::TargetNotFound(6),
+                path: vec![],
+            })
+        );
+        assert_eq!(
+            sort_all_detailed(&domain[1..]),
+            Err(DetailedError {
+                error: TopsortError::TargetNotFound(5),
+                path: vec![2, 4],
+            })
+        );
+
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2], "cat"),
+        ];
+        assert_eq!(
+            sort_all_detailed(&domain),
+            Err(DetailedError {
+                error: TopsortError::CyclicDependency(vec![2, 3, 2]),
+                path: vec![1, 2, 3],
+            })
+        );
+    }
+
+    #[test]
+    fn sort_lenient_works() {
+        let domain = [
+            Node::new(1, vec![2, 4], "hello").with_optional_deps(vec![5]),
+            Node::new(2, vec![4, 6], "world"),
+            Node::new(3, vec![], "cat"),
+        ];
+        assert_eq!(
+            sort_lenient(&domain, 1),
+            Ok((
+                vec!["world", "hello"],
+                vec![
+                    Warning::MissingDependency(2, 4),
+                    Warning::MissingDependency(2, 6),
+                    Warning::MissingDependency(1, 4),
+                    Warning::MissingOptionalDependency(1, 5),
+                ]
+            ))
+        );
+        assert_eq!(
+            sort_all_lenient(&domain).map(|(out, warnings)| (out, warnings.len())),
+            Ok((vec!["world", "hello", "cat"], 4))
+        );
+        assert_eq!(sort_lenient(&domain, 3), Ok((vec!["cat"], vec![])));
+        assert_eq!(
+            sort_lenient(&domain, 4),
+            Err(TopsortError::TargetNotFound(4))
+        );
+    }
+
+    #[test]
+    fn sort_lenient_cyclic_dependency() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![1], "world"),
+        ];
+        assert_eq!(
+            sort_lenient(&domain, 1),
+            Err(TopsortError::CyclicDependency(vec![1, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn cyclic_dependency() {
+        let result = sort(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![1], "world"),
+                Node::new(3, vec![2], "cat"),
+            ],
+            1,
+        );
+        assert_eq!(result, Err(TopsortError::CyclicDependency(vec![1, 2, 1])));
+    }
+
+    #[test]
+    fn empty_domain() {
+        let result = sort(&[] as &[Node<i32, i32>], 1);
+        assert_eq!(result, Err(TopsortError::TargetNotFound(1)));
+    }
+
+    #[test]
+    fn sort_try_cb_works() {
+        let mut out = Vec::new();
+        let result: Result<(), TrySortError<_, ()>> = sort_try_cb(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+            ],
+            1,
+            &mut |node: &Node<_, _>| {
+                out.push(node.value);
+                Ok(())
+            },
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, vec!["world", "cat", "hello"]);
+    }
+
+    #[test]
+    fn sort_try_cb_sort_error() {
+        let result: Result<(), TrySortError<_, ()>> =
+            sort_try_cb(&[Node::new(1, vec![2], "hello")], 1, &mut |_: &Node<
+                _,
+                _,
+            >| {
+                Ok(())
+            });
+        assert_eq!(
+            result,
+            Err(TrySortError::Sort(TopsortError::TargetNotFound(2)))
+        );
+    }
+
+    #[test]
+    fn sort_cb_while_stops_early() {
+        let mut out = Vec::new();
+        let result = sort_cb_while(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![4], "cat"),
+            ],
+            1,
+            &mut |node: &Node<_, _>| {
+                out.push(node.value);
+                ControlFlow::Break(node.id)
+            },
+        );
+        // the missing dependency of 3 is never reached
+        assert_eq!(result, Ok(ControlFlow::Break(2)));
+        assert_eq!(out, vec!["world"]);
+    }
+
+    #[test]
+    fn sort_cb_while_continues() {
+        let mut out = Vec::new();
+        let result: Result<ControlFlow<()>, _> = sort_cb_while(
+            &[
+                Node::new(1, vec![2], "hello"),
+                Node::new(2, vec![], "world"),
+            ],
+            1,
+            &mut |node: &Node<_, _>| {
+                out.push(node.value);
+                ControlFlow::Continue(())
+            },
+        );
+        assert_eq!(result, Ok(ControlFlow::Continue(())));
+        assert_eq!(out, vec!["world", "hello"]);
+    }
+
+    #[test]
+    fn sort_all_works() {
+        let result = sort_all(&[
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![5], "dog"),
+            Node::new(5, vec![], "mouse"),
+        ]);
+        assert_eq!(result, Ok(vec!["world", "cat", "hello", "mouse", "dog"]));
+    }
+
+    #[test]
+    fn sort_all_cyclic_dependency() {
+        let result = sort_all(&[
+            Node::new(1, vec![], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2], "cat"),
+        ]);
+        assert_eq!(result, Err(TopsortError::CyclicDependency(vec![2, 3, 2])));
+    }
+
+    #[test]
+    fn sort_multi_works() {
+        let result = sort_multi(
+            &[
+                Node::new(1, vec![2, 3], "hello"),
+                Node::new(2, vec![], "world"),
+                Node::new(3, vec![2], "cat"),
+                Node::new(4, vec![3], "dog"),
+                Node::new(5, vec![], "mouse"),
+            ],
+            &[4, 1],
+        );
+        assert_eq!(result, Ok(vec!["world", "cat", "dog", "hello"]));
+    }
+
+    #[test]
+    fn sort_multi_target_not_found() {
+        let result = sort_multi(
+            &[Node::new(1, vec![], "hello"), Node::new(2, vec![], "world")],
+            &[1, 3],
+        );
+        assert_eq!(result, Err(TopsortError::TargetNotFound(3)));
+    }
+
+    #[test]
+    fn sort_refs_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], String::from("hello")),
+            Node::new(2, vec![], String::from("world")),
+            Node::new(3, vec![2], String::from("cat")),
+        ];
+        let result = sort_refs(&domain, 1);
+        assert_eq!(
+            result,
+            Ok(vec![&domain[1].value, &domain[2].value, &domain[0].value])
+        );
+    }
+
+    #[test]
+    fn sort_with_ids_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], String::from("hello")),
+            Node::new(2, vec![], String::from("world")),
+            Node::new(3, vec![2], String::from("cat")),
+        ];
+        assert_eq!(
+            sort_with_ids(&domain, 3),
+            Ok(vec![(2, &domain[1].value), (3, &domain[2].value)])
+        );
+        assert_eq!(
+            sort_with_ids(&domain[1..], 1),
+            Err(TopsortError::TargetNotFound(1))
+        );
+    }
+
+    #[test]
+    fn sort_indices_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        assert_eq!(sort_indices(&domain, 1), Ok(vec![1, 2, 0]));
+        assert_eq!(sort_indices(&domain, 4), Ok(vec![3]));
+        assert_eq!(
+            sort_indices(&domain, 5),
+            Err(TopsortError::TargetNotFound(5))
+        );
+    }
+
+    #[test]
+    fn into_sort_works() {
+        let result = into_sort(
+            vec![
+                Node::new(1, vec![2, 3], String::from("hello")),
+                Node::new(2, vec![], String::from("world")),
+                Node::new(3, vec![2], String::from("cat")),
+                Node::new(4, vec![], String::from("dog")),
+            ],
+            1,
+        );
+        assert_eq!(
+            result,
+            Ok(vec![
+                String::from("world"),
+                String::from("cat"),
+                String::from("hello")
+            ])
+        );
+    }
+
+    #[test]
+    fn into_sort_all_works() {
+        let domain = (1..=4).map(|id| Node::new(id, (id + 1..=4).rev().collect(), id.to_string()));
+        assert_eq!(
+            into_sort_all(domain),
+            Ok(vec![
+                String::from("4"),
+                String::from("3"),
+                String::from("2"),
+                String::from("1")
+            ])
+        );
+        assert_eq!(
+            into_sort_all(vec![Node::new(1, vec![2], "hello")]),
+            Err(TopsortError::TargetNotFound(2))
+        );
+    }
+
+    #[test]
+    fn self_dependency() {
+        let result = sort(&[Node::new(1, vec![1], "hello")], 1);
+        assert_eq!(result, Err(TopsortError::SelfDependency(1)));
+    }
+
+    #[test]
+    fn cycle_path_excludes_prefix() {
+        let result = sort(
+            &[
+                Node::new(1, vec![2], "hello"),
+                Node::new(2, vec![3], "world"),
+                Node::new(3, vec![4], "cat"),
+                Node::new(4, vec![2], "dog"),
+            ],
+            1,
+        );
+        assert_eq!(
+            result,
+            Err(TopsortError::CyclicDependency(vec![2, 3, 4, 2]))
+        );
+    }
+
+    #[test]
+    fn string_ids() {
+        let result = sort(
+            &[
+                Node::new(String::from("cat"), vec![String::from("dog")], "Garfield"),
+                Node::new(String::from("dog"), vec![], "Odie"),
+            ],
+            String::from("cat"),
+        );
+        assert_eq!(result, Ok(vec!["Odie", "Garfield"]));
+    }
+
+    #[test]
+    fn stable_order_follows_deps() {
+        let diamond = |deps| {
+            sort(
+                &[
+                    Node::new(1, deps, "hello"),
+                    Node::new(2, vec![4], "world"),
+                    Node::new(3, vec![4], "cat"),
+                    Node::new(4, vec![], "dog"),
+                ],
+                1,
+            )
+        };
+        assert_eq!(
+            diamond(vec![2, 3]),
+            Ok(vec!["dog", "world", "cat", "hello"])
+        );
+        assert_eq!(
+            diamond(vec![3, 2]),
+            Ok(vec!["dog", "cat", "world", "hello"])
+        );
+    }
+
+    #[test]
+    fn stable_order_ignores_domain_order() {
+        let mut domain = vec![
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        let expected = sort(&domain, 1);
+        domain.reverse();
+        assert_eq!(sort(&domain, 1), expected);
+        domain.swap(0, 2);
+        assert_eq!(sort(&domain, 1), expected);
+    }
+
+    #[test]
+    fn stable_order_follows_domain_for_sort_all() {
+        let result = sort_all(&[
+            Node::new(3, vec![], "cat"),
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![], "world"),
+        ]);
+        assert_eq!(result, Ok(vec!["cat", "world", "hello"]));
+    }
+
+    #[test]
+    fn duplicate_id() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(2, vec![], "cat"),
+        ];
+        assert_eq!(sort(&domain, 1), Err(TopsortError::DuplicateId(2)));
+        assert_eq!(sort_all(&domain), Err(TopsortError::DuplicateId(2)));
+    }
+
+    #[test]
+    fn sort_items_works() {
+        struct Task {
+            name: u32,
+            after: Vec<u32>,
+        }
+
+        impl HasDependencies for Task {
+            type Id = u32;
+
+            fn id(&self) -> &u32 {
+                &self.name
+            }
+
+            fn deps(&self) -> &[u32] {
+                &self.after
+            }
+        }
+
+        let tasks = [
+            Task {
+                name: 1,
+                after: vec![2, 3],
+            },
+            Task {
+                name: 2,
+                after: vec![],
+            },
+            Task {
+                name: 3,
+                after: vec![2],
+            },
+        ];
+        let result: Result<Vec<_>, _> =
+            sort_items(&tasks, 1).map(|tasks| tasks.into_iter().map(|task| task.name).collect());
+        assert_eq!(result, Ok(vec![2, 3, 1]));
+
+        let result = sort_items(&tasks[1..], 1).map(|tasks| tasks.len());
+        assert_eq!(result, Err(TopsortError::TargetNotFound(1)));
+    }
+
+    #[test]
+    fn sort_edges_works() {
+        let edges = [(1, 2), (1, 3), (3, 2), (4, 1)];
+        assert_eq!(sort_edges(&edges, 1), Ok(vec![2, 3, 1]));
+        assert_eq!(sort_edges(&edges, 2), Ok(vec![2]));
+        assert_eq!(sort_edges(&edges, 5), Err(TopsortError::TargetNotFound(5)));
+        assert_eq!(
+            sort_edges(&[(1, 2), (1, 3), (1, 2), (3, 2), (1, 3)], 1),
+            Ok(vec![2, 3, 1])
+        );
+        assert_eq!(
+            sort_edges(&[(1, 2), (2, 1)], 1),
+            Err(TopsortError::CyclicDependency(vec![1, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn deep_chain() {
+        let depth = 100_000;
+        let domain: Vec<_> = (0..depth)
+            .map(|i| Node::new(i, if i > 0 { vec![i - 1] } else { vec![] }, i))
+            .collect();
+        let result = sort(&domain, depth - 1);
+        assert_eq!(result, Ok((0..depth).collect()));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::bitset::BitSet;
+use crate::traversal::Traversal;
+use crate::{Node, TopsortError};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::sync::atomic::AtomicBool;
+
+/// callback reporting the number of visited nodes and the size of the domain
+type Progress<'a> = Box<dyn FnMut(usize, usize) + 'a>;
+
+/// sorts the same `domain` repeatedly, keeping the id lookup and traversal buffers between calls
+///
+/// building a [`Sorter`] costs as much as a single [`crate::sort`], each sort after that only
+/// touches nodes reachable from its target, see [`Sorter::on_progress`] for reporting progress on big domains
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog"], "Garfield"),
+///         Node::new("dog", vec![], "Odie"),
+///         Node::new("mouse", vec!["dog"], "Jerry"),
+///     ];
+///     let mut sorter = Sorter::new(&domain).unwrap();
+///     assert_eq!(sorter.sort("cat"), Ok(vec!["Odie", "Garfield"]));
+///     assert_eq!(sorter.sort("mouse"), Ok(vec!["Odie", "Jerry"]));
+/// ```
+pub struct Sorter<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    domain: &'a [Node<Id, Item>],
+    traversal: Traversal<'a, Node<Id, Item>>,
+    /// indices of nodes visited by the last sort
+    order: Vec<usize>,
+    /// reporting interval and callback set by [`Sorter::on_progress`]
+    progress: Option<(usize, Progress<'a>)>,
+}
+
+impl<'a, Id, Item> Sorter<'a, Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// fails if ids in `domain` aren't unique
+    pub fn new(domain: &'a [Node<Id, Item>]) -> Result<Self, TopsortError<Id>> {
+        Ok(Self {
+            domain,
+            traversal: Traversal::new(domain)?,
+            order: Vec::new(),
+            progress: None,
+        })
+    }
+
+    /// calls `progress` with the number of visited nodes and the number of nodes in the domain
+    /// every `interval` visited nodes and once more at the end of each successful sort
+    ///
+    /// only [`Sorter::sort_all`] is guaranteed to visit every node, other sorts may finish early
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let domain: Vec<_> = (0..10).map(|i| Node::new(i, vec![], ())).collect();
+    ///     let mut reports = Vec::new();
+    ///     let mut sorter = Sorter::new(&domain)
+    ///         .unwrap()
+    ///         .on_progress(4, |visited, total| reports.push((visited, total)));
+    ///     sorter.sort_all().unwrap();
+    ///     drop(sorter);
+    ///     assert_eq!(reports, vec![(4, 10), (8, 10), (10, 10)]);
+    /// ```
+    pub fn on_progress<F>(mut self, interval: usize, progress: F) -> Self
+    where
+        F: FnMut(usize, usize) + 'a,
+    {
+        self.progress = Some((interval.max(1), Box::new(progress)));
+        self
+    }
+
+    /// calls `cb` with nodes in topological order, ending on the node with id of `target`, see [`crate::sort_cb`]
+    pub fn sort_cb<F>(&mut self, target: Id, cb: &mut F) -> Result<(), TopsortError<Id>>
+    where
+        F: FnMut(&'a Node<Id, Item>),
+    {
+        self.run(target)?;
+        for &index in self.order.iter() {
+            cb(&self.domain[index]);
+        }
+
+        Ok(())
+    }
+
+    /// makes sorts fail with [`TopsortError::Cancelled`] once `flag` is set, e.g. from another thread
+    ///
+    /// the flag is checked on every step of the traversal and isn't cleared by the sorter, sorting
+    /// works again once it's cleared
+    ///
+    /// # Examples:
+    /// ```
+    ///     use std::sync::atomic::{AtomicBool, Ordering};
+    ///     use szyk::*;
+    ///
+    ///     let domain: Vec<_> = (0..1000).map(|i| Node::new(i, vec![], ())).collect();
+    ///     let cancel = AtomicBool::new(false);
+    ///     let mut sorter = Sorter::new(&domain)
+    ///         .unwrap()
+    ///         .cancel_on(&cancel)
+    ///         .on_progress(100, |_, _| cancel.store(true, Ordering::Relaxed));
+    ///     assert_eq!(sorter.sort_all(), Err(TopsortError::Cancelled));
+    ///
+    ///     cancel.store(false, Ordering::Relaxed);
+    ///     assert_eq!(sorter.sort(999), Ok(vec![()]));
+    /// ```
+    pub fn cancel_on(mut self, flag: &'a AtomicBool) -> Self {
+        self.traversal.cancel_on(flag);
+        self
+    }
+
+    /// makes sorts fail with [`TopsortError::DepthLimitExceeded`] on dependencies more than `max_depth`
+    /// edges away from the target, bounding the memory used by the traversal on adversarial inputs
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let domain: Vec<_> = (0..10)
+    ///         .map(|i| Node::new(i, if i > 0 { vec![i - 1] } else { vec![] }, i))
+    ///         .collect();
+    ///     let mut sorter = Sorter::new(&domain).unwrap().max_depth(3);
+    ///     assert_eq!(sorter.sort(3), Ok(vec![0, 1, 2, 3]));
+    ///     assert_eq!(sorter.sort(9), Err(TopsortError::DepthLimitExceeded(5)));
+    /// ```
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.traversal.limit_depth(max_depth);
+        self
+    }
+
+    /// returns values of nodes in topological order, ending on the node with id of `target`, see [`crate::sort`]
+    pub fn sort(&mut self, target: Id) -> Result<Vec<Item>, TopsortError<Id>>
+    where
+        Item: Clone,
+    {
+        self.run(target)?;
+        Ok(self
+            .order
+            .iter()
+            .map(|&index| self.domain[index].value.clone())
+            .collect())
+    }
+
+    /// returns references to values of nodes in topological order, ending on the node with id of `target`,
+    /// see [`crate::sort_refs`]
+    pub fn sort_refs(&mut self, target: Id) -> Result<Vec<&'a Item>, TopsortError<Id>> {
+        self.run(target)?;
+        let domain = self.domain;
+        Ok(self
+            .order
+            .iter()
+            .map(|&index| &domain[index].value)
+            .collect())
+    }
+
+    /// returns values of every node in topological order, see [`crate::sort_all`]
+    pub fn sort_all(&mut self) -> Result<Vec<Item>, TopsortError<Id>>
+    where
+        Item: Clone,
+    {
+        self.traversal.reset(&self.order);
+        self.order.clear();
+        self.visit(0..self.domain.len())?;
+        Ok(self
+            .order
+            .iter()
+            .map(|&index| self.domain[index].value.clone())
+            .collect())
+    }
+
+    /// positions in the domain of nodes visited by the last sort
+    ///
+    /// # Examples:
+    /// ```
+    ///     use szyk::*;
+    ///
+    ///     let domain = [
+    ///         Node::new("cat", vec!["dog"], "Garfield"),
+    ///         Node::new("dog", vec![], "Odie"),
+    ///         Node::new("mouse", vec![], "Jerry"),
+    ///     ];
+    ///     let mut sorter = Sorter::new(&domain).unwrap();
+    ///     sorter.sort("cat").unwrap();
+    ///     let unused: Vec<_> = (0..domain.len())
+    ///         .filter(|&position| !sorter.visited().contains(position))
+    ///         .map(|position| domain[position].id)
+    ///         .collect();
+    ///     assert_eq!(unused, vec!["mouse"]);
+    /// ```
+    pub fn visited(&self) -> &BitSet {
+        self.traversal.visited()
+    }
+
+    /// fills `order` with the dependency tree of `target`
+    fn run(&mut self, target: Id) -> Result<(), TopsortError<Id>> {
+        self.traversal.reset(&self.order);
+        self.order.clear();
+
+        let index = self.traversal.find_index(&target)?;
+        self.visit(core::iter::once(index))
+    }
+
+    /// appends dependency trees of nodes at `roots` to `order`, reporting progress
+    fn visit<I>(&mut self, roots: I) -> Result<(), TopsortError<Id>>
+    where
+        I: Iterator<Item = usize>,
+    {
+        let total = self.domain.len();
+        let Self {
+            traversal,
+            order,
+            progress,
+            ..
+        } = self;
+
+        for root in roots {
+            traversal.visit(root, &mut |index| {
+                order.push(index);
+                if let Some((interval, progress)) = progress {
+                    if order.len() % *interval == 0 {
+                        progress(order.len(), total);
+                    }
+                }
+            })?;
+        }
+
+        if let Some((interval, progress)) = progress {
+            if order.len() % *interval != 0 {
+                progress(order.len(), total);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorter_matches_sort() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![3], "dog"),
+        ];
+        let mut sorter = Sorter::new(&domain).unwrap();
+        for target in [1, 4, 2, 1, 3] {
+            assert_eq!(sorter.sort(target), crate::sort(&domain, target));
+        }
+        assert_eq!(sorter.sort_refs(4), Ok(vec![&"world", &"cat", &"dog"]));
+    }
+
+    #[test]
+    fn sorter_recovers_from_errors() {
+        let domain = [
+            Node::new(1, vec![2], "hello"),
+            Node::new(2, vec![3], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![5], "dog"),
+            Node::new(6, vec![], "mouse"),
+        ];
+        let mut sorter = Sorter::new(&domain).unwrap();
+        assert_eq!(
+            sorter.sort(1),
+            Err(TopsortError::CyclicDependency(vec![2, 3, 2]))
+        );
+        assert_eq!(sorter.sort(4), Err(TopsortError::TargetNotFound(5)));
+        assert_eq!(sorter.sort(7), Err(TopsortError::TargetNotFound(7)));
+        assert_eq!(sorter.sort(6), Ok(vec!["mouse"]));
+        assert_eq!(
+            sorter.sort(2),
+            Err(TopsortError::CyclicDependency(vec![2, 3, 2]))
+        );
+
+        let mut out = Vec::new();
+        sorter.sort_cb(6, &mut |node| out.push(node.id)).unwrap();
+        assert_eq!(out, vec![6]);
+    }
+
+    #[test]
+    fn sorter_reports_progress() {
+        let domain = [
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![], ()),
+            Node::new(3, vec![2], ()),
+            Node::new(4, vec![], ()),
+        ];
+        let mut reports = Vec::new();
+        let mut sorter = Sorter::new(&domain)
+            .unwrap()
+            .on_progress(2, |visited, total| reports.push((visited, total)));
+        sorter.sort(1).unwrap();
+        sorter.sort(2).unwrap();
+        sorter.sort_all().unwrap();
+        drop(sorter);
+        assert_eq!(reports, vec![(2, 4), (3, 4), (1, 4), (2, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn sorter_cancelled() {
+        let domain = [
+            Node::new(1, vec![2], ()),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![], ()),
+        ];
+        let cancel = AtomicBool::new(true);
+        let mut sorter = Sorter::new(&domain).unwrap().cancel_on(&cancel);
+        assert_eq!(sorter.sort(1), Err(TopsortError::Cancelled));
+        assert_eq!(sorter.sort_all(), Err(TopsortError::Cancelled));
+
+        cancel.store(false, core::sync::atomic::Ordering::Relaxed);
+        assert_eq!(sorter.sort(1), Ok(vec![(), (), ()]));
+    }
+
+    #[test]
+    fn sorter_max_depth() {
+        let domain = [
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![], ()),
+            Node::new(3, vec![4], ()),
+            Node::new(4, vec![], ()),
+        ];
+        let mut sorter = Sorter::new(&domain).unwrap().max_depth(0);
+        assert_eq!(sorter.sort(2), Ok(vec![()]));
+        assert_eq!(sorter.sort(1), Err(TopsortError::DepthLimitExceeded(2)));
+
+        let mut sorter = Sorter::new(&domain).unwrap().max_depth(1);
+        assert_eq!(sorter.sort(3), Ok(vec![(), ()]));
+        assert_eq!(sorter.sort(1), Err(TopsortError::DepthLimitExceeded(4)));
+        assert_eq!(sorter.sort_all(), Err(TopsortError::DepthLimitExceeded(4)));
+    }
+
+    #[test]
+    fn sorter_duplicate_id() {
+        let domain = [Node::new(1, vec![], ()), Node::new(1, vec![], ())];
+        assert!(matches!(
+            Sorter::new(&domain),
+            Err(TopsortError::DuplicateId(1))
+        ));
+    }
+}
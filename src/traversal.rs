@@ -0,0 +1,354 @@
+use crate::bitset::BitSet;
+use crate::collections::HashMap;
+use crate::{HasDependencies, Node, TopsortError, Warning};
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// maps ids to positions in `domain`, failing on ids shared by more than one node
+pub(crate) fn build_index<T>(domain: &[T]) -> Result<HashMap<&T::Id, usize>, TopsortError<T::Id>>
+where
+    T: HasDependencies,
+{
+    let mut index = HashMap::with_capacity(domain.len());
+    for (position, node) in domain.iter().enumerate() {
+        if index.insert(node.id(), position).is_some() {
+            return Err(TopsortError::DuplicateId(node.id().clone()));
+        }
+    }
+    Ok(index)
+}
+
+/// maps ids to positions in `domain`, the first node wins on duplicates
+pub(crate) fn first_index<Id, Item>(domain: &[Node<Id, Item>]) -> HashMap<&Id, usize>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut index = HashMap::with_capacity(domain.len());
+    for (position, node) in domain.iter().enumerate() {
+        index.entry(&node.id).or_insert(position);
+    }
+    index
+}
+
+/// maps ids to positions in the domain of a traversal
+enum Lookup<'a, Id> {
+    /// built for a single traversal
+    Built(HashMap<&'a Id, usize>),
+    /// maintained by the owner of the domain, e.g. [`crate::Graph`]
+    Shared(&'a HashMap<Id, usize>),
+}
+
+impl<'a, Id> Lookup<'a, Id>
+where
+    Id: Eq + Hash,
+{
+    fn get(&self, id: &Id) -> Option<usize> {
+        match self {
+            Lookup::Built(index) => index.get(id).copied(),
+            Lookup::Shared(index) => index.get(id).copied(),
+        }
+    }
+}
+
+/// state of a single depth-first traversal over `domain`
+pub(crate) struct Traversal<'a, T>
+where
+    T: HasDependencies,
+{
+    domain: &'a [T],
+    index: Lookup<'a, T::Id>,
+    visited: BitSet,
+    on_path: BitSet,
+    /// explicit work stack of (node index, position of the next dependency to visit),
+    /// so deep graphs don't overflow the call stack
+    stack: Vec<(usize, usize)>,
+    /// node pushed by [`Traversal::start`] whose discovery wasn't reported yet
+    discovered: Option<usize>,
+    /// stops the traversal once set
+    cancel: Option<&'a AtomicBool>,
+    /// most edges allowed between the node a traversal starts from and any of its dependencies
+    max_depth: Option<usize>,
+    /// missing dependencies found so far, collected instead of failing when set
+    warnings: Option<Vec<Warning<T::Id>>>,
+}
+
+/// step of a depth-first traversal, nodes are identified by their index in the domain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Event {
+    /// the node is entered, before any of its dependencies
+    Discover(usize),
+    /// all dependencies of the node were visited
+    Finish(usize),
+    /// the first node depends on the second one, which is still being visited, closing a cycle
+    BackEdge(usize, usize),
+}
+
+impl<'a, T> Traversal<'a, T>
+where
+    T: HasDependencies,
+{
+    /// fails if ids in `domain` aren't unique
+    pub(crate) fn new(domain: &'a [T]) -> Result<Self, TopsortError<T::Id>> {
+        Ok(Self {
+            domain,
+            index: Lookup::Built(build_index(domain)?),
+            visited: BitSet::new(domain.len()),
+            on_path: BitSet::new(domain.len()),
+            stack: Vec::new(),
+            discovered: None,
+            cancel: None,
+            max_depth: None,
+            warnings: None,
+        })
+    }
+
+    /// uses an existing `index` instead of building one, it has to match `domain`
+    pub(crate) fn with_index(domain: &'a [T], index: &'a HashMap<T::Id, usize>) -> Self {
+        Self {
+            domain,
+            index: Lookup::Shared(index),
+            visited: BitSet::new(domain.len()),
+            on_path: BitSet::new(domain.len()),
+            stack: Vec::new(),
+            discovered: None,
+            cancel: None,
+            max_depth: None,
+            warnings: None,
+        }
+    }
+
+    /// makes the traversal fail with [`TopsortError::Cancelled`] once `flag` is set
+    pub(crate) fn cancel_on(&mut self, flag: &'a AtomicBool) {
+        self.cancel = Some(flag);
+    }
+
+    /// makes the traversal fail with [`TopsortError::DepthLimitExceeded`] on dependencies more than
+    /// `max_depth` edges away from the node it started from
+    pub(crate) fn limit_depth(&mut self, max_depth: usize) {
+        self.max_depth = Some(max_depth);
+    }
+
+    /// treats missing dependencies as nodes without dependencies of their own, recording them as
+    /// warnings instead of failing the traversal
+    pub(crate) fn collect_warnings(&mut self) {
+        self.warnings.get_or_insert_with(Vec::new);
+    }
+
+    /// returns warnings collected so far, leaving the traversal collecting new ones
+    pub(crate) fn take_warnings(&mut self) -> Vec<Warning<T::Id>> {
+        self.warnings
+            .as_mut()
+            .map(core::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// treats the node at `index` as already visited, so neither it nor its dependencies are visited
+    /// through it
+    pub(crate) fn skip(&mut self, index: usize) {
+        self.visited.insert(index);
+    }
+
+    /// positions of nodes finished by the traversal so far
+    pub(crate) fn visited(&self) -> &BitSet {
+        &self.visited
+    }
+
+    pub(crate) fn find_index(&self, target: &T::Id) -> Result<usize, TopsortError<T::Id>> {
+        match self.index.get(target) {
+            Some(index) => Ok(index),
+            None => Err(TopsortError::TargetNotFound(target.clone())),
+        }
+    }
+
+    /// same as [`Traversal::find_index`], naming `target` by any borrowed form of the id type
+    pub(crate) fn find_index_borrowed<Q>(&self, target: &Q) -> Result<usize, TopsortError<T::Id>>
+    where
+        T::Id: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = T::Id> + ?Sized,
+    {
+        let found = match &self.index {
+            // keys of a built index only borrow as the id type itself, scanning costs no more than
+            // building the index did and doesn't allocate an owned id
+            Lookup::Built(_) => self
+                .domain
+                .iter()
+                .position(|node| node.id().borrow() == target),
+            Lookup::Shared(index) => index.get(target).copied(),
+        };
+        match found {
+            Some(index) => Ok(index),
+            None => Err(TopsortError::TargetNotFound(target.to_owned())),
+        }
+    }
+
+    /// indices of dependencies of the node at `index` present in the domain, optional ones missing from
+    /// it are skipped
+    pub(crate) fn dep_indices(
+        &self,
+        index: usize,
+    ) -> impl Iterator<Item = Result<usize, TopsortError<T::Id>>> + '_ {
+        let node = &self.domain[index];
+        node.deps()
+            .iter()
+            .filter_map(move |dep| match self.index.get(dep) {
+                Some(dep_index) => Some(Ok(dep_index)),
+                None if node.is_optional(dep) => None,
+                None => Some(Err(TopsortError::TargetNotFound(dep.clone()))),
+            })
+    }
+
+    /// calls `cb` with indices of nodes in topological order, ending on the node at `index`
+    pub(crate) fn visit<F>(&mut self, index: usize, cb: &mut F) -> Result<(), TopsortError<T::Id>>
+    where
+        F: FnMut(usize),
+    {
+        self.start(index);
+        while let Some(finished) = self.next_finished() {
+            cb(finished?);
+        }
+
+        Ok(())
+    }
+
+    /// schedules visiting the node at `index` and its dependencies, unless it was already visited
+    pub(crate) fn start(&mut self, index: usize) {
+        if self.visited.contains(index) || self.on_path.contains(index) {
+            return;
+        }
+
+        self.on_path.insert(index);
+        self.stack.push((index, 0));
+        self.discovered = Some(index);
+    }
+
+    /// advances the traversal until the next node has all of its dependencies visited, returning its index
+    pub(crate) fn next_finished(&mut self) -> Option<Result<usize, TopsortError<T::Id>>> {
+        loop {
+            match self.next_event()? {
+                Ok(Event::Discover(_)) => {}
+                Ok(Event::Finish(index)) => return Some(Ok(index)),
+                // detect cyclic dependencies
+                Ok(Event::BackEdge(from, to)) if from == to => {
+                    return Some(Err(TopsortError::SelfDependency(
+                        self.domain[to].id().clone(),
+                    )))
+                }
+                Ok(Event::BackEdge(_, to)) => {
+                    return Some(Err(TopsortError::CyclicDependency(self.cycle(to))))
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    /// advances the traversal to the next event, back edges are skipped after being reported
+    pub(crate) fn next_event(&mut self) -> Option<Result<Event, TopsortError<T::Id>>> {
+        if let Some(index) = self.discovered.take() {
+            return Some(Ok(Event::Discover(index)));
+        }
+
+        while let Some((index, next_dep)) = self.stack.last_mut() {
+            let index = *index;
+            if let Some(flag) = self.cancel {
+                if flag.load(Ordering::Relaxed) {
+                    return Some(Err(TopsortError::Cancelled));
+                }
+            }
+
+            match self.domain[index].deps().get(*next_dep) {
+                Some(dep) => {
+                    *next_dep += 1;
+
+                    let dep_index = match self.index.get(dep) {
+                        Some(dep_index) => dep_index,
+                        None => {
+                            let node = &self.domain[index];
+                            let optional = node.is_optional(dep);
+                            match self.warnings.as_mut() {
+                                Some(warnings) if optional => {
+                                    warnings.push(Warning::MissingOptionalDependency(
+                                        node.id().clone(),
+                                        dep.clone(),
+                                    ))
+                                }
+                                Some(warnings) => warnings.push(Warning::MissingDependency(
+                                    node.id().clone(),
+                                    dep.clone(),
+                                )),
+                                None if optional => {}
+                                None => {
+                                    return Some(Err(TopsortError::TargetNotFound(dep.clone())))
+                                }
+                            }
+                            continue;
+                        }
+                    };
+                    if self.visited.contains(dep_index) {
+                        continue;
+                    }
+                    if self.on_path.contains(dep_index) {
+                        return Some(Ok(Event::BackEdge(index, dep_index)));
+                    }
+                    if self
+                        .max_depth
+                        .is_some_and(|max_depth| self.stack.len() > max_depth)
+                    {
+                        return Some(Err(TopsortError::DepthLimitExceeded(dep.clone())));
+                    }
+
+                    self.on_path.insert(dep_index);
+                    self.stack.push((dep_index, 0));
+                    return Some(Ok(Event::Discover(dep_index)));
+                }
+                None => {
+                    // all dependencies visited
+                    self.stack.pop();
+                    self.on_path.remove(index);
+                    self.visited.insert(index);
+                    return Some(Ok(Event::Finish(index)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// forgets nodes at `visited` indices, and any left on the stack by an error, so the traversal
+    /// can start over without reallocating
+    pub(crate) fn reset(&mut self, visited: &[usize]) {
+        self.discovered = None;
+        for (index, _) in self.stack.drain(..) {
+            self.on_path.remove(index);
+        }
+        for &index in visited {
+            self.visited.remove(index);
+        }
+    }
+
+    /// ids of nodes on the path from the node the traversal started from to the one visited last,
+    /// left after an error until [`Traversal::reset`]
+    pub(crate) fn path(&self) -> Vec<T::Id> {
+        self.stack
+            .iter()
+            .map(|(on_stack, _)| self.domain[*on_stack].id().clone())
+            .collect()
+    }
+
+    /// collects ids on the `stack` starting from the node at `index`, closing the cycle on it
+    fn cycle(&self, index: usize) -> Vec<T::Id> {
+        let start = self
+            .stack
+            .iter()
+            .position(|(on_stack, _)| *on_stack == index)
+            .unwrap_or(0);
+
+        self.stack[start..]
+            .iter()
+            .map(|(on_stack, _)| self.domain[*on_stack].id().clone())
+            .chain(core::iter::once(self.domain[index].id().clone()))
+            .collect()
+    }
+}
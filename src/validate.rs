@@ -0,0 +1,275 @@
+use crate::collections::{HashMap, HashSet};
+use crate::traversal::build_index;
+use crate::{HasDependencies, Node, TopsortError};
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// checks that ids in `domain` are unique and all dependencies are present, reporting every missing
+/// dependency at once as [`TopsortError::MissingDependencies`]
+///
+/// doesn't look for cycles
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let result = validate(&[
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///     ]);
+///     assert_eq!(
+///         result,
+///         Err(TopsortError::MissingDependencies(vec![
+///             ("wooden pickaxe", "sticks"),
+///             ("planks", "wood"),
+///         ]))
+///     );
+/// ```
+pub fn validate<Id, Item>(domain: &[Node<Id, Item>]) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let index = &build_index(domain)?;
+    let missing: Vec<(Id, Id)> = domain
+        .iter()
+        .flat_map(|node| {
+            node.deps
+                .iter()
+                .filter(move |dep| !index.contains_key(dep) && !node.is_optional(dep))
+                .map(move |dep| (node.id.clone(), dep.clone()))
+        })
+        .collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(TopsortError::MissingDependencies(missing))
+    }
+}
+
+/// returns pairs of ids of nodes from `domain` and their dependencies listed more than once, each pair
+/// once, in the order of `domain` and of dependencies
+///
+/// [`Node::new`] drops such repetitions, they're left by nodes built otherwise, e.g. deserialized or
+/// with `deps` changed directly; sorts visit a repeated dependency once, but exporters list its edge
+/// every time, [`Node::dedup_deps`] removes them
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let mut node = Node::new("sticks", vec!["planks"], "Sticks");
+///     node.deps.push("planks");
+///     let domain = [node, Node::new("planks", vec![], "Planks")];
+///     assert_eq!(duplicate_deps(&domain), vec![("sticks", "planks")]);
+/// ```
+pub fn duplicate_deps<Id, Item>(domain: &[Node<Id, Item>]) -> Vec<(Id, Id)>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut duplicates = Vec::new();
+    for node in domain.iter() {
+        let mut seen = HashSet::new();
+        let mut reported = HashSet::new();
+        for dep in node.deps.iter() {
+            if !seen.insert(dep) && reported.insert(dep) {
+                duplicates.push((node.id.clone(), dep.clone()));
+            }
+        }
+    }
+    duplicates
+}
+
+/// returns pairs of ids of nodes from `domain` and their optional dependencies missing from it, which
+/// sorts skip silently
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks"], "Pickaxe").with_optional_deps(vec!["gloves"]),
+///         Node::new("planks", vec![], "Planks").with_optional_deps(vec!["saw"]),
+///     ];
+///     assert_eq!(
+///         missing_optional_deps(&domain),
+///         vec![("wooden pickaxe", "gloves"), ("planks", "saw")]
+///     );
+/// ```
+pub fn missing_optional_deps<Id, Item>(domain: &[Node<Id, Item>]) -> Vec<(Id, Id)>
+where
+    Id: Clone + Eq + Hash,
+{
+    let ids: HashSet<&Id> = domain.iter().map(|node| &node.id).collect();
+    domain
+        .iter()
+        .flat_map(|node| {
+            node.optional_deps
+                .iter()
+                .filter(|dep| !ids.contains(dep))
+                .map(move |dep| (node.id.clone(), dep.clone()))
+        })
+        .collect()
+}
+
+/// checks that `order` lists ids of nodes from `domain` so that every node comes after all of its
+/// dependencies, failing on the first node listed before one of them with
+/// [`TopsortError::UnorderedDependency`]
+///
+/// `order` doesn't have to cover the whole domain, but dependencies of the nodes it lists have to be in it
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     let domain = [
+///         Node::new("wooden pickaxe", vec!["planks", "sticks"], "Pickaxe"),
+///         Node::new("planks", vec!["wood"], "Planks"),
+///         Node::new("sticks", vec!["planks"], "Sticks"),
+///         Node::new("wood", vec![], "Wood"),
+///     ];
+///     assert_eq!(verify_order(&domain, &["wood", "planks", "sticks"]), Ok(()));
+///     assert_eq!(
+///         verify_order(&domain, &["wood", "sticks", "planks"]),
+///         Err(TopsortError::UnorderedDependency("sticks", "planks"))
+///     );
+/// ```
+pub fn verify_order<Id, Item>(
+    domain: &[Node<Id, Item>],
+    order: &[Id],
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+{
+    let index = build_index(domain)?;
+    let mut positions: HashMap<&Id, usize> = HashMap::with_capacity(order.len());
+    for (position, id) in order.iter().enumerate() {
+        if positions.insert(id, position).is_some() {
+            return Err(TopsortError::DuplicateId(id.clone()));
+        }
+    }
+
+    for (position, id) in order.iter().enumerate() {
+        let node = match index.get(id) {
+            Some(&node) => &domain[node],
+            None => return Err(TopsortError::TargetNotFound(id.clone())),
+        };
+        for dep in node.deps.iter() {
+            if !index.contains_key(dep) {
+                if node.is_optional(dep) {
+                    continue;
+                }
+                return Err(TopsortError::TargetNotFound(dep.clone()));
+            }
+            if !matches!(positions.get(dep), Some(&dep_position) if dep_position < position) {
+                return Err(TopsortError::UnorderedDependency(id.clone(), dep.clone()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_deps_works() {
+        let mut hello = Node::new(1, vec![2, 3], "hello");
+        hello.deps.extend([2, 3, 2]);
+        let mut world = Node::new(2, vec![3], "world");
+        world.deps.push(4);
+        let domain = [hello, world, Node::new(3, vec![3, 3], "cat")];
+        assert_eq!(duplicate_deps(&domain), vec![(1, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn validate_works() {
+        let result = validate(&[
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![1], "cat"),
+        ]);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn validate_collects_missing() {
+        let result = validate(&[
+            Node::new(1, vec![2, 4, 5], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![4], "cat"),
+        ]);
+        assert_eq!(
+            result,
+            Err(TopsortError::MissingDependencies(vec![
+                (1, 4),
+                (1, 5),
+                (3, 4)
+            ]))
+        );
+    }
+
+    #[test]
+    fn validate_duplicate_id() {
+        let result = validate(&[Node::new(1, vec![], "hello"), Node::new(1, vec![], "world")]);
+        assert_eq!(result, Err(TopsortError::DuplicateId(1)));
+    }
+
+    #[test]
+    fn verify_order_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], "hello"),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![2], "cat"),
+            Node::new(4, vec![], "dog"),
+        ];
+        assert_eq!(verify_order(&domain, &[2, 3, 1]), Ok(()));
+        assert_eq!(verify_order(&domain, &[4, 2]), Ok(()));
+        assert_eq!(
+            verify_order(&domain, &[3, 2, 1]),
+            Err(TopsortError::UnorderedDependency(3, 2))
+        );
+        assert_eq!(
+            verify_order(&domain, &[2, 1]),
+            Err(TopsortError::UnorderedDependency(1, 3))
+        );
+    }
+
+    #[test]
+    fn verify_order_errors() {
+        let domain = [
+            Node::new(1, vec![], "hello"),
+            Node::new(2, vec![3], "world"),
+        ];
+        assert_eq!(
+            verify_order(&domain, &[1, 5]),
+            Err(TopsortError::TargetNotFound(5))
+        );
+        assert_eq!(
+            verify_order(&domain, &[1, 2]),
+            Err(TopsortError::TargetNotFound(3))
+        );
+        assert_eq!(
+            verify_order(&domain, &[1, 1]),
+            Err(TopsortError::DuplicateId(1))
+        );
+    }
+
+    #[test]
+    fn optional_deps_may_be_missing() {
+        let domain = [
+            Node::new(1, vec![2], "hello").with_optional_deps(vec![3, 4]),
+            Node::new(2, vec![], "world"),
+            Node::new(3, vec![], "cat"),
+        ];
+        assert_eq!(validate(&domain), Ok(()));
+        assert_eq!(verify_order(&domain, &[2, 3, 1]), Ok(()));
+        assert_eq!(
+            verify_order(&domain, &[2, 1, 3]),
+            Err(TopsortError::UnorderedDependency(1, 3))
+        );
+        assert_eq!(missing_optional_deps(&domain), vec![(1, 4)]);
+    }
+}
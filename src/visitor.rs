@@ -0,0 +1,247 @@
+use crate::traversal::{Event, Traversal};
+use crate::{Edge, Node, TopsortError};
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// receives events of a depth-first traversal, see [`sort_visit`]
+///
+/// every method does nothing by default, so implementors only override the events they need
+pub trait Visitor<Id, Item>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// called when `node` is entered, before any of its dependencies
+    fn discover(&mut self, _node: &Node<Id, Item>) {}
+
+    /// called once all dependencies of `node` were visited, nodes finish in topological order
+    fn finish(&mut self, _node: &Node<Id, Item>) {}
+
+    /// called when the traversal follows the edge from `from` to its dependency `to`, right before `to`
+    /// is discovered, with data attached to the edge, see [`Node::edge`]
+    fn tree_edge(&mut self, _from: &Node<Id, Item>, _to: &Node<Id, Item>, _edge: Option<&Edge>) {}
+
+    /// called when `from` depends on `to` which is still being visited, meaning the edge closes a cycle
+    ///
+    /// `from` and `to` are the same node for a self-loop
+    fn back_edge(&mut self, _from: &Node<Id, Item>, _to: &Node<Id, Item>) {}
+}
+
+/// walks the dependency tree of the node with id of `target` depth-first, reporting every step to `visitor`
+///
+/// unlike [`crate::sort_cb`] cycles don't stop the traversal, the edge closing each of them is reported
+/// to [`Visitor::back_edge`] and skipped
+///
+/// # Examples:
+/// ```
+///     use szyk::*;
+///
+///     struct Tree(Vec<String>, usize);
+///
+///     impl Visitor<&'static str, ()> for Tree {
+///         fn discover(&mut self, node: &Node<&'static str, ()>) {
+///             self.0.push(format!("{}{}", "  ".repeat(self.1), node.id));
+///             self.1 += 1;
+///         }
+///
+///         fn finish(&mut self, _: &Node<&'static str, ()>) {
+///             self.1 -= 1;
+///         }
+///
+///         fn back_edge(&mut self, _from: &Node<&'static str, ()>, to: &Node<&'static str, ()>) {
+///             self.0.push(format!("{}{} (cycle)", "  ".repeat(self.1), to.id));
+///         }
+///     }
+///
+///     let domain = [
+///         Node::new("cat", vec!["dog", "mouse"], ()),
+///         Node::new("dog", vec!["cat"], ()),
+///         Node::new("mouse", vec![], ()),
+///     ];
+///     let mut tree = Tree(Vec::new(), 0);
+///     sort_visit(&domain, "cat", &mut tree).unwrap();
+///     assert_eq!(tree.0, vec!["cat", "  dog", "    cat (cycle)", "  mouse"]);
+/// ```
+pub fn sort_visit<Id, Item, V>(
+    domain: &[Node<Id, Item>],
+    target: Id,
+    visitor: &mut V,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    V: Visitor<Id, Item>,
+{
+    let mut traversal = Traversal::new(domain)?;
+    let index = traversal.find_index(&target)?;
+    walk(domain, &mut traversal, index, visitor)
+}
+
+/// walks every node of `domain` depth-first, reporting every step to `visitor`, see [`sort_visit`]
+///
+/// nodes that don't depend on each other are visited in the order they appear in `domain`
+pub fn sort_all_visit<Id, Item, V>(
+    domain: &[Node<Id, Item>],
+    visitor: &mut V,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    V: Visitor<Id, Item>,
+{
+    let mut traversal = Traversal::new(domain)?;
+    for index in 0..domain.len() {
+        walk(domain, &mut traversal, index, visitor)?;
+    }
+
+    Ok(())
+}
+
+fn walk<Id, Item, V>(
+    domain: &[Node<Id, Item>],
+    traversal: &mut Traversal<'_, Node<Id, Item>>,
+    index: usize,
+    visitor: &mut V,
+) -> Result<(), TopsortError<Id>>
+where
+    Id: Clone + Eq + Hash,
+    V: Visitor<Id, Item>,
+{
+    // nodes discovered and not finished yet, the last one is the parent of the next discovered node
+    let mut path: Vec<usize> = Vec::new();
+    traversal.start(index);
+    while let Some(event) = traversal.next_event() {
+        match event? {
+            Event::Discover(index) => {
+                let node = &domain[index];
+                if let Some(&parent) = path.last() {
+                    let parent = &domain[parent];
+                    visitor.tree_edge(parent, node, parent.edge(&node.id));
+                }
+                path.push(index);
+                visitor.discover(node);
+            }
+            Event::Finish(index) => {
+                path.pop();
+                visitor.finish(&domain[index]);
+            }
+            Event::BackEdge(from, to) => visitor.back_edge(&domain[from], &domain[to]),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+
+    #[derive(Default)]
+    struct Recorder(Vec<(&'static str, i32, i32)>);
+
+    impl Visitor<i32, ()> for Recorder {
+        fn discover(&mut self, node: &Node<i32, ()>) {
+            self.0.push(("discover", node.id, node.id));
+        }
+
+        fn finish(&mut self, node: &Node<i32, ()>) {
+            self.0.push(("finish", node.id, node.id));
+        }
+
+        fn back_edge(&mut self, from: &Node<i32, ()>, to: &Node<i32, ()>) {
+            self.0.push(("back_edge", from.id, to.id));
+        }
+    }
+
+    #[test]
+    fn sort_visit_works() {
+        let domain = [
+            Node::new(1, vec![2, 3], ()),
+            Node::new(2, vec![], ()),
+            Node::new(3, vec![2, 3], ()),
+        ];
+        let mut recorder = Recorder::default();
+        sort_visit(&domain, 1, &mut recorder).unwrap();
+        assert_eq!(
+            recorder.0,
+            vec![
+                ("discover", 1, 1),
+                ("discover", 2, 2),
+                ("finish", 2, 2),
+                ("discover", 3, 3),
+                ("back_edge", 3, 3),
+                ("finish", 3, 3),
+                ("finish", 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn tree_edges_carry_data() {
+        struct Edges(Vec<(i32, i32, Option<String>)>);
+
+        impl Visitor<i32, ()> for Edges {
+            fn tree_edge(&mut self, from: &Node<i32, ()>, to: &Node<i32, ()>, edge: Option<&Edge>) {
+                let kind = edge.and_then(|edge| edge.kind.clone());
+                self.0.push((from.id, to.id, kind));
+            }
+        }
+
+        let domain = [
+            Node::new(1, vec![2], ()).with_edge(3, Edge::new().with_kind("runtime")),
+            Node::new(2, vec![3], ()),
+            Node::new(3, vec![], ()),
+        ];
+        let mut edges = Edges(Vec::new());
+        sort_visit(&domain, 1, &mut edges).unwrap();
+        assert_eq!(edges.0, vec![(1, 2, None), (2, 3, None)]);
+
+        let domain = [
+            Node::new(1, vec![], ()).with_edge(3, Edge::new().with_kind("runtime")),
+            Node::new(3, vec![], ()),
+        ];
+        let mut edges = Edges(Vec::new());
+        sort_all_visit(&domain, &mut edges).unwrap();
+        assert_eq!(edges.0, vec![(1, 3, Some(String::from("runtime")))]);
+    }
+
+    #[test]
+    fn sort_all_visit_reports_every_cycle() {
+        let domain = [
+            Node::new(1, vec![2], ()),
+            Node::new(2, vec![1], ()),
+            Node::new(3, vec![4], ()),
+            Node::new(4, vec![3, 1], ()),
+        ];
+        let mut recorder = Recorder::default();
+        sort_all_visit(&domain, &mut recorder).unwrap();
+        let back_edges: Vec<_> = recorder
+            .0
+            .iter()
+            .filter(|(event, _, _)| *event == "back_edge")
+            .map(|&(_, from, to)| (from, to))
+            .collect();
+        assert_eq!(back_edges, vec![(2, 1), (4, 3)]);
+
+        let finished: Vec<_> = recorder
+            .0
+            .iter()
+            .filter(|(event, _, _)| *event == "finish")
+            .map(|&(_, id, _)| id)
+            .collect();
+        assert_eq!(finished, vec![2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn sort_visit_errors() {
+        let domain = [Node::new(1, vec![2], ())];
+        let mut recorder = Recorder::default();
+        assert_eq!(
+            sort_visit(&domain, 1, &mut recorder),
+            Err(TopsortError::TargetNotFound(2))
+        );
+        assert_eq!(
+            sort_visit(&domain, 3, &mut recorder),
+            Err(TopsortError::TargetNotFound(3))
+        );
+    }
+}
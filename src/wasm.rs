@@ -0,0 +1,135 @@
+//! JavaScript bindings over a [`Graph`] of string ids, built with `wasm-bindgen`
+//!
+//! errors are thrown as JS `Error`s with the message of the [`TopsortError`], results holding more
+//! than a list of ids are returned as JSON strings
+//!
+//! # Examples
+//! ```js
+//!     import { SzykGraph } from "szyk";
+//!
+//!     const graph = new SzykGraph();
+//!     graph.addNode("cat");
+//!     graph.addNode("dog");
+//!     graph.addEdge("cat", "dog");
+//!     graph.sort("cat"); // ["dog", "cat"]
+//!
+//!     const loaded = SzykGraph.fromJson('{"nodes":[{"id":"a"}],"links":[]}');
+//! ```
+
+use crate::{find_cycles, json, Graph, Node, TopsortError};
+use wasm_bindgen::prelude::*;
+
+/// graph of string ids, exported to JS as `SzykGraph`
+#[wasm_bindgen(js_name = SzykGraph)]
+#[derive(Debug, Default)]
+pub struct WasmGraph {
+    graph: Graph<String, ()>,
+}
+
+#[wasm_bindgen(js_class = SzykGraph)]
+impl WasmGraph {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// parses a node-link JSON document, see [`json::parse`]
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(input: &str) -> Result<WasmGraph, JsError> {
+        Ok(Self {
+            graph: json::parse(input)?,
+        })
+    }
+
+    /// serializes the graph as a node-link JSON document, see [`json::to_json`]
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(json::to_json(self.graph.nodes())?)
+    }
+
+    /// adds a node without dependencies, does nothing if it's already in the graph
+    #[wasm_bindgen(js_name = addNode)]
+    pub fn add_node(&mut self, id: String) {
+        if !self.graph.contains(&id) {
+            self.graph.add_node(id, ());
+        }
+    }
+
+    /// makes `from` depend on `to`, see [`Graph::add_edge`]
+    #[wasm_bindgen(js_name = addEdge)]
+    pub fn add_edge(&mut self, from: String, to: String) -> Result<(), JsError> {
+        self.graph.add_edge(from, to).map_err(error)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.graph.contains(&String::from(id))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// ids in topological order, ending on `target`
+    pub fn sort(&self, target: String) -> Result<Vec<String>, JsError> {
+        let mut ids = Vec::new();
+        self.graph
+            .sort_cb(target, &mut |node: &Node<String, ()>| {
+                ids.push(node.id.clone())
+            })
+            .map_err(error)?;
+        Ok(ids)
+    }
+
+    /// ids of every node in topological order, see [`crate::sort_all`]
+    #[wasm_bindgen(js_name = sortAll)]
+    pub fn sort_all(&self) -> Result<Vec<String>, JsError> {
+        let mut ids = Vec::new();
+        crate::sort_all_cb(self.graph.nodes(), &mut |node: &Node<String, ()>| {
+            ids.push(node.id.clone())
+        })
+        .map_err(error)?;
+        Ok(ids)
+    }
+
+    /// every elementary cycle as a JSON array of arrays of ids, see [`find_cycles`]
+    #[wasm_bindgen(js_name = findCycles)]
+    pub fn find_cycles(&self) -> Result<String, JsError> {
+        let cycles = find_cycles(self.graph.nodes()).map_err(error)?;
+        Ok(serde_json::to_string(&cycles)?)
+    }
+}
+
+fn error(err: TopsortError<String>) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_works() {
+        let mut graph = WasmGraph::from_json(
+            r#"{"nodes":[{"id":"cat"},{"id":"dog"}],"links":[{"source":"cat","target":"dog"}]}"#,
+        )
+        .unwrap();
+        graph.add_node(String::from("mouse"));
+        graph
+            .add_edge(String::from("dog"), String::from("mouse"))
+            .unwrap();
+
+        assert_eq!(graph.length(), 3);
+        assert!(graph.contains("mouse"));
+        assert_eq!(
+            graph.sort(String::from("cat")).unwrap(),
+            vec!["mouse", "dog", "cat"]
+        );
+        assert_eq!(graph.sort_all().unwrap(), vec!["mouse", "dog", "cat"]);
+        assert_eq!(graph.find_cycles().unwrap(), "[]");
+        assert_eq!(
+            graph.to_json().unwrap(),
+            r#"{"nodes":[{"id":"cat"},{"id":"dog"},{"id":"mouse"}],"links":[{"source":"cat","target":"dog"},{"source":"dog","target":"mouse"}]}"#
+        );
+    }
+}
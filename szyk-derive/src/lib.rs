@@ -0,0 +1,164 @@
+//! `#[derive(TopoNode)]` implementing `szyk::HasDependencies` for structs, enable it with the `derive`
+//! feature of `szyk`
+//!
+//! the id is the field marked `#[topo(id)]`, or the one named `id`, and dependencies are the field
+//! marked `#[topo(deps)]`, or the one named `deps`, which has to dereference to a slice of ids;
+//! a field marked `#[topo(optional)]` lists dependencies which may be missing from the domain, they
+//! have to be listed in the dependencies too
+//!
+//! # Examples
+//! ```ignore
+//!     use szyk::*;
+//!
+//!     #[derive(TopoNode)]
+//!     struct Migration {
+//!         #[topo(id)]
+//!         name: &'static str,
+//!         #[topo(deps)]
+//!         after: Vec<&'static str>,
+//!     }
+//!
+//!     let migrations = [
+//!         Migration { name: "add index", after: vec!["create table"] },
+//!         Migration { name: "create table", after: vec![] },
+//!     ];
+//!     let sorted = sort_items(&migrations, "add index").unwrap();
+//!     assert_eq!(sorted[0].name, "create table");
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields};
+
+#[proc_macro_derive(TopoNode, attributes(topo))]
+pub fn derive_topo_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// role of a field given by its `#[topo(..)]` attribute
+#[derive(Clone, Copy)]
+enum Role {
+    Id,
+    Deps,
+    Optional,
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "TopoNode can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "TopoNode can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut id = None;
+    let mut deps = None;
+    let mut optional = None;
+    for field in fields.iter() {
+        for role in roles(field)? {
+            let slot = match role {
+                Role::Id => &mut id,
+                Role::Deps => &mut deps,
+                Role::Optional => &mut optional,
+            };
+            if slot.is_some() {
+                return Err(Error::new_spanned(field, "duplicate `topo` attribute"));
+            }
+            *slot = Some(field);
+        }
+    }
+
+    let named = |name: &str| {
+        fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+    };
+    let id = match id.or_else(|| named("id")) {
+        Some(field) => field,
+        None => {
+            return Err(Error::new(
+                Span::call_site(),
+                "TopoNode needs a field named `id` or marked `#[topo(id)]`",
+            ))
+        }
+    };
+    let deps = match deps.or_else(|| named("deps")) {
+        Some(field) => field,
+        None => {
+            return Err(Error::new(
+                Span::call_site(),
+                "TopoNode needs a field named `deps` or marked `#[topo(deps)]`",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let id_ty = &id.ty;
+    let id_ident = &id.ident;
+    let deps_ident = &deps.ident;
+    let is_optional = optional.map(|field| {
+        let ident = &field.ident;
+        quote! {
+            fn is_optional(&self, dep: &Self::Id) -> bool {
+                self.#ident.contains(dep)
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::szyk::HasDependencies for #name #ty_generics #where_clause {
+            type Id = #id_ty;
+
+            fn id(&self) -> &Self::Id {
+                &self.#id_ident
+            }
+
+            fn deps(&self) -> &[Self::Id] {
+                &self.#deps_ident
+            }
+
+            #is_optional
+        }
+    })
+}
+
+/// roles given to `field` by its `#[topo(..)]` attributes
+fn roles(field: &Field) -> Result<Vec<Role>, Error> {
+    let mut roles = Vec::new();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("topo"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let role = if meta.path.is_ident("id") {
+                Role::Id
+            } else if meta.path.is_ident("deps") {
+                Role::Deps
+            } else if meta.path.is_ident("optional") {
+                Role::Optional
+            } else {
+                return Err(meta.error("expected `id`, `deps` or `optional`"));
+            };
+            roles.push(role);
+            Ok(())
+        })?;
+    }
+    Ok(roles)
+}